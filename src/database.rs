@@ -0,0 +1,63 @@
+use std::error::Error;
+
+use crate::sql::engine::{error::ExecutionError, result::ResultSet, Executor};
+use crate::sql::function::{AggregateUdf, FunctionRegistry, ScalarFunction, ScalarUdf, UserAggregate};
+use crate::sql::optimizer::optimizer::{Optimizer, PhysicalPlan};
+use crate::sql::parser::{ast::Statement, Parser};
+use crate::sql::planner::{
+  error::PlannerError,
+  plan::{Plan, Planner},
+};
+use crate::storage::manager::BufferPool;
+
+// INFO: Handle of an embedded database, the functions registered on it can be called from every statement it runs
+pub struct Database {
+  buffer_pool: BufferPool,
+  functions: FunctionRegistry,
+}
+
+impl Database {
+  pub fn new() -> Self {
+    Database { buffer_pool: BufferPool::new(), functions: FunctionRegistry::new() }
+  }
+
+  // INFO: Fails when a function, aggregate or window function with the same name exists already
+  pub fn register_scalar(&mut self, function: impl ScalarUdf) -> Result<(), PlannerError> {
+    self.functions.register(ScalarFunction::from_udf(function))
+  }
+
+  pub fn register_aggregate(&mut self, aggregate: impl AggregateUdf) -> Result<(), PlannerError> {
+    self.functions.register_aggregate(UserAggregate::new(aggregate))
+  }
+
+  // INFO: Runs the statements of the script in order and stops at the first one that fails
+  pub fn execute(&mut self, script: &str) -> Result<Vec<ResultSet>, Box<dyn Error>> {
+    let statements = Parser::new(script).parse_script()?;
+    statements.into_iter().map(|statement| self.execute_statement(statement)).collect()
+  }
+
+  pub fn execute_statement(&mut self, statement: Statement) -> Result<ResultSet, Box<dyn Error>> {
+    let plan = self.plan(statement)?;
+    let physical_plan = self.optimize(plan)?;
+
+    Ok(self.execute_plan(physical_plan)?)
+  }
+
+  pub fn plan(&mut self, statement: Statement) -> Result<Plan, PlannerError> {
+    Planner::new(self.buffer_pool.get_catalog(), &self.functions).build(statement)
+  }
+
+  pub fn optimize(&mut self, plan: Plan) -> Result<PhysicalPlan, PlannerError> {
+    Optimizer::new(plan, self.buffer_pool.get_catalog()).optimize()
+  }
+
+  pub fn execute_plan(&mut self, plan: PhysicalPlan) -> Result<ResultSet, ExecutionError> {
+    Executor::new(plan, &mut self.buffer_pool).execute()
+  }
+}
+
+impl Default for Database {
+  fn default() -> Self {
+    Self::new()
+  }
+}
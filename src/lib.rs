@@ -0,0 +1,5 @@
+pub mod database;
+pub mod sql;
+pub mod storage;
+
+pub use database::Database;
@@ -1,14 +1,11 @@
-#![allow(unused)]
-pub mod sql;
-pub mod storage;
 // fn main() {
 //   // let input = "SELECT age, name FROM table WHERE adult = 'yes' and
-//   // year = '2012-01-22' and is_emigrant = true and name = null
+//   // year = '2012-01-22' and is_emigrant = true and name IS NULL
 //   // group by name, age
 //   // having age > 18
 //   // order by name asc, age desc
 //   // offset 6 limit 5";
-//   // let input = "DELETE FROM users WHERE age > 18 and name = 'John' and is_emigrant = true and name = null";
+//   // let input = "DELETE FROM users WHERE age > 18 and name = 'John' and is_emigrant = true and name IS NULL";
 
 //   // let input = "INSERT INTO users (name, age, is_emigrant) VALUES ('John', 25, true)";
 
@@ -28,15 +25,23 @@ pub mod storage;
 //   println!("{:?}", statement);
 // }
 
-
 // alter table ne radi majstore
 use std::io::{self, Write};
 
-use crate::sql::{
-  catalog, engine::Executor, planner::plan::{Node, Plan}
-};
+use db::{sql, Database};
 
 fn main() {
+  let mut database = Database::new();
+
+  // INFO: `db migration.sql` runs the script and exits instead of starting the prompt
+  if let Some(path) = std::env::args().nth(1) {
+    match std::fs::read_to_string(&path) {
+      Ok(script) => run(&script, &mut database),
+      Err(e) => println!("Could not read {}: {}", path, e),
+    }
+    return;
+  }
+
   let mut input = String::new();
 
   loop {
@@ -44,28 +49,51 @@ fn main() {
     io::stdout().flush().unwrap();
 
     input.clear();
-    io::stdin().read_line(&mut input).unwrap();
+    let read = io::stdin().read_line(&mut input).unwrap();
 
-    if input.trim() == "exit" {
+    if read == 0 || input.trim() == "exit" {
       break;
     }
 
-    let mut storage_manager = storage::manager::StorageManager::new();
-    let mut buffer_pool = storage::manager::BufferPool::new();
-    let mut catalog = buffer_pool.get_catalog();
-    println!("{:?}", catalog);
-
-    let mut parser = sql::parser::Parser::new(&input);
-    let statement = parser.parse();
+    run(&input, &mut database);
+  }
+}
 
-    let plan = sql::planner::plan::Planner::new().build(statement.unwrap());
+fn run(input: &str, database: &mut Database) {
+  let mut parser = sql::parser::Parser::new(input);
+  let statements = match parser.parse_script() {
+    Ok(statements) => statements,
+    Err(e) => {
+      println!("{}", e);
+      return;
+    }
+  };
+
+  for statement in statements {
+    let plan = match database.plan(statement) {
+      Ok(plan) => plan,
+      Err(e) => {
+        println!("{}", e);
+        return;
+      }
+    };
     println!("{:?}", plan);
 
-    let mut optimizer = sql::optimizer::optimizer::Optimizer::new(plan, catalog);
-    let physical_plan = optimizer.optimize();
+    let physical_plan = match database.optimize(plan) {
+      Ok(physical_plan) => physical_plan,
+      Err(e) => {
+        println!("{}", e);
+        return;
+      }
+    };
     println!("{:?}", physical_plan);
 
-    let mut executor = Executor::new(physical_plan, &mut buffer_pool);
-    executor.execute();
+    match database.execute_plan(physical_plan) {
+      Ok(result) => println!("{}", result),
+      Err(e) => {
+        println!("{}", e);
+        return;
+      }
+    }
   }
 }
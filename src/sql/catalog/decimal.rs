@@ -0,0 +1,363 @@
+use serde_derive::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Neg;
+use std::str::FromStr;
+
+// INFO: 10^38 still fits into an i128 so every value with up to 38 digits can be stored
+pub const DECIMAL_MAX_PRECISION: u32 = 38;
+
+// INFO: Digits a division keeps after the point when both operands have fewer
+const DIVISION_SCALE: u32 = 20;
+
+// INFO: Exact fixed point number, the value is `mantissa / 10^scale`. The scale is kept as it is so 1.50 stays 1.50,
+// the mantissa never has more than DECIMAL_MAX_PRECISION digits
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Decimal {
+  mantissa: i128,
+  scale: u32,
+}
+
+impl Decimal {
+  // INFO: None when the value has more digits than a decimal can hold
+  pub fn new(mantissa: i128, scale: u32) -> Option<Decimal> {
+    (scale <= DECIMAL_MAX_PRECISION && digits(mantissa) <= DECIMAL_MAX_PRECISION).then_some(Decimal { mantissa, scale })
+  }
+
+  // INFO: Goes through the shortest text of the float so 0.1 becomes exactly 0.1
+  pub fn from_f64(value: f64) -> Option<Decimal> {
+    match value.is_finite() {
+      true => value.to_string().parse().ok(),
+      false => None,
+    }
+  }
+
+  pub fn to_f64(self) -> f64 {
+    self.to_string().parse().unwrap_or(f64::NAN)
+  }
+
+  // INFO: None when the value has a fraction or doesn't fit an i64
+  pub fn to_i64(self) -> Option<i64> {
+    (self.mantissa % pow10(self.scale)? == 0).then(|| i64::try_from(self.mantissa / pow10(self.scale)?).ok()).flatten()
+  }
+
+  pub fn scale(self) -> u32 {
+    self.scale
+  }
+
+  // INFO: Number of digits of the value, the zeros of the scale included
+  pub fn precision(self) -> u32 {
+    digits(self.mantissa)
+  }
+
+  pub fn is_zero(self) -> bool {
+    self.mantissa == 0
+  }
+
+  // INFO: Changes the number of digits after the point, dropped digits are rounded half away from zero
+  pub fn rescale(self, scale: u32) -> Option<Decimal> {
+    match scale.cmp(&self.scale) {
+      Ordering::Equal => Some(self),
+      Ordering::Greater => Decimal::new(self.mantissa.checked_mul(pow10(scale - self.scale)?)?, scale),
+      Ordering::Less => Decimal::new(round_div(self.mantissa, pow10(self.scale - scale)?), scale),
+    }
+  }
+
+  // INFO: Negative digits round to tens, hundreds and so on
+  pub fn round(self, digits: i64) -> Option<Decimal> {
+    if digits >= 0 {
+      return self.rescale(digits.min(DECIMAL_MAX_PRECISION as i64) as u32);
+    }
+
+    let shift: u32 = digits.unsigned_abs().try_into().ok()?;
+    match (pow10(shift), shift.checked_add(self.scale).and_then(pow10)) {
+      (Some(factor), Some(divisor)) => Decimal::new(round_div(self.mantissa, divisor).checked_mul(factor)?, 0),
+      _ => Decimal::new(0, 0),
+    }
+  }
+
+  pub fn floor(self) -> Option<Decimal> {
+    Decimal::new(self.mantissa.div_euclid(pow10(self.scale)?), 0)
+  }
+
+  pub fn ceil(self) -> Option<Decimal> {
+    (-self).floor().map(Decimal::neg)
+  }
+
+  pub fn abs(self) -> Decimal {
+    Decimal { mantissa: self.mantissa.abs(), scale: self.scale }
+  }
+
+  pub fn checked_add(self, other: Decimal) -> Option<Decimal> {
+    let scale = self.scale.max(other.scale);
+    Decimal::new(self.rescale(scale)?.mantissa.checked_add(other.rescale(scale)?.mantissa)?, scale)
+  }
+
+  pub fn checked_sub(self, other: Decimal) -> Option<Decimal> {
+    self.checked_add(-other)
+  }
+
+  // INFO: The scale of the result is the sum of the scales, trailing digits are rounded away when the result doesn't fit
+  pub fn checked_mul(self, other: Decimal) -> Option<Decimal> {
+    let (mut left, mut right) = (self.normalize(), other.normalize());
+
+    loop {
+      let product = left.mantissa.checked_mul(right.mantissa);
+      if let Some(result) = product.and_then(|mantissa| Decimal::new(mantissa, left.scale + right.scale)) {
+        return Some(result.rescale(self.scale + other.scale).unwrap_or(result));
+      }
+
+      let operand = if left.scale >= right.scale { &mut left } else { &mut right };
+      if operand.scale == 0 {
+        return None;
+      }
+      *operand = operand.rescale(operand.scale - 1)?;
+    }
+  }
+
+  // INFO: Keeps at least DIVISION_SCALE digits after the point, fewer when the quotient is too large for them.
+  // Zeros past the scales of the operands are dropped. None when dividing by zero
+  pub fn checked_div(self, other: Decimal) -> Option<Decimal> {
+    if other.is_zero() {
+      return None;
+    }
+
+    let minimum = self.scale.max(other.scale);
+    let mut scale = minimum.max(DIVISION_SCALE);
+
+    loop {
+      let shift = (scale + other.scale).checked_sub(self.scale)?;
+      let quotient = pow10(shift)
+        .and_then(|factor| self.mantissa.checked_mul(factor))
+        .and_then(|dividend| Decimal::new(round_div(dividend, other.mantissa), scale));
+
+      match quotient {
+        Some(quotient) => return Some(quotient.trim(minimum)),
+        None if shift == 0 || scale == 0 => return None,
+        None => scale -= 1,
+      }
+    }
+  }
+
+  // INFO: The remainder has the sign of the dividend like the `%` of integers. None when dividing by zero
+  pub fn checked_rem(self, other: Decimal) -> Option<Decimal> {
+    let scale = self.scale.max(other.scale);
+    let (dividend, divisor) = (self.rescale(scale)?.mantissa, other.rescale(scale)?.mantissa);
+
+    Decimal::new(dividend.checked_rem(divisor)?, scale)
+  }
+
+  // INFO: Same value without the trailing zeros after the point
+  fn normalize(self) -> Decimal {
+    self.trim(0)
+  }
+
+  fn trim(mut self, minimum: u32) -> Decimal {
+    while self.scale > minimum && self.mantissa % 10 == 0 {
+      (self.mantissa, self.scale) = (self.mantissa / 10, self.scale - 1);
+    }
+
+    self
+  }
+}
+
+impl Neg for Decimal {
+  type Output = Decimal;
+
+  fn neg(self) -> Decimal {
+    Decimal { mantissa: -self.mantissa, scale: self.scale }
+  }
+}
+
+impl From<i64> for Decimal {
+  fn from(value: i64) -> Self {
+    Decimal { mantissa: value as i128, scale: 0 }
+  }
+}
+
+// INFO: Digits with an optional sign, point and exponent like `-12.50` or `1.5e3`
+impl FromStr for Decimal {
+  type Err = ();
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let (number, exponent) = match text.split_once(['e', 'E']) {
+      Some((number, exponent)) => (number, exponent.parse::<i32>().map_err(|_| ())?),
+      None => (text, 0),
+    };
+    let (negative, number) = match number.strip_prefix('-') {
+      Some(number) => (true, number),
+      None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+    if (whole.is_empty() && fraction.is_empty()) || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+      return Err(());
+    }
+
+    let digits = format!("{}{}", whole, fraction);
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > DECIMAL_MAX_PRECISION as usize {
+      return Err(());
+    }
+
+    let mantissa: i128 = if digits.is_empty() { 0 } else { digits.parse().map_err(|_| ())? };
+    let scale = fraction.len() as i64 - exponent as i64;
+    let decimal = match u32::try_from(scale) {
+      Ok(scale) => Decimal::new(mantissa, scale),
+      Err(_) => Decimal::new(mantissa, 0).and_then(|decimal| decimal.checked_mul(Decimal::new(pow10((-scale) as u32)?, 0)?)),
+    };
+
+    decimal.map(|decimal| if negative { -decimal } else { decimal }).ok_or(())
+  }
+}
+
+impl PartialEq for Decimal {
+  fn eq(&self, other: &Decimal) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+  fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+// INFO: Compares the values, 1.5 and 1.50 are equal. A mantissa that can't be brought to the other scale is the larger one
+impl Ord for Decimal {
+  fn cmp(&self, other: &Decimal) -> Ordering {
+    let scale = self.scale.max(other.scale);
+
+    match (self.rescale(scale), other.rescale(scale)) {
+      (Some(left), Some(right)) => left.mantissa.cmp(&right.mantissa),
+      (None, _) => 0.cmp(&self.mantissa).reverse(),
+      (_, None) => 0.cmp(&other.mantissa),
+    }
+  }
+}
+
+impl fmt::Display for Decimal {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let digits = format!("{:0>width$}", self.mantissa.unsigned_abs(), width = self.scale as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - self.scale as usize);
+    let sign = if self.mantissa < 0 { "-" } else { "" };
+
+    match fraction {
+      "" => write!(f, "{}{}", sign, whole),
+      fraction => write!(f, "{}{}.{}", sign, whole, fraction),
+    }
+  }
+}
+
+fn pow10(exponent: u32) -> Option<i128> {
+  10i128.checked_pow(exponent)
+}
+
+fn digits(mantissa: i128) -> u32 {
+  mantissa.unsigned_abs().checked_ilog10().map_or(1, |log| log + 1)
+}
+
+// INFO: Integer division rounding half away from zero
+fn round_div(dividend: i128, divisor: i128) -> i128 {
+  let (quotient, remainder) = (dividend / divisor, dividend % divisor);
+
+  if remainder.unsigned_abs() >= divisor.unsigned_abs() - remainder.unsigned_abs() {
+    quotient + dividend.signum() * divisor.signum()
+  } else {
+    quotient
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn decimal(text: &str) -> Decimal {
+    text.parse().unwrap()
+  }
+
+  fn text(decimal: Option<Decimal>) -> Option<String> {
+    decimal.map(|decimal| decimal.to_string())
+  }
+
+  #[test]
+  fn rounds_half_away_from_zero() {
+    assert_eq!(text(decimal("2.5").rescale(0)), Some("3".to_string()));
+    assert_eq!(text(decimal("-2.5").rescale(0)), Some("-3".to_string()));
+    assert_eq!(text(decimal("2.49").rescale(0)), Some("2".to_string()));
+    assert_eq!(text(decimal("-1.005").rescale(2)), Some("-1.01".to_string()));
+    assert_eq!(text(decimal("1.5").rescale(3)), Some("1.500".to_string()));
+
+    assert_eq!(text(decimal("125").round(-1)), Some("130".to_string()));
+    assert_eq!(text(decimal("-125").round(-1)), Some("-130".to_string()));
+    assert_eq!(text(decimal("124.9").round(-1)), Some("120".to_string()));
+    assert_eq!(text(decimal("12.345").round(2)), Some("12.35".to_string()));
+    assert_eq!(text(decimal("999").round(-40)), Some("0".to_string()));
+  }
+
+  #[test]
+  fn multiplication_rounds_operands_until_the_product_fits() {
+    let large = Decimal::new(pow10(37).unwrap(), 0).unwrap();
+
+    assert_eq!(text(decimal("1.50").checked_mul(decimal("2"))), Some("3.00".to_string()));
+    assert_eq!(text(decimal("1.5").checked_mul(large)), Some(format!("2{}", "0".repeat(37))));
+    assert_eq!(text(decimal("-2.5").checked_mul(large)), Some(format!("-3{}", "0".repeat(37))));
+    assert_eq!(decimal("15").checked_mul(large), None);
+  }
+
+  #[test]
+  fn division_drops_digits_until_the_quotient_fits() {
+    let large = Decimal::new(pow10(37).unwrap(), 0).unwrap();
+
+    assert_eq!(text(decimal("1").checked_div(decimal("3"))), Some(format!("0.{}", "3".repeat(20))));
+    assert_eq!(text(decimal("10").checked_div(decimal("4"))), Some("2.5".to_string()));
+    assert_eq!(text(decimal("1.00").checked_div(decimal("4"))), Some("0.25".to_string()));
+    assert_eq!(text(decimal("2").checked_div(decimal("3"))), Some(format!("0.{}7", "6".repeat(19))));
+    assert_eq!(text(large.checked_div(decimal("3"))), Some(format!("{}.3", "3".repeat(37))));
+    assert_eq!(large.checked_div(decimal("0.001")), None);
+    assert_eq!(decimal("1").checked_div(decimal("0.00")), None);
+  }
+
+  #[test]
+  fn parses_exponents() {
+    assert_eq!(decimal("1.5e3").to_string(), "1500");
+    assert_eq!(decimal("1.5E-3").to_string(), "0.0015");
+    assert_eq!(decimal("-2e0").to_string(), "-2");
+    assert_eq!(decimal("+.5").to_string(), "0.5");
+    assert_eq!(decimal("12.50").to_string(), "12.50");
+    assert_eq!(decimal("1e37").to_string(), format!("1{}", "0".repeat(37)));
+
+    for invalid in ["1e", "e5", ".", "1.5e3.0", "1e38", "1e-39", "1x"] {
+      assert_eq!(invalid.parse::<Decimal>(), Err(()), "{}", invalid);
+    }
+  }
+
+  #[test]
+  fn orders_values_that_cant_be_rescaled() {
+    let large = Decimal::new(pow10(37).unwrap(), 0).unwrap();
+
+    assert_eq!(decimal("1.5"), decimal("1.50"));
+    assert!(decimal("-0.1") < decimal("0.01"));
+    assert_eq!(large.cmp(&decimal("0.5")), Ordering::Greater);
+    assert_eq!(decimal("0.5").cmp(&large), Ordering::Less);
+    assert_eq!((-large).cmp(&decimal("0.5")), Ordering::Less);
+    assert_eq!(decimal("0.5").cmp(&-large), Ordering::Greater);
+  }
+
+  #[test]
+  fn overflows_to_none() {
+    let largest = Decimal::new(pow10(38).unwrap() - 1, 0).unwrap();
+
+    assert_eq!(Decimal::new(pow10(38).unwrap(), 0), None);
+    assert_eq!(Decimal::new(1, 39), None);
+    assert_eq!(largest.checked_add(decimal("1")), None);
+    assert_eq!((-largest).checked_sub(decimal("1")), None);
+    assert_eq!(largest.rescale(1), None);
+    assert_eq!(largest.round(-1), None);
+    assert_eq!(largest.to_i64(), None);
+    assert_eq!(decimal("1.5").to_i64(), None);
+    assert_eq!(Decimal::from_f64(f64::INFINITY), None);
+  }
+}
@@ -0,0 +1,228 @@
+use chrono::{Duration, Months, NaiveDateTime};
+use serde_derive::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Neg;
+use std::str::FromStr;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+const DAYS_PER_MONTH: i64 = 30; // INFO: only used to compare intervals and to spread fractions of a month
+
+// INFO: Months, days and time are kept apart since a month or a day doesn't always have the same length,
+// `'2024-01-31' + INTERVAL '1 month'` is the end of February and a day may have 23 or 25 hours in a time zone
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Interval {
+  months: i32,
+  days: i32,
+  micros: i64,
+}
+
+impl Interval {
+  pub fn new(months: i32, days: i32, micros: i64) -> Self {
+    Interval { months, days, micros }
+  }
+
+  // INFO: Whole days and the time left, like `'2024-01-02 06:00' - '2024-01-01'` is `1 day 06:00:00`
+  pub fn between(later: NaiveDateTime, earlier: NaiveDateTime) -> Option<Interval> {
+    let micros = (later - earlier).num_microseconds()?;
+    Some(Interval { months: 0, days: i32::try_from(micros / MICROS_PER_DAY).ok()?, micros: micros % MICROS_PER_DAY })
+  }
+
+  // INFO: The months are added first and keep the day of the month when it exists, otherwise the last day is taken
+  pub fn add_to(self, timestamp: NaiveDateTime) -> Option<NaiveDateTime> {
+    let timestamp = match self.months {
+      months if months < 0 => timestamp.checked_sub_months(Months::new(months.unsigned_abs()))?,
+      months => timestamp.checked_add_months(Months::new(months as u32))?,
+    };
+
+    timestamp.checked_add_signed(Duration::days(self.days as i64))?.checked_add_signed(Duration::microseconds(self.micros))
+  }
+
+  pub fn checked_add(self, other: Interval) -> Option<Interval> {
+    Some(Interval {
+      months: self.months.checked_add(other.months)?,
+      days: self.days.checked_add(other.days)?,
+      micros: self.micros.checked_add(other.micros)?,
+    })
+  }
+
+  pub fn checked_sub(self, other: Interval) -> Option<Interval> {
+    Some(Interval {
+      months: self.months.checked_sub(other.months)?,
+      days: self.days.checked_sub(other.days)?,
+      micros: self.micros.checked_sub(other.micros)?,
+    })
+  }
+
+  // INFO: A fraction of a month goes to the days and a fraction of a day to the time, `1 mon * 1.5` is `1 mon 15 days`
+  pub fn checked_mul(self, factor: f64) -> Option<Interval> {
+    Interval::spread(self.months as f64 * factor, self.days as f64 * factor, self.micros as f64 * factor)
+  }
+
+  pub fn checked_div(self, divisor: f64) -> Option<Interval> {
+    (divisor != 0.0).then(|| self.checked_mul(1.0 / divisor)).flatten()
+  }
+
+  fn spread(months: f64, days: f64, micros: f64) -> Option<Interval> {
+    let days = days + months.fract() * DAYS_PER_MONTH as f64;
+    let micros = micros + days.fract() * MICROS_PER_DAY as f64;
+
+    let fits = |value: f64, limit: f64| value.is_finite() && value.abs() <= limit;
+    if !fits(months, i32::MAX as f64) || !fits(days, i32::MAX as f64) || !fits(micros.round(), i64::MAX as f64) {
+      return None;
+    }
+
+    Some(Interval { months: months.trunc() as i32, days: days.trunc() as i32, micros: micros.round() as i64 })
+  }
+
+  // INFO: Length of the interval with months of 30 days, intervals are compared by it
+  fn total_micros(self) -> i128 {
+    (self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128) * MICROS_PER_DAY as i128 + self.micros as i128
+  }
+}
+
+impl Neg for Interval {
+  type Output = Interval;
+
+  // INFO: Wraps like the negation of integers in a release build, only the smallest months or days can't be negated
+  fn neg(self) -> Interval {
+    Interval { months: self.months.wrapping_neg(), days: self.days.wrapping_neg(), micros: self.micros.wrapping_neg() }
+  }
+}
+
+impl PartialEq for Interval {
+  fn eq(&self, other: &Interval) -> bool {
+    self.total_micros() == other.total_micros()
+  }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+  fn partial_cmp(&self, other: &Interval) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+// INFO: `1 mon` and `30 days` are equal like `24:00:00` and `1 day`
+impl Ord for Interval {
+  fn cmp(&self, other: &Interval) -> Ordering {
+    self.total_micros().cmp(&other.total_micros())
+  }
+}
+
+impl Hash for Interval {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.total_micros().hash(state);
+  }
+}
+
+// INFO: Numbers followed by a unit like `3 days` or `1 year 2 months`, a time like `04:05:06.5` and an optional `ago`
+// that negates the whole interval. Numbers can have a fraction, `1.5 hours` is `01:30:00`
+impl FromStr for Interval {
+  type Err = ();
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let mut words = text.split_whitespace().peekable();
+    let mut interval = Interval::default();
+    let mut ago = false;
+
+    if words.peek().is_none() {
+      return Err(());
+    }
+
+    while let Some(word) = words.next() {
+      if ago {
+        return Err(());
+      }
+
+      let part = match word {
+        word if word.eq_ignore_ascii_case("ago") => {
+          ago = true;
+          continue;
+        }
+        word if word.contains(':') => Interval { micros: parse_time(word).ok_or(())?, ..Default::default() },
+        word => {
+          let number: f64 = word.parse().map_err(|_| ())?;
+          let (months, days, micros) = match words.next().ok_or(())?.to_lowercase().as_str() {
+            "year" | "years" | "y" => (number * 12.0, 0.0, 0.0),
+            "month" | "months" | "mon" | "mons" => (number, 0.0, 0.0),
+            "week" | "weeks" | "w" => (0.0, number * 7.0, 0.0),
+            "day" | "days" | "d" => (0.0, number, 0.0),
+            "hour" | "hours" | "h" => (0.0, 0.0, number * 3_600.0 * MICROS_PER_SECOND as f64),
+            "minute" | "minutes" | "min" | "mins" | "m" => (0.0, 0.0, number * 60.0 * MICROS_PER_SECOND as f64),
+            "second" | "seconds" | "sec" | "secs" | "s" => (0.0, 0.0, number * MICROS_PER_SECOND as f64),
+            "millisecond" | "milliseconds" | "ms" => (0.0, 0.0, number * 1_000.0),
+            "microsecond" | "microseconds" | "us" => (0.0, 0.0, number),
+            _ => return Err(()),
+          };
+
+          Interval::spread(months, days, micros).ok_or(())?
+        }
+      };
+
+      interval = interval.checked_add(part).ok_or(())?;
+    }
+
+    Ok(if ago { -interval } else { interval })
+  }
+}
+
+// INFO: `hh:mm` or `hh:mm:ss` with an optional fraction of a second and sign, in microseconds
+fn parse_time(text: &str) -> Option<i64> {
+  let (negative, text) = match text.strip_prefix('-') {
+    Some(text) => (true, text),
+    None => (false, text.strip_prefix('+').unwrap_or(text)),
+  };
+
+  let mut parts = text.split(':');
+  let hours: i64 = parts.next()?.parse().ok()?;
+  let minutes: i64 = parts.next()?.parse().ok().filter(|minutes| (0..60).contains(minutes))?;
+  let seconds: f64 = match parts.next() {
+    Some(seconds) => seconds.parse().ok().filter(|seconds: &f64| (0.0..60.0).contains(seconds))?,
+    None => 0.0,
+  };
+  if parts.next().is_some() {
+    return None;
+  }
+
+  let micros = hours
+    .checked_mul(3_600 * MICROS_PER_SECOND)?
+    .checked_add(minutes * 60 * MICROS_PER_SECOND + (seconds * MICROS_PER_SECOND as f64).round() as i64)?;
+  Some(if negative { -micros } else { micros })
+}
+
+// INFO: Written like `1 year 2 mons 3 days 04:05:06`, the parts that are zero are left out
+impl fmt::Display for Interval {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let plural = |count: i64, unit: &str| format!("{} {}{}", count, unit, if count.abs() == 1 { "" } else { "s" });
+
+    let (years, months) = (self.months / 12, self.months % 12);
+    let mut parts = Vec::new();
+    if years != 0 {
+      parts.push(plural(years as i64, "year"));
+    }
+    if months != 0 {
+      parts.push(plural(months as i64, "mon"));
+    }
+    if self.days != 0 {
+      parts.push(plural(self.days as i64, "day"));
+    }
+
+    if self.micros != 0 || parts.is_empty() {
+      let micros = self.micros.unsigned_abs();
+      let seconds = micros / MICROS_PER_SECOND as u64;
+      let mut time = format!("{:02}:{:02}:{:02}", seconds / 3_600, seconds / 60 % 60, seconds % 60);
+
+      let fraction = micros % MICROS_PER_SECOND as u64;
+      if fraction != 0 {
+        time.push_str(format!(".{:06}", fraction).trim_end_matches('0'));
+      }
+      parts.push(format!("{}{}", if self.micros < 0 { "-" } else { "" }, time));
+    }
+
+    write!(f, "{}", parts.join(" "))
+  }
+}
@@ -0,0 +1,342 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use super::Value;
+
+// INFO: Documents nested deeper than this are rejected instead of overflowing the stack while parsing them
+const MAX_DEPTH: usize = 512;
+
+// INFO: Parsed JSON document, it is stored in this form so the text is only parsed once. Keys of an object keep
+// their order, a repeated key keeps its last value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Json {
+  Null,
+  Boolean(bool),
+  Number(String), // INFO: the text of the number as it was written, so no digit of it is lost
+  String(String),
+  Array(Vec<Json>),
+  Object(Vec<(String, Json)>),
+}
+
+// INFO: Steps from a JSON column to the value an index is built on, `column -> 'a' ->> 'b'`.
+// The last step gives text when `as_text` is set and JSON otherwise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonPath {
+  pub steps: Vec<String>,
+  pub as_text: bool,
+}
+
+impl Json {
+  // INFO: A key of an object or a position in an array, negative positions count from the end
+  pub fn get(&self, step: &str) -> Option<&Json> {
+    match self {
+      Json::Object(entries) => entries.iter().find(|(key, _)| key == step).map(|(_, value)| value),
+      Json::Array(items) => {
+        let position = step.parse::<i64>().ok()?;
+        let position = if position < 0 { position + items.len() as i64 } else { position };
+        items.get(usize::try_from(position).ok()?)
+      }
+      _ => None,
+    }
+  }
+
+  pub fn get_path<S: AsRef<str>>(&self, steps: &[S]) -> Option<&Json> {
+    steps.iter().try_fold(self, |json, step| json.get(step.as_ref()))
+  }
+
+  // INFO: What `->>` gives, a string without its quotes and NULL for a JSON null
+  pub fn to_text(&self) -> Option<String> {
+    match self {
+      Json::Null => None,
+      Json::String(s) => Some(s.clone()),
+      json => Some(json.to_string()),
+    }
+  }
+
+  pub fn type_name(&self) -> &'static str {
+    match self {
+      Json::Null => "null",
+      Json::Boolean(_) => "boolean",
+      Json::Number(_) => "number",
+      Json::String(_) => "string",
+      Json::Array(_) => "array",
+      Json::Object(_) => "object",
+    }
+  }
+}
+
+impl JsonPath {
+  // INFO: NULL when the value is not JSON or the path leads nowhere
+  pub fn extract(&self, value: &Value) -> Value {
+    let Value::Json(json) = value else { return Value::Null };
+
+    match (json.get_path(&self.steps), self.as_text) {
+      (Some(json), true) => json.to_text().map_or(Value::Null, Value::Text),
+      (Some(json), false) => Value::Json(json.clone()),
+      (None, _) => Value::Null,
+    }
+  }
+}
+
+// INFO: A path is either `{a,b,0}` like the `#>` operator takes or `$.a.b[0]`, None when it is neither
+pub fn parse_path(path: &str) -> Option<Vec<String>> {
+  let path = path.trim();
+
+  if let Some(steps) = path.strip_prefix('{').and_then(|path| path.strip_suffix('}')) {
+    return Some(match steps.trim() {
+      "" => Vec::new(),
+      steps => steps.split(',').map(|step| step.trim().trim_matches('"').to_string()).collect(),
+    });
+  }
+
+  let mut rest = path.strip_prefix('$')?;
+  let mut steps = Vec::new();
+  while !rest.is_empty() {
+    let (step, next) = match rest.strip_prefix('.') {
+      Some(rest) => rest.split_at(rest.find(['.', '[']).unwrap_or(rest.len())),
+      None => {
+        let (index, next) = rest.strip_prefix('[')?.split_once(']')?;
+        (index.trim(), next)
+      }
+    };
+
+    if step.is_empty() {
+      return None;
+    }
+    steps.push(step.trim_matches('"').to_string());
+    rest = next;
+  }
+
+  Some(steps)
+}
+
+impl FromStr for Json {
+  type Err = ();
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let mut chars = text.chars().peekable();
+    let json = parse_value(&mut chars, 0)?;
+
+    skip_whitespace(&mut chars);
+    match chars.next() {
+      None => Ok(json),
+      Some(_) => Err(()),
+    }
+  }
+}
+
+fn parse_value(chars: &mut Peekable<Chars>, depth: usize) -> Result<Json, ()> {
+  if depth > MAX_DEPTH {
+    return Err(());
+  }
+
+  skip_whitespace(chars);
+  match chars.peek().ok_or(())? {
+    '{' => {
+      chars.next();
+      let mut entries: Vec<(String, Json)> = Vec::new();
+
+      if !next_if(chars, '}') {
+        loop {
+          skip_whitespace(chars);
+          let key = parse_string(chars)?;
+          skip_whitespace(chars);
+          expect(chars, ':')?;
+          let value = parse_value(chars, depth + 1)?;
+
+          entries.retain(|(existing, _)| *existing != key);
+          entries.push((key, value));
+
+          if !next_if(chars, ',') {
+            break;
+          }
+        }
+        expect(chars, '}')?;
+      }
+
+      Ok(Json::Object(entries))
+    }
+    '[' => {
+      chars.next();
+      let mut items = Vec::new();
+
+      if !next_if(chars, ']') {
+        loop {
+          items.push(parse_value(chars, depth + 1)?);
+
+          if !next_if(chars, ',') {
+            break;
+          }
+        }
+        expect(chars, ']')?;
+      }
+
+      Ok(Json::Array(items))
+    }
+    '"' => parse_string(chars).map(Json::String),
+    't' => parse_word(chars, "true", Json::Boolean(true)),
+    'f' => parse_word(chars, "false", Json::Boolean(false)),
+    'n' => parse_word(chars, "null", Json::Null),
+    _ => parse_number(chars),
+  }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, ()> {
+  expect(chars, '"')?;
+  let mut string = String::new();
+
+  loop {
+    match chars.next().ok_or(())? {
+      '"' => return Ok(string),
+      '\\' => match chars.next().ok_or(())? {
+        '"' => string.push('"'),
+        '\\' => string.push('\\'),
+        '/' => string.push('/'),
+        'b' => string.push('\u{8}'),
+        'f' => string.push('\u{c}'),
+        'n' => string.push('\n'),
+        'r' => string.push('\r'),
+        't' => string.push('\t'),
+        'u' => {
+          let high = parse_hex(chars)?;
+          // INFO: characters outside of the basic plane are written as a surrogate pair
+          let code = match high {
+            0xD800..=0xDBFF if chars.next_if_eq(&'\\').is_some() && chars.next_if_eq(&'u').is_some() => {
+              let low = parse_hex(chars)?;
+              0x10000 + ((high - 0xD800) << 10) + low.checked_sub(0xDC00).filter(|low| *low < 0x400).ok_or(())?
+            }
+            code => code,
+          };
+          string.push(char::from_u32(code).ok_or(())?);
+        }
+        _ => return Err(()),
+      },
+      ch if ch.is_control() => return Err(()),
+      ch => string.push(ch),
+    }
+  }
+}
+
+fn parse_hex(chars: &mut Peekable<Chars>) -> Result<u32, ()> {
+  (0..4).try_fold(0, |code, _| Ok(code * 16 + chars.next().and_then(|ch| ch.to_digit(16)).ok_or(())?))
+}
+
+fn parse_word(chars: &mut Peekable<Chars>, word: &str, json: Json) -> Result<Json, ()> {
+  for expected in word.chars() {
+    expect(chars, expected)?;
+  }
+
+  Ok(json)
+}
+
+// INFO: `-`, the digits without leading zeros, an optional fraction and an optional exponent
+fn parse_number(chars: &mut Peekable<Chars>) -> Result<Json, ()> {
+  fn digits(chars: &mut Peekable<Chars>, number: &mut String) -> bool {
+    let start = number.len();
+    while let Some(ch) = chars.next_if(char::is_ascii_digit) {
+      number.push(ch);
+    }
+    number.len() > start
+  }
+
+  let mut number = String::new();
+
+  if chars.next_if_eq(&'-').is_some() {
+    number.push('-');
+  }
+  let start = number.len();
+  if !digits(chars, &mut number) || (number[start..].starts_with('0') && number.len() - start > 1) {
+    return Err(());
+  }
+  if chars.next_if_eq(&'.').is_some() {
+    number.push('.');
+    if !digits(chars, &mut number) {
+      return Err(());
+    }
+  }
+  if let Some(e) = chars.next_if(|ch| *ch == 'e' || *ch == 'E') {
+    number.push(e);
+    if let Some(sign) = chars.next_if(|ch| *ch == '+' || *ch == '-') {
+      number.push(sign);
+    }
+    if !digits(chars, &mut number) {
+      return Err(());
+    }
+  }
+
+  Ok(Json::Number(number))
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+  while chars.next_if(|ch| matches!(ch, ' ' | '\t' | '\n' | '\r')).is_some() {}
+}
+
+// INFO: Skips the whitespace before the character as well
+fn next_if(chars: &mut Peekable<Chars>, expected: char) -> bool {
+  skip_whitespace(chars);
+  chars.next_if_eq(&expected).is_some()
+}
+
+fn expect(chars: &mut Peekable<Chars>, expected: char) -> Result<(), ()> {
+  match chars.next() {
+    Some(ch) if ch == expected => Ok(()),
+    _ => Err(()),
+  }
+}
+
+impl fmt::Display for Json {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Json::Null => write!(f, "null"),
+      Json::Boolean(b) => write!(f, "{}", b),
+      Json::Number(n) => write!(f, "{}", n),
+      Json::String(s) => write_string(f, s),
+      Json::Array(items) => {
+        write!(f, "[")?;
+        for (i, item) in items.iter().enumerate() {
+          write!(f, "{}{}", if i > 0 { ", " } else { "" }, item)?;
+        }
+        write!(f, "]")
+      }
+      Json::Object(entries) => {
+        write!(f, "{{")?;
+        for (i, (key, value)) in entries.iter().enumerate() {
+          write!(f, "{}", if i > 0 { ", " } else { "" })?;
+          write_string(f, key)?;
+          write!(f, ": {}", value)?;
+        }
+        write!(f, "}}")
+      }
+    }
+  }
+}
+
+fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+  write!(f, "\"")?;
+  for ch in string.chars() {
+    match ch {
+      '"' => write!(f, "\\\"")?,
+      '\\' => write!(f, "\\\\")?,
+      '\n' => write!(f, "\\n")?,
+      '\r' => write!(f, "\\r")?,
+      '\t' => write!(f, "\\t")?,
+      ch if ch.is_control() => write!(f, "\\u{:04x}", ch as u32)?,
+      ch => write!(f, "{}", ch)?,
+    }
+  }
+  write!(f, "\"")
+}
+
+// INFO: Printed the way it is written after a JSON column, `-> 'a' ->> 'b'`
+impl fmt::Display for JsonPath {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, step) in self.steps.iter().enumerate() {
+      let operator = if self.as_text && i + 1 == self.steps.len() { "->>" } else { "->" };
+      write!(f, "{}{} '{}'", if i > 0 { " " } else { "" }, operator, step.replace('\'', "''"))?;
+    }
+
+    Ok(())
+  }
+}
@@ -1,5 +1,19 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+mod decimal;
+mod interval;
+mod json;
+mod uuid;
+
+pub use decimal::{Decimal, DECIMAL_MAX_PRECISION};
+pub use interval::Interval;
+pub use json::{parse_path, Json, JsonPath};
+pub use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct Catalog {
@@ -8,42 +22,192 @@ pub struct Catalog {
   pub views: Option<Vec<String>>, // INFO: Only string for now so I can decide later do I want to implement it
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+pub type ColumnId = u32;
+
+// INFO: Columns keep the order they were defined in, stored rows refer to them by id so a rename doesn't touch the rows.
+// Ids of dropped columns are never given out again
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Table {
   pub name: String,
-  pub columns: HashMap<String, Column>,
+  pub columns: Vec<Column>,
+  pub column_ids: HashMap<String, ColumnId>,
+  pub next_column_id: ColumnId,
+  pub indexes: Vec<Index>,
+  pub constraints: Vec<Constraint>,
 }
 
 impl Table {
-  pub fn new(name: String, columns: HashMap<String, Column>) -> Self {
-    Self { name, columns }
+  pub fn new(name: String) -> Self {
+    let (columns, column_ids) = (Vec::new(), HashMap::new());
+    Self { name, columns, column_ids, next_column_id: 0, indexes: Vec::new(), constraints: Vec::new() }
+  }
+
+  // INFO: PRIMARY KEY and UNIQUE constraints get a unique index with the same name, a primary key also makes its columns NOT NULL
+  pub fn add_constraint(&mut self, constraint: Constraint) {
+    match &constraint.kind {
+      ConstraintKind::PrimaryKey(columns) => {
+        for column in columns {
+          if let Some(column) = self.get_mut_column(column) {
+            column.nullable = false;
+          }
+        }
+
+        self.add_index(Index::new(constraint.name.clone(), columns.clone(), true));
+      }
+      ConstraintKind::Unique(columns) => self.add_index(Index::new(constraint.name.clone(), columns.clone(), true)),
+      ConstraintKind::Check { .. } | ConstraintKind::ForeignKey(_) => {}
+    }
+
+    self.constraints.push(constraint);
+  }
+
+  // INFO: The index of a PRIMARY KEY or UNIQUE constraint goes with it, NOT NULL of a primary key stays on its columns
+  pub fn remove_constraint(&mut self, constraint_name: &str) -> Option<Constraint> {
+    let position = self.constraints.iter().position(|constraint| constraint.name == constraint_name)?;
+    self.indexes.retain(|index| index.name != constraint_name);
+
+    Some(self.constraints.remove(position))
+  }
+
+  pub fn get_constraint(&self, constraint_name: &str) -> Option<&Constraint> {
+    self.constraints.iter().find(|constraint| constraint.name == constraint_name)
+  }
+
+  pub fn primary_key(&self) -> Option<&Constraint> {
+    self.constraints.iter().find(|constraint| matches!(constraint.kind, ConstraintKind::PrimaryKey(_)))
+  }
+
+  pub fn foreign_keys(&self) -> impl Iterator<Item = (&String, &Reference)> {
+    self.constraints.iter().filter_map(|constraint| match &constraint.kind {
+      ConstraintKind::ForeignKey(reference) => Some((&constraint.name, reference)),
+      _ => None,
+    })
+  }
+
+  pub fn add_index(&mut self, index: Index) {
+    self.indexes.push(index);
+  }
+
+  pub fn unique_indexes(&self) -> impl Iterator<Item = &Index> {
+    self.indexes.iter().filter(|index| index.unique)
   }
 
-  pub fn add_column(&mut self, column: Column) {
-    self.columns.insert(column.name.clone(), column);
+  // INFO: Finds the unique index that covers exactly the given columns, in any order. An index on a JSON path doesn't
+  // cover its column
+  pub fn find_unique_index(&self, columns: &[String]) -> Option<&Index> {
+    self.unique_indexes().find(|index| {
+      index.path.is_none() && index.columns.len() == columns.len() && columns.iter().all(|column| index.columns.contains(column))
+    })
+  }
+
+  // INFO: The column goes after the existing ones and gets the next id
+  pub fn add_column(&mut self, mut column: Column) {
+    column.id = self.next_column_id;
+    self.next_column_id += 1;
+
+    self.column_ids.insert(column.name.clone(), column.id);
+    self.columns.push(column);
+  }
+
+  // INFO: The column keeps its id and its place
+  pub fn replace_column(&mut self, mut column: Column) {
+    if let Some(existing) = self.get_mut_column(&column.name) {
+      column.id = existing.id;
+      *existing = column;
+    }
   }
 
   pub fn remove_column(&mut self, column_name: &str) -> Option<Column> {
-    self.columns.remove(column_name)
+    let id = self.column_ids.remove(column_name)?;
+    let position = self.columns.iter().position(|column| column.id == id)?;
+
+    Some(self.columns.remove(position))
   }
 
   pub fn get_column(&self, column_name: &str) -> Option<&Column> {
-    self.columns.get(column_name)
+    let id = self.column_id(column_name)?;
+    self.columns.iter().find(|column| column.id == id)
+  }
+
+  pub fn column_id(&self, column_name: &str) -> Option<ColumnId> {
+    self.column_ids.get(column_name).copied()
+  }
+
+  // INFO: The column is renamed in its constraints and indexes as well, CHECK conditions are SQL so the caller rewrites them
+  pub fn rename_column(&mut self, column_name: &str, new_name: &str) {
+    if let Some(id) = self.column_ids.remove(column_name) {
+      self.column_ids.insert(new_name.to_string(), id);
+    }
+    if let Some(column) = self.get_mut_column(new_name) {
+      column.name = new_name.to_string();
+    }
+
+    let rename = |columns: &mut Vec<String>| {
+      columns.iter_mut().filter(|column| *column == column_name).for_each(|column| *column = new_name.to_string())
+    };
+
+    for index in &mut self.indexes {
+      rename(&mut index.columns);
+    }
+
+    for constraint in &mut self.constraints {
+      match &mut constraint.kind {
+        ConstraintKind::PrimaryKey(columns) | ConstraintKind::Unique(columns) | ConstraintKind::Check { columns, .. } => {
+          rename(columns)
+        }
+        ConstraintKind::ForeignKey(reference) => {
+          rename(&mut reference.columns);
+          if reference.table_name == self.name {
+            rename(&mut reference.referenced_columns);
+          }
+        }
+      }
+    }
   }
 
   pub fn get_mut_column(&mut self, column_name: &str) -> Option<&mut Column> {
-    self.columns.get_mut(column_name)
+    let id = self.column_id(column_name)?;
+    self.columns.iter_mut().find(|column| column.id == id)
+  }
+
+  pub fn column_names(&self) -> Vec<String> {
+    self.columns.iter().map(|column| column.name.clone()).collect()
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Index {
+  pub name: String,
+  pub columns: Vec<String>,
+  pub unique: bool,
+  pub path: Option<JsonPath>, // INFO: the key is the value extracted from the only column instead of the column itself
+}
+
+impl Index {
+  pub fn new(name: String, columns: Vec<String>, unique: bool) -> Self {
+    Self { name, columns, unique, path: None }
+  }
+
+  pub fn on_path(name: String, column: String, path: JsonPath, unique: bool) -> Self {
+    Self { name, columns: vec![column], unique, path: Some(path) }
+  }
+
+  // INFO: The keys as they are written, `payload ->> 'id'` for an index on a JSON path
+  pub fn keys(&self) -> Vec<String> {
+    match &self.path {
+      Some(path) => self.columns.iter().map(|column| format!("{} {}", column, path)).collect(),
+      None => self.columns.clone(),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Column {
+  pub id: ColumnId, // INFO: given by the table when the column is added
   pub name: String,
   pub data_type: DataType,
-  pub unique: bool,
   pub nullable: bool,
-  pub default: Option<String>,
-  pub references: Option<Reference>,
+  pub default: Option<String>, // INFO: DEFAULT and CHECK expressions are kept as SQL and parsed again when they are used
 }
 
 impl Column {
@@ -55,10 +219,6 @@ impl Column {
     self.data_type = new_data_type;
   }
 
-  pub fn update_unique(&mut self, new_unique: bool) {
-    self.unique = new_unique;
-  }
-
   pub fn update_nullable(&mut self, new_nullable: bool) {
     self.nullable = new_nullable;
   }
@@ -67,46 +227,490 @@ impl Column {
     self.default = new_default;
   }
 
-  pub fn update_references(&mut self, new_references: Option<Reference>) {
-    self.references = new_references;
-  }
-
   pub fn get_attribute(&self, attribute_name: &str) -> Option<&dyn std::fmt::Debug> {
     match attribute_name {
       "name" => Some(&self.name),
       "data_type" => Some(&self.data_type),
-      "unique" => Some(&self.unique),
       "nullable" => Some(&self.nullable),
       "default" => self.default.as_ref().map(|v| v as &dyn std::fmt::Debug),
-      "references" => self.references.as_ref().map(|v| v as &dyn std::fmt::Debug),
       _ => None,
     }
   }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// INFO: Column constraints are kept here as well, only NOT NULL and DEFAULT stay on the column
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Constraint {
+  pub name: String,
+  pub kind: ConstraintKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ConstraintKind {
+  PrimaryKey(Vec<String>),
+  Unique(Vec<String>),
+  Check { columns: Vec<String>, condition: String }, // INFO: columns are the ones the condition reads
+  ForeignKey(Reference),
+}
+
+impl Constraint {
+  pub fn new(name: String, kind: ConstraintKind) -> Self {
+    Self { name, kind }
+  }
+
+  pub fn columns(&self) -> &[String] {
+    match &self.kind {
+      ConstraintKind::PrimaryKey(columns) | ConstraintKind::Unique(columns) => columns,
+      ConstraintKind::Check { columns, .. } => columns,
+      ConstraintKind::ForeignKey(reference) => &reference.columns,
+    }
+  }
+}
+
+// INFO: Written the way ALTER TABLE ADD takes it
+impl fmt::Display for Constraint {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "CONSTRAINT {} ", self.name)?;
+
+    match &self.kind {
+      ConstraintKind::PrimaryKey(columns) => write!(f, "PRIMARY KEY ({})", columns.join(", ")),
+      ConstraintKind::Unique(columns) => write!(f, "UNIQUE ({})", columns.join(", ")),
+      ConstraintKind::Check { condition, .. } => write!(f, "CHECK ({})", condition),
+      ConstraintKind::ForeignKey(reference) => {
+        write!(
+          f,
+          "FOREIGN KEY ({}) REFERENCES {} ({})",
+          reference.columns.join(", "),
+          reference.table_name,
+          reference.referenced_columns.join(", ")
+        )?;
+        if reference.on_delete != ReferentialAction::NoAction {
+          write!(f, " ON DELETE {}", reference.on_delete)?;
+        }
+        if reference.on_update != ReferentialAction::NoAction {
+          write!(f, " ON UPDATE {}", reference.on_update)?;
+        }
+
+        Ok(())
+      }
+    }
+  }
+}
+
+// INFO: The referenced columns are the columns of a PRIMARY KEY or UNIQUE constraint of the referenced table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Reference {
-  pub table_name: i32,
-  pub column_name: i32,
+  pub columns: Vec<String>,
+  pub table_name: String,
+  pub referenced_columns: Vec<String>,
+  pub on_delete: ReferentialAction,
+  pub on_update: ReferentialAction,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReferentialAction {
+  NoAction,
+  Restrict,
+  Cascade,
+  SetNull,
+}
+
+impl fmt::Display for ReferentialAction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ReferentialAction::NoAction => write!(f, "NO ACTION"),
+      ReferentialAction::Restrict => write!(f, "RESTRICT"),
+      ReferentialAction::Cascade => write!(f, "CASCADE"),
+      ReferentialAction::SetNull => write!(f, "SET NULL"),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DataType {
+  SmallInt,
   Int,
+  BigInt,
   Text,
+  Varchar(u32),
+  Char(u32), // INFO: shorter values are padded with spaces
   Boolean,
+  Real,
   Float,
+  Decimal(Option<(u32, u32)>), // INFO: precision and scale, a DECIMAL without them takes any scale
   Date,
   DateTime,
+  TimestampTz, // INFO: kept in UTC, the offset of the text is applied when it is read
+  Interval,
+  Json,
+  Bytea,
+  Uuid,
   Null,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Value {
-  Int(i32),
+  Int(i64),
   Float(f64),
+  Decimal(Decimal),
   Text(String),
   Boolean(bool),
+  Date(NaiveDate),
+  Timestamp(NaiveDateTime),
+  TimestampTz(DateTime<Utc>),
+  Interval(Interval),
+  Json(Json),
+  Bytes(Vec<u8>),
+  Uuid(Uuid),
+  Null,
+}
+
+impl Value {
+  pub fn data_type(&self) -> DataType {
+    match self {
+      Value::Int(_) => DataType::Int,
+      Value::Float(_) => DataType::Float,
+      Value::Decimal(_) => DataType::Decimal(None),
+      Value::Text(_) => DataType::Text,
+      Value::Boolean(_) => DataType::Boolean,
+      Value::Date(_) => DataType::Date,
+      Value::Timestamp(_) => DataType::DateTime,
+      Value::TimestampTz(_) => DataType::TimestampTz,
+      Value::Interval(_) => DataType::Interval,
+      Value::Json(_) => DataType::Json,
+      Value::Bytes(_) => DataType::Bytea,
+      Value::Uuid(_) => DataType::Uuid,
+      Value::Null => DataType::Null,
+    }
+  }
+
+  // INFO: Converts the value so it can be stored in a column of the given type, None if it can't be stored there.
+  // The range or length of the column is checked by `DataType::fit`
+  pub fn coerce_to(self, data_type: &DataType) -> Option<Value> {
+    match (self, data_type.value_type()) {
+      (Value::Null, _) => Some(Value::Null),
+      (Value::Int(i), DataType::Int) => Some(Value::Int(i)),
+      (Value::Int(i), DataType::Float) => Some(Value::Float(i as f64)),
+      (Value::Float(f), DataType::Float) => Some(Value::Float(f)),
+      (Value::Float(f), DataType::Int) if f.fract() == 0.0 && f >= i64::MIN as f64 && f <= i64::MAX as f64 => {
+        Some(Value::Int(f as i64))
+      }
+      (Value::Int(i), DataType::Decimal(_)) => Some(Value::Decimal(Decimal::from(i))),
+      (Value::Decimal(d), DataType::Decimal(_)) => Some(Value::Decimal(d)),
+      (Value::Decimal(d), DataType::Float) => Some(Value::Float(d.to_f64())),
+      (Value::Decimal(d), DataType::Int) => d.to_i64().map(Value::Int),
+      (Value::Text(s), DataType::Text) => Some(Value::Text(s)),
+      (Value::Boolean(b), DataType::Boolean) => Some(Value::Boolean(b)),
+      (Value::Date(d), DataType::Date) => Some(Value::Date(d)),
+      (Value::Date(d), DataType::DateTime) => Some(Value::Timestamp(d.and_time(Default::default()))),
+      (Value::Timestamp(t), DataType::DateTime) => Some(Value::Timestamp(t)),
+      // INFO: a date or timestamp without a time zone is taken as UTC
+      (Value::Date(d), DataType::TimestampTz) => Some(Value::TimestampTz(d.and_time(Default::default()).and_utc())),
+      (Value::Timestamp(t), DataType::TimestampTz) => Some(Value::TimestampTz(t.and_utc())),
+      (Value::TimestampTz(t), DataType::TimestampTz) => Some(Value::TimestampTz(t)),
+      (Value::TimestampTz(t), DataType::DateTime) => Some(Value::Timestamp(t.naive_utc())),
+      // INFO: a string is read as the date or time it spells, a timestamp written as a bare date starts at midnight
+      (Value::Text(s), DataType::Date) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok().map(Value::Date),
+      (Value::Text(s), DataType::DateTime) => parse_timestamp(s.trim()).map(Value::Timestamp),
+      (Value::Text(s), DataType::TimestampTz) => parse_timestamptz(s.trim()).map(Value::TimestampTz),
+      (Value::Interval(i), DataType::Interval) => Some(Value::Interval(i)),
+      (Value::Text(s), DataType::Interval) => s.parse().ok().map(Value::Interval),
+      (Value::Json(json), DataType::Json) => Some(Value::Json(json)),
+      (Value::Text(s), DataType::Json) => s.parse().ok().map(Value::Json),
+      (Value::Bytes(b), DataType::Bytea) => Some(Value::Bytes(b)),
+      (Value::Text(s), DataType::Bytea) => s.strip_prefix("\\x").and_then(decode_hex).map(Value::Bytes),
+      (Value::Uuid(u), DataType::Uuid) => Some(Value::Uuid(u)),
+      (Value::Text(s), DataType::Uuid) => s.parse().ok().map(Value::Uuid),
+      _ => None,
+    }
+  }
+
+  // INFO: Explicit conversion used by CAST, None when the value has no representation in the target type or doesn't
+  // fit it. Like in SQL a string cast to VARCHAR(n) or CHAR(n) is cut to n characters
+  pub fn cast(self, data_type: &DataType) -> Option<Value> {
+    match (self.convert(data_type)?, data_type) {
+      (Value::Text(s), DataType::Varchar(length) | DataType::Char(length)) => {
+        data_type.fit(Value::Text(s.chars().take(*length as usize).collect()))
+      }
+      (value, data_type) => data_type.fit(value),
+    }
+  }
+
+  // INFO: The conversion of CAST without the range and length checks
+  pub fn convert(self, data_type: &DataType) -> Option<Value> {
+    match (self, data_type.value_type()) {
+      (Value::Null, _) => Some(Value::Null),
+      (Value::Float(f), DataType::Int) => {
+        let rounded = f.round();
+        (rounded >= i64::MIN as f64 && rounded <= i64::MAX as f64).then_some(Value::Int(rounded as i64))
+      }
+      (Value::Decimal(d), DataType::Int) => d.round(0)?.to_i64().map(Value::Int),
+      (Value::Float(f), DataType::Decimal(_)) => Decimal::from_f64(f).map(Value::Decimal),
+      (Value::Int(i), DataType::Boolean) => Some(Value::Boolean(i != 0)),
+      (Value::Boolean(b), DataType::Int) => Some(Value::Int(b as i64)),
+      (
+        value @ (Value::Int(_)
+        | Value::Float(_)
+        | Value::Decimal(_)
+        | Value::Boolean(_)
+        | Value::Date(_)
+        | Value::Timestamp(_)
+        | Value::TimestampTz(_)
+        | Value::Interval(_)
+        | Value::Json(_)
+        | Value::Bytes(_)
+        | Value::Uuid(_)),
+        DataType::Text,
+      ) => Some(Value::Text(value.to_string())),
+      (Value::Timestamp(t), DataType::Date) => Some(Value::Date(t.date())),
+      (Value::TimestampTz(t), DataType::Date) => Some(Value::Date(t.date_naive())),
+      (Value::Text(s), DataType::Date) => NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok().map(Value::Date),
+      (Value::Text(s), DataType::DateTime) => parse_timestamp(s.trim()).map(Value::Timestamp),
+      (Value::Text(s), DataType::Int) => s.trim().parse().ok().map(Value::Int),
+      (Value::Text(s), DataType::Float) => s.trim().parse().ok().map(Value::Float),
+      (Value::Text(s), DataType::Decimal(_)) => s.trim().parse().ok().map(Value::Decimal),
+      // INFO: text that isn't written in hex gives its UTF-8 bytes
+      (Value::Text(s), DataType::Bytea) => match s.strip_prefix("\\x") {
+        Some(digits) => decode_hex(digits).map(Value::Bytes),
+        None => Some(Value::Bytes(s.into_bytes())),
+      },
+      (Value::Uuid(u), DataType::Bytea) => Some(Value::Bytes(u.as_bytes().to_vec())),
+      (Value::Bytes(b), DataType::Uuid) => b.try_into().ok().map(|bytes| Value::Uuid(Uuid::from_bytes(bytes))),
+      (Value::Text(s), DataType::Boolean) => match s.trim().to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "on" | "1" => Some(Value::Boolean(true)),
+        "false" | "f" | "no" | "n" | "off" | "0" => Some(Value::Boolean(false)),
+        _ => None,
+      },
+      (value, value_type) => value.coerce_to(&value_type),
+    }
+  }
+
+  pub fn is_null(&self) -> bool {
+    matches!(self, Value::Null)
+  }
+
+  // INFO: Compares values of compatible types, None when the values can't be compared or one of them is NULL
+  pub fn compare(&self, other: &Value) -> Option<Ordering> {
+    match (self, other) {
+      (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+      (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+      (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
+      (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
+      (Value::Decimal(a), Value::Decimal(b)) => Some(a.cmp(b)),
+      (Value::Decimal(a), Value::Int(b)) => Some(a.cmp(&Decimal::from(*b))),
+      (Value::Int(a), Value::Decimal(b)) => Some(Decimal::from(*a).cmp(b)),
+      (Value::Decimal(a), Value::Float(b)) => a.to_f64().partial_cmp(b),
+      (Value::Float(a), Value::Decimal(b)) => a.partial_cmp(&b.to_f64()),
+      (Value::Text(a), Value::Text(b)) => Some(a.cmp(b)),
+      (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+      (Value::Date(a), Value::Date(b)) => Some(a.cmp(b)),
+      (Value::Timestamp(a), Value::Timestamp(b)) => Some(a.cmp(b)),
+      (Value::Date(a), Value::Timestamp(b)) => Some(a.and_time(Default::default()).cmp(b)),
+      (Value::Timestamp(a), Value::Date(b)) => Some(a.cmp(&b.and_time(Default::default()))),
+      (Value::TimestampTz(a), Value::TimestampTz(b)) => Some(a.cmp(b)),
+      (Value::TimestampTz(a), Value::Date(_) | Value::Timestamp(_)) => Some(a.naive_utc().cmp(&other.to_naive()?)),
+      (Value::Date(_) | Value::Timestamp(_), Value::TimestampTz(b)) => Some(self.to_naive()?.cmp(&b.naive_utc())),
+      (Value::Interval(a), Value::Interval(b)) => Some(a.cmp(b)),
+      // INFO: documents are ordered by their text
+      (Value::Json(a), Value::Json(b)) => Some(a.to_string().cmp(&b.to_string())),
+      (Value::Bytes(a), Value::Bytes(b)) => Some(a.cmp(b)),
+      (Value::Uuid(a), Value::Uuid(b)) => Some(a.cmp(b)),
+      _ => None,
+    }
+  }
+
+  // INFO: The point in time of a date or timestamp, the UTC time for a TIMESTAMPTZ
+  pub fn to_naive(&self) -> Option<NaiveDateTime> {
+    match self {
+      Value::Date(d) => Some(d.and_time(Default::default())),
+      Value::Timestamp(t) => Some(*t),
+      Value::TimestampTz(t) => Some(t.naive_utc()),
+      _ => None,
+    }
+  }
+
+  // INFO: Total order used for sorting and index keys, NULLs go last
+  pub fn sort_cmp(&self, other: &Value) -> Ordering {
+    match (self, other) {
+      (Value::Null, Value::Null) => Ordering::Equal,
+      (Value::Null, _) => Ordering::Greater,
+      (_, Value::Null) => Ordering::Less,
+      (Value::Int(a), Value::Float(b)) => (*a as f64).total_cmp(b),
+      (Value::Float(a), Value::Int(b)) => a.total_cmp(&(*b as f64)),
+      (Value::Float(a), Value::Float(b)) => a.total_cmp(b),
+      (Value::Decimal(a), Value::Float(b)) => a.to_f64().total_cmp(b),
+      (Value::Float(a), Value::Decimal(b)) => a.total_cmp(&b.to_f64()),
+      _ => self.compare(other).unwrap_or_else(|| self.data_type().to_string().cmp(&other.data_type().to_string())),
+    }
+  }
+}
+
+// INFO: Pairs of hex digits like the ones of `X'DEADBEEF'`, None for an odd number of digits or a character that isn't one
+pub fn decode_hex(digits: &str) -> Option<Vec<u8>> {
+  let digits = digits.as_bytes();
+  if !digits.len().is_multiple_of(2) {
+    return None;
+  }
+
+  digits.chunks(2).map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok()).collect()
+}
+
+// INFO: `YYYY-MM-DD HH:MM:SS` with optional fractional seconds, a `T` can separate the date and the time and a bare
+// date starts at midnight
+pub fn parse_timestamp(text: &str) -> Option<NaiveDateTime> {
+  ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().map(|date| date.and_time(Default::default())))
+}
+
+// INFO: A timestamp followed by an offset like `+02`, `+02:00` or `Z`. Without an offset the time is taken as UTC
+pub fn parse_timestamptz(text: &str) -> Option<DateTime<Utc>> {
+  let text = text.strip_suffix(['Z', 'z']).map_or(text.to_string(), |text| format!("{}+00", text));
+
+  ["%Y-%m-%d %H:%M:%S%.f%#z", "%Y-%m-%dT%H:%M:%S%.f%#z", "%Y-%m-%d %H:%M:%S%.f %#z"]
+    .iter()
+    .find_map(|format| DateTime::parse_from_str(&text, format).ok())
+    .map(|timestamp| timestamp.with_timezone(&Utc))
+    .or_else(|| parse_timestamp(&text).map(|timestamp| timestamp.and_utc()))
+}
+
+impl PartialEq for Value {
+  fn eq(&self, other: &Value) -> bool {
+    self.sort_cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for Value {}
+
+impl PartialOrd for Value {
+  fn partial_cmp(&self, other: &Value) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl Ord for Value {
+  fn cmp(&self, other: &Value) -> Ordering {
+    self.sort_cmp(other)
+  }
+}
+
+// INFO: Numbers are hashed as floats so Int(1), Float(1.0) and Decimal(1.00) land together like they compare equal
+impl Hash for Value {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    match self {
+      Value::Int(i) => (*i as f64).to_bits().hash(state),
+      Value::Float(f) => f.to_bits().hash(state),
+      Value::Decimal(d) => d.to_f64().to_bits().hash(state),
+      Value::Text(s) => s.hash(state),
+      Value::Boolean(b) => b.hash(state),
+      Value::Date(d) => d.and_time(Default::default()).hash(state),
+      Value::Timestamp(t) => t.hash(state),
+      Value::TimestampTz(t) => t.naive_utc().hash(state),
+      Value::Interval(i) => i.hash(state),
+      Value::Json(json) => json.to_string().hash(state),
+      Value::Bytes(b) => b.hash(state),
+      Value::Uuid(u) => u.hash(state),
+      Value::Null => state.write_u8(0),
+    }
+  }
+}
+
+impl fmt::Display for Value {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Value::Int(i) => write!(f, "{}", i),
+      Value::Float(n) => write!(f, "{}", n),
+      Value::Decimal(d) => write!(f, "{}", d),
+      Value::Text(s) => write!(f, "{}", s),
+      Value::Boolean(b) => write!(f, "{}", b),
+      Value::Date(d) => write!(f, "{}", d),
+      Value::Timestamp(t) => write!(f, "{}", t.format("%Y-%m-%d %H:%M:%S%.f")),
+      Value::TimestampTz(t) => write!(f, "{}+00", t.format("%Y-%m-%d %H:%M:%S%.f")),
+      Value::Interval(i) => write!(f, "{}", i),
+      Value::Json(json) => write!(f, "{}", json),
+      Value::Bytes(b) => write!(f, "\\x{}", b.iter().map(|byte| format!("{:02x}", byte)).collect::<String>()),
+      Value::Uuid(u) => write!(f, "{}", u),
+      Value::Null => write!(f, "NULL"),
+    }
+  }
+}
+
+impl DataType {
+  // INFO: Whether values of this type can be stored in a column of the target type, ranges and lengths are checked
+  // once the value is known
+  pub fn is_assignable_to(&self, target: &DataType) -> bool {
+    let (source, target) = (self.value_type(), target.value_type());
+    let numeric = |data_type: &DataType| matches!(data_type, DataType::Int | DataType::Float | DataType::Decimal(_));
+    let temporal = |data_type: &DataType| matches!(data_type, DataType::Date | DataType::DateTime | DataType::TimestampTz);
+    matches!(
+      (&source, &target),
+      (DataType::Null, _) | (DataType::Text, DataType::Json | DataType::Uuid | DataType::Interval | DataType::Bytea)
+    ) || (numeric(&source) && numeric(&target))
+      || ((temporal(&source) || source == DataType::Text) && temporal(&target))
+      || source == target
+  }
+
+  // INFO: Type of the values a column of this type holds, the width or length of the column only limits which values fit
+  pub fn value_type(&self) -> DataType {
+    match self {
+      DataType::SmallInt | DataType::Int | DataType::BigInt => DataType::Int,
+      DataType::Real | DataType::Float => DataType::Float,
+      DataType::Decimal(_) => DataType::Decimal(None),
+      DataType::Text | DataType::Varchar(_) | DataType::Char(_) => DataType::Text,
+      data_type => data_type.clone(),
+    }
+  }
+
+  // INFO: Checks that a value of the value type fits the range or length of this type, CHAR(n) drops its trailing spaces
+  // and DECIMAL(p, s) rounds it to s digits after the point
+  pub fn fit(&self, value: Value) -> Option<Value> {
+    match (self, value) {
+      (DataType::SmallInt, Value::Int(i)) => i16::try_from(i).is_ok().then_some(Value::Int(i)),
+      (DataType::Int, Value::Int(i)) => i32::try_from(i).is_ok().then_some(Value::Int(i)),
+      (DataType::Real, Value::Float(f)) => {
+        // INFO: goes through the shortest text of the f32 so 1.1 stays 1.1 instead of 1.100000023841858
+        let real = f as f32;
+        (real.is_finite() || !f.is_finite()).then(|| Value::Float(real.to_string().parse().unwrap_or(real as f64)))
+      }
+      (DataType::Decimal(Some((precision, scale))), Value::Decimal(d)) => {
+        d.rescale(*scale).filter(|d| d.precision() <= *precision).map(Value::Decimal)
+      }
+      (DataType::Varchar(length), Value::Text(s)) => (s.chars().count() <= *length as usize).then_some(Value::Text(s)),
+      // INFO: the padding isn't kept since, like in SQL, it counts neither in comparisons and keys nor in LENGTH
+      (DataType::Char(length), Value::Text(s)) => {
+        let s = s.trim_end_matches(' ');
+        (s.chars().count() <= *length as usize).then(|| Value::Text(s.to_string()))
+      }
+      (_, value) => Some(value),
+    }
+  }
+}
+
+impl fmt::Display for DataType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DataType::SmallInt => write!(f, "SMALLINT"),
+      DataType::Int => write!(f, "INT"),
+      DataType::BigInt => write!(f, "BIGINT"),
+      DataType::Text => write!(f, "TEXT"),
+      DataType::Varchar(length) => write!(f, "VARCHAR({})", length),
+      DataType::Char(length) => write!(f, "CHAR({})", length),
+      DataType::Boolean => write!(f, "BOOLEAN"),
+      DataType::Real => write!(f, "REAL"),
+      DataType::Float => write!(f, "FLOAT"),
+      DataType::Decimal(Some((precision, scale))) => write!(f, "DECIMAL({}, {})", precision, scale),
+      DataType::Decimal(None) => write!(f, "DECIMAL"),
+      DataType::Date => write!(f, "DATE"),
+      DataType::DateTime => write!(f, "TIMESTAMP"),
+      DataType::TimestampTz => write!(f, "TIMESTAMPTZ"),
+      DataType::Interval => write!(f, "INTERVAL"),
+      DataType::Json => write!(f, "JSON"),
+      DataType::Bytea => write!(f, "BYTEA"),
+      DataType::Uuid => write!(f, "UUID"),
+      DataType::Null => write!(f, "NULL"),
+    }
+  }
 }
 
 impl Catalog {
@@ -135,12 +739,19 @@ impl Catalog {
   }
 
   pub fn add_column(&mut self, table_name: &str, column: Column) {
-    self.tables.get_mut(table_name).map(|table| table.add_column(column));
+    if let Some(table) = self.tables.get_mut(table_name) {
+      table.add_column(column);
+    }
   }
 
+  // INFO: The column keeps its id, it is renamed first when the updated column has another name
   pub fn update_column(&mut self, updated_column: Column, column_name: &str, table_name: &str) {
-    if let Some(column) = self.tables.get_mut(table_name).and_then(|table| table.get_mut_column(column_name)) {
-      *column = updated_column
+    if let Some(table) = self.tables.get_mut(table_name) {
+      if updated_column.name != column_name {
+        table.rename_column(column_name, &updated_column.name);
+      }
+
+      table.replace_column(updated_column);
     }
   }
 
@@ -153,4 +764,54 @@ impl Catalog {
   pub fn remove_column(&mut self, table_name: &str, column_name: &str) {
     self.tables.get_mut(table_name).map(|table| table.remove_column(column_name));
   }
+
+  // INFO: Foreign keys of every table that reference the given table, each one with the name of its table and its own name
+  pub fn referencing_constraints(&self, table_name: &str) -> Vec<(String, String, Reference)> {
+    let mut references: Vec<(String, String, Reference)> = self
+      .tables
+      .values()
+      .flat_map(|table| table.foreign_keys().map(move |(name, reference)| (table.name.clone(), name.clone(), reference.clone())))
+      .filter(|(_, _, reference)| reference.table_name == table_name)
+      .collect();
+    references.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+    references
+  }
+
+  // INFO: Foreign keys of other tables follow the table to its new name
+  pub fn rename_table(&mut self, table_name: &str, new_name: &str) {
+    if let Some(mut table) = self.tables.remove(table_name) {
+      table.name = new_name.to_string();
+      self.tables.insert(new_name.to_string(), table);
+    }
+
+    for reference in self.references_mut().filter(|reference| reference.table_name == table_name) {
+      reference.table_name = new_name.to_string();
+    }
+  }
+
+  // INFO: Foreign keys of other tables follow a referenced column to its new name
+  pub fn rename_referenced_column(&mut self, table_name: &str, column_name: &str, new_name: &str) {
+    for reference in self.references_mut().filter(|reference| reference.table_name == table_name) {
+      for column in reference.referenced_columns.iter_mut().filter(|column| *column == column_name) {
+        *column = new_name.to_string();
+      }
+    }
+  }
+
+  fn references_mut(&mut self) -> impl Iterator<Item = &mut Reference> {
+    self.tables.values_mut().flat_map(|table| &mut table.constraints).filter_map(|constraint| match &mut constraint.kind {
+      ConstraintKind::ForeignKey(reference) => Some(reference),
+      _ => None,
+    })
+  }
+
+  // INFO: Index files are named after the index so its name can't be used twice in the whole database
+  pub fn has_index(&self, index_name: &str) -> bool {
+    self.index_table(index_name).is_some()
+  }
+
+  pub fn index_table(&self, index_name: &str) -> Option<&Table> {
+    self.tables.values().find(|table| table.indexes.iter().any(|index| index.name == index_name))
+  }
 }
@@ -0,0 +1,80 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// INFO: 16 bytes in the order they are written, so ordering the bytes orders the text as well
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+  pub fn from_bytes(bytes: [u8; 16]) -> Self {
+    Uuid(bytes)
+  }
+
+  pub fn as_bytes(&self) -> &[u8; 16] {
+    &self.0
+  }
+
+  // INFO: Random version 4 UUID. The bits come from hashers the standard library seeds with random keys of the
+  // operating system, a counter keeps two calls in the same nanosecond apart
+  pub fn new_v4() -> Self {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos());
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut bytes = [0; 16];
+    for half in bytes.chunks_mut(8) {
+      let mut hasher = RandomState::new().build_hasher();
+      hasher.write_u128(nanos);
+      hasher.write_u64(count);
+      half.copy_from_slice(&hasher.finish().to_be_bytes());
+    }
+
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    Uuid(bytes)
+  }
+}
+
+// INFO: 32 hex digits in any case, with or without the hyphens of `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` and braces
+impl FromStr for Uuid {
+  type Err = ();
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let text = text.trim();
+    let text = text.strip_prefix('{').and_then(|text| text.strip_suffix('}')).unwrap_or(text);
+
+    let digits = match text.len() {
+      32 => text.to_string(),
+      36 if [8, 13, 18, 23].iter().all(|&i| text.as_bytes()[i] == b'-') => text.replace('-', ""),
+      _ => return Err(()),
+    };
+    if digits.len() != 32 || !digits.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+      return Err(());
+    }
+
+    let mut bytes = [0; 16];
+    for (byte, pair) in bytes.iter_mut().zip(digits.as_bytes().chunks(2)) {
+      *byte = u8::from_str_radix(std::str::from_utf8(pair).map_err(|_| ())?, 16).map_err(|_| ())?;
+    }
+
+    Ok(Uuid(bytes))
+  }
+}
+
+impl fmt::Display for Uuid {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, byte) in self.0.iter().enumerate() {
+      if [4, 6, 8, 10].contains(&i) {
+        write!(f, "-")?;
+      }
+      write!(f, "{:02x}", byte)?;
+    }
+
+    Ok(())
+  }
+}
@@ -1,3 +1,6 @@
 pub const PAGE_SIZE: usize = 4096;
 pub const BUFFER_POOL_SIZE: usize = 100;
-pub const CATALOG_FILE: &str = "catalog.bin";
\ No newline at end of file
+pub const CATALOG_FILE: &str = "catalog.bin";
+pub const TABLE_FILE_EXTENSION: &str = "tbl";
+pub const INDEX_FILE_EXTENSION: &str = "idx";
+pub const MAX_RECURSIVE_ITERATIONS: usize = 1000;
@@ -0,0 +1,168 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::sql::catalog::{Decimal, Value};
+use crate::sql::function::udf::AggregateState;
+use crate::sql::planner::plan::AggregateFunction;
+
+use super::error::ExecutionError;
+
+// INFO: Running state of one aggregate call for one group
+#[derive(Debug)]
+pub struct Accumulator {
+  function: AggregateFunction,
+  state: State,
+  seen: Option<HashSet<Vec<Value>>>, // INFO: arguments already accumulated by a DISTINCT aggregate
+}
+
+#[derive(Debug)]
+enum State {
+  Count(i64),
+  Sum(Option<Value>),
+  Avg(Option<Value>, usize), // INFO: the sum of the values, integers are summed as floats
+  Extreme(Option<Value>),    // INFO: the smallest or the largest value so far
+  User(AggregateState),
+}
+
+impl Accumulator {
+  pub fn new(function: AggregateFunction, distinct: bool) -> Self {
+    let state = match &function {
+      AggregateFunction::Count => State::Count(0),
+      AggregateFunction::Sum => State::Sum(None),
+      AggregateFunction::Avg => State::Avg(None, 0),
+      AggregateFunction::Min | AggregateFunction::Max => State::Extreme(None),
+      AggregateFunction::User(aggregate) => State::User(aggregate.init()),
+    };
+
+    Self { function, state, seen: distinct.then(HashSet::new) }
+  }
+
+  // INFO: Rows with a NULL argument are skipped, COUNT(*) has no arguments so it counts every row
+  pub fn accumulate(&mut self, args: Vec<Value>) -> Result<(), ExecutionError> {
+    if args.iter().any(Value::is_null) {
+      return Ok(());
+    }
+
+    if let Some(seen) = &mut self.seen {
+      if !seen.insert(args.clone()) {
+        return Ok(());
+      }
+    }
+
+    let function = &self.function;
+
+    if let (State::User(state), AggregateFunction::User(aggregate)) = (&mut self.state, function) {
+      return aggregate.accumulate(state, args);
+    }
+
+    match (&mut self.state, args.into_iter().next()) {
+      (State::Count(count), _) => *count = count.checked_add(1).ok_or(ExecutionError::NumericOverflow)?,
+      (_, None) => {}
+      (State::Sum(sum), Some(value)) => *sum = Some(add(function, sum.take(), value)?),
+      (State::Avg(total, count), Some(value)) => {
+        let value = match value {
+          Value::Int(i) => Value::Float(i as f64),
+          value => value,
+        };
+        *total = Some(add(function, total.take(), value)?);
+        *count += 1;
+      }
+      (State::Extreme(current), Some(value)) => {
+        let replace = match current {
+          None => true,
+          Some(current) => {
+            let ordering = value.compare(current).ok_or_else(|| invalid_argument(function, &value))?;
+            match function {
+              AggregateFunction::Min => ordering == Ordering::Less,
+              _ => ordering == Ordering::Greater,
+            }
+          }
+        };
+
+        if replace {
+          *current = Some(value);
+        }
+      }
+      (State::User(_), _) => unreachable!("user state belongs to a user aggregate"),
+    }
+
+    Ok(())
+  }
+
+  pub fn finalize(self) -> Result<Value, ExecutionError> {
+    result(&self.function, self.state)
+  }
+
+  // INFO: The result for the rows accumulated so far, more rows can be accumulated afterwards
+  pub fn current(&self) -> Result<Value, ExecutionError> {
+    let state = match (&self.state, &self.function) {
+      (State::Count(count), _) => State::Count(*count),
+      (State::Sum(sum), _) => State::Sum(sum.clone()),
+      (State::Avg(total, count), _) => State::Avg(total.clone(), *count),
+      (State::Extreme(value), _) => State::Extreme(value.clone()),
+      (State::User(state), AggregateFunction::User(aggregate)) => State::User(aggregate.copy(state)),
+      (State::User(_), _) => unreachable!("user state belongs to a user aggregate"),
+    };
+
+    result(&self.function, state)
+  }
+}
+
+fn result(function: &AggregateFunction, state: State) -> Result<Value, ExecutionError> {
+  let value = match state {
+    State::Count(count) => Value::Int(count),
+    State::Avg(Some(Value::Float(total)), count) => Value::Float(total / count as f64),
+    State::Avg(Some(Value::Decimal(total)), count) => {
+      Value::Decimal(total.checked_div(Decimal::from(count as i64)).ok_or(ExecutionError::NumericOverflow)?)
+    }
+    State::Avg(Some(Value::Interval(total)), count) => {
+      Value::Interval(total.checked_div(count as f64).ok_or(ExecutionError::DateTimeOverflow)?)
+    }
+    State::Avg(..) => Value::Null,
+    State::Sum(value) | State::Extreme(value) => value.unwrap_or(Value::Null),
+    State::User(state) => match function {
+      AggregateFunction::User(aggregate) => return aggregate.finalize(state),
+      _ => unreachable!("user state belongs to a user aggregate"),
+    },
+  };
+
+  Ok(value)
+}
+
+// INFO: Adds a value to the running sum, the sum becomes a FLOAT once a FLOAT is added and a DECIMAL once a DECIMAL is
+fn add(function: &AggregateFunction, sum: Option<Value>, value: Value) -> Result<Value, ExecutionError> {
+  let sum = match (sum, value) {
+    (None, value @ (Value::Int(_) | Value::Float(_) | Value::Decimal(_) | Value::Interval(_))) => value,
+    (Some(Value::Int(a)), Value::Int(b)) => Value::Int(a.checked_add(b).ok_or(ExecutionError::NumericOverflow)?),
+    (Some(Value::Float(a)), Value::Float(b)) => Value::Float(a + b),
+    (Some(Value::Float(a)), b @ (Value::Int(_) | Value::Decimal(_))) => Value::Float(a + to_float(&b)),
+    (Some(a @ (Value::Int(_) | Value::Decimal(_))), Value::Float(b)) => Value::Float(to_float(&a) + b),
+    (Some(a @ (Value::Int(_) | Value::Decimal(_))), b @ (Value::Int(_) | Value::Decimal(_))) => {
+      Value::Decimal(to_decimal(&a).checked_add(to_decimal(&b)).ok_or(ExecutionError::NumericOverflow)?)
+    }
+    (Some(Value::Interval(a)), Value::Interval(b)) => Value::Interval(a.checked_add(b).ok_or(ExecutionError::DateTimeOverflow)?),
+    (_, value) => return Err(invalid_argument(function, &value)),
+  };
+
+  Ok(sum)
+}
+
+fn to_float(value: &Value) -> f64 {
+  match value {
+    Value::Int(i) => *i as f64,
+    Value::Decimal(d) => d.to_f64(),
+    _ => f64::NAN,
+  }
+}
+
+fn to_decimal(value: &Value) -> Decimal {
+  match value {
+    Value::Decimal(d) => *d,
+    Value::Int(i) => Decimal::from(*i),
+    _ => Decimal::from(0),
+  }
+}
+
+fn invalid_argument(function: &AggregateFunction, value: &Value) -> ExecutionError {
+  ExecutionError::InvalidAggregateArgument { function: function.to_string(), data_type: value.data_type() }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+use std::io;
+
+use crate::sql::catalog::{DataType, Value};
+use crate::sql::constants::MAX_RECURSIVE_ITERATIONS;
+use crate::sql::planner::error::PlannerError;
+
+#[derive(Debug)]
+pub enum ExecutionError {
+  Storage(String),
+  TableNotFound(String),
+  ColumnNotFound(String),
+  AmbiguousColumn(String),
+  TypeMismatch { column: String, expected: DataType, value: Value },
+  OutOfRange { column: String, data_type: DataType, value: Value },
+  NotNullViolation { table: String, column: String },
+  CheckViolation { constraint: String, column: String, value: Value },
+  UniqueViolation { constraint: String, columns: Vec<String>, key: Vec<Value> },
+  RowAffectedTwice(String),
+  RowCheckViolation { table: String, constraint: String },
+  ForeignKeyViolation { constraint: String, columns: Vec<String>, key: Vec<Value>, table: String },
+  ReferencedKey { constraint: String, columns: Vec<String>, key: Vec<Value>, table: String, referencing: String },
+  InvalidOperands { operator: String, left: DataType, right: DataType },
+  DivisionByZero,
+  NumericOverflow,
+  DateTimeOverflow,
+  InvalidLimit(Value),
+  InvalidCast { value: Value, data_type: DataType },
+  InvalidPattern(String),
+  UnresolvedSubquery,
+  InvalidDmlSource,
+  UnresolvedAggregate(String),
+  UnresolvedWindow(String),
+  InvalidAggregateArgument { function: String, data_type: DataType },
+  InvalidFunctionArgument { function: String, data_type: DataType },
+  InvalidArgumentValue { function: String, value: Value },
+  InvalidFunctionResult { function: String, expected: DataType, value: Value },
+  Function { function: String, message: String },
+  SubqueryReturnedMultipleRows,
+  RecursionLimitExceeded(String),
+  Planning(PlannerError),
+}
+
+impl fmt::Display for ExecutionError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ExecutionError::Storage(message) => write!(f, "Execution Error: Storage failure: {}.", message),
+      ExecutionError::TableNotFound(table) => write!(f, "Execution Error: Table '{}' does not exist.", table),
+      ExecutionError::ColumnNotFound(column) => write!(f, "Execution Error: Column '{}' does not exist.", column),
+      ExecutionError::AmbiguousColumn(column) => write!(f, "Execution Error: Column '{}' is ambiguous.", column),
+      ExecutionError::TypeMismatch { column, expected, value } => {
+        write!(f, "Execution Error: Value {} can't be stored in column '{}' of type {}.", value, column, expected)
+      }
+      ExecutionError::OutOfRange { column, data_type, value } if data_type.value_type() == DataType::Text => {
+        write!(f, "Execution Error: Value '{}' is too long for column '{}' of type {}.", value, column, data_type)
+      }
+      ExecutionError::OutOfRange { column, data_type, value } => {
+        write!(f, "Execution Error: Value {} is out of range for column '{}' of type {}.", value, column, data_type)
+      }
+      ExecutionError::NotNullViolation { table, column } => {
+        write!(f, "Execution Error: NULL value in column '{}' of table '{}' violates the NOT NULL constraint.", column, table)
+      }
+      ExecutionError::CheckViolation { constraint, column, value } => {
+        write!(f, "Execution Error: Value {} of column '{}' violates check constraint '{}'.", value, column, constraint)
+      }
+      ExecutionError::UniqueViolation { constraint, columns, key } => {
+        let columns = columns.join(", ");
+        write!(f, "Execution Error: Key ({}) = ({}) violates unique constraint '{}'.", columns, values(key), constraint)
+      }
+      ExecutionError::RowCheckViolation { table, constraint } => {
+        write!(f, "Execution Error: Row of table '{}' violates check constraint '{}'.", table, constraint)
+      }
+      ExecutionError::ForeignKeyViolation { constraint, columns, key, table } => write!(
+        f,
+        "Execution Error: Key ({}) = ({}) is not present in table '{}', it violates foreign key constraint '{}'.",
+        columns.join(", "),
+        values(key),
+        table,
+        constraint
+      ),
+      ExecutionError::ReferencedKey { constraint, columns, key, table, referencing } => write!(
+        f,
+        "Execution Error: Key ({}) = ({}) of table '{}' is still referenced from table '{}' by foreign key constraint '{}'.",
+        columns.join(", "),
+        values(key),
+        table,
+        referencing,
+        constraint
+      ),
+      ExecutionError::RowAffectedTwice(table) => write!(
+        f,
+        "Execution Error: ON CONFLICT DO UPDATE can't affect a row of table '{}' a second time, the proposed rows have the same key.",
+        table
+      ),
+      ExecutionError::InvalidOperands { operator, left, right } => {
+        write!(f, "Execution Error: Operator {} can't be applied to {} and {}.", operator, left, right)
+      }
+      ExecutionError::DivisionByZero => write!(f, "Execution Error: Division by zero."),
+      ExecutionError::NumericOverflow => write!(f, "Execution Error: Numeric value out of range."),
+      ExecutionError::DateTimeOverflow => write!(f, "Execution Error: Date, timestamp or interval value out of range."),
+      ExecutionError::InvalidLimit(value) => write!(f, "Execution Error: {} is not a valid row count.", value),
+      ExecutionError::InvalidCast { value, data_type } => {
+        write!(f, "Execution Error: Value {} can't be cast to type {}.", value, data_type)
+      }
+      ExecutionError::InvalidPattern(pattern) => {
+        write!(f, "Execution Error: LIKE pattern '{}' must not end with the escape character.", pattern)
+      }
+      ExecutionError::UnresolvedSubquery => write!(f, "Execution Error: Subquery can't be used here."),
+      ExecutionError::InvalidDmlSource => write!(f, "Execution Error: UPDATE and DELETE can only read rows from a table scan."),
+      ExecutionError::UnresolvedAggregate(function) => {
+        write!(f, "Execution Error: Aggregate function {} can't be used here.", function)
+      }
+      ExecutionError::UnresolvedWindow(function) => {
+        write!(f, "Execution Error: Window function {} can't be used here.", function)
+      }
+      ExecutionError::InvalidAggregateArgument { function, data_type } => {
+        write!(f, "Execution Error: Aggregate function {} can't be applied to {}.", function, data_type)
+      }
+      ExecutionError::InvalidFunctionArgument { function, data_type } => {
+        write!(f, "Execution Error: Function {} can't be applied to {}.", function, data_type)
+      }
+      ExecutionError::InvalidArgumentValue { function, value } => {
+        write!(f, "Execution Error: Value {} is not a valid argument of function {}.", value, function)
+      }
+      ExecutionError::InvalidFunctionResult { function, expected, value } => {
+        write!(f, "Execution Error: Function {} returned {} but it is declared to return {}.", function, value, expected)
+      }
+      ExecutionError::Function { function, message } => write!(f, "Execution Error: Function {} failed: {}.", function, message),
+      ExecutionError::RecursionLimitExceeded(name) => {
+        write!(f, "Execution Error: Recursive query '{}' did not finish after {} iterations.", name, MAX_RECURSIVE_ITERATIONS)
+      }
+      ExecutionError::SubqueryReturnedMultipleRows => {
+        write!(f, "Execution Error: More than one row returned by a subquery used as an expression.")
+      }
+      ExecutionError::Planning(error) => write!(f, "{}", error),
+    }
+  }
+}
+
+impl std::error::Error for ExecutionError {}
+
+fn values(key: &[Value]) -> String {
+  key.iter().map(Value::to_string).collect::<Vec<_>>().join(", ")
+}
+
+impl From<io::Error> for ExecutionError {
+  fn from(error: io::Error) -> Self {
+    ExecutionError::Storage(error.to_string())
+  }
+}
+
+impl From<PlannerError> for ExecutionError {
+  fn from(error: PlannerError) -> Self {
+    ExecutionError::Planning(error)
+  }
+}
+
+impl From<bincode::Error> for ExecutionError {
+  fn from(error: bincode::Error) -> Self {
+    ExecutionError::Storage(error.to_string())
+  }
+}
@@ -0,0 +1,430 @@
+use chrono::Days;
+use std::cmp::Ordering;
+
+use crate::sql::catalog::{Decimal, Interval, Value};
+use crate::sql::planner::plan::Expression;
+
+use super::error::ExecutionError;
+use super::result::{resolve_column, Field};
+
+// INFO: Evaluates the expression against one row, NULL follows the SQL three valued logic
+pub fn evaluate(expression: &Expression, columns: &[Field], row: &[Value]) -> Result<Value, ExecutionError> {
+  match expression {
+    Expression::Identifier(name) => Ok(row[resolve_column(columns, name)?].clone()),
+    Expression::Constant(value) => Ok(Value::from(value.clone())),
+    Expression::DataType(data_type) => Err(ExecutionError::ColumnNotFound(data_type.to_string())),
+    Expression::Alias(expression, _) => evaluate(expression, columns, row),
+    Expression::Outer(name, _) => Err(ExecutionError::ColumnNotFound(name.clone())),
+    Expression::Subquery(_) | Expression::Exists(_) | Expression::InSubquery(..) => Err(ExecutionError::UnresolvedSubquery),
+    Expression::Aggregate(function, ..) => Err(ExecutionError::UnresolvedAggregate(function.to_string())),
+    Expression::Window(function, ..) => Err(ExecutionError::UnresolvedWindow(function.to_string())),
+    Expression::Function(function, args) => {
+      function.invoke(args.iter().map(|arg| evaluate(arg, columns, row)).collect::<Result<_, _>>()?)
+    }
+    Expression::InList(expression, list) => in_list(expression, list, columns, row),
+    Expression::Between(expression, low, high) => {
+      let value = evaluate(expression, columns, row)?;
+      let above = compare_values(&value, &evaluate(low, columns, row)?, "BETWEEN", |ordering| ordering != Ordering::Less)?;
+      let below = compare_values(&value, &evaluate(high, columns, row)?, "BETWEEN", |ordering| ordering != Ordering::Greater)?;
+
+      match (as_boolean(above, "BETWEEN")?, as_boolean(below, "BETWEEN")?) {
+        (Some(false), _) | (_, Some(false)) => Ok(Value::Boolean(false)),
+        (Some(true), Some(true)) => Ok(Value::Boolean(true)),
+        _ => Ok(Value::Null),
+      }
+    }
+    Expression::Like(expression, pattern, escape) => like(expression, pattern, *escape, false, columns, row),
+    Expression::ILike(expression, pattern, escape) => like(expression, pattern, *escape, true, columns, row),
+
+    Expression::IsNull(expression) => Ok(Value::Boolean(evaluate(expression, columns, row)?.is_null())),
+    Expression::Case(branches, default) => {
+      for (condition, result) in branches {
+        if is_true(&evaluate(condition, columns, row)?) {
+          return evaluate(result, columns, row);
+        }
+      }
+
+      default.as_ref().map_or(Ok(Value::Null), |default| evaluate(default, columns, row))
+    }
+    Expression::Coalesce(expressions) => {
+      for expression in expressions {
+        let value = evaluate(expression, columns, row)?;
+        if !value.is_null() {
+          return Ok(value);
+        }
+      }
+
+      Ok(Value::Null)
+    }
+    Expression::NullIf(left, right) => {
+      let (left, right) = (evaluate(left, columns, row)?, evaluate(right, columns, row)?);
+
+      match left.compare(&right) {
+        Some(Ordering::Equal) => Ok(Value::Null),
+        Some(_) => Ok(left),
+        None if left.is_null() || right.is_null() => Ok(left),
+        None => Err(ExecutionError::InvalidOperands {
+          operator: "NULLIF".to_string(),
+          left: left.data_type(),
+          right: right.data_type(),
+        }),
+      }
+    }
+    Expression::Cast(expression, data_type) => {
+      let value = evaluate(expression, columns, row)?;
+      value.clone().cast(data_type).ok_or_else(|| ExecutionError::InvalidCast { value, data_type: data_type.clone() })
+    }
+
+    Expression::Equal(left, right) => compare(left, right, columns, row, "=", |ordering| ordering == Ordering::Equal),
+    Expression::NotEqual(left, right) => compare(left, right, columns, row, "!=", |ordering| ordering != Ordering::Equal),
+    Expression::GreaterThan(left, right) => compare(left, right, columns, row, ">", |ordering| ordering == Ordering::Greater),
+    Expression::GreaterThanOrEqual(left, right) => {
+      compare(left, right, columns, row, ">=", |ordering| ordering != Ordering::Less)
+    }
+    Expression::LessThan(left, right) => compare(left, right, columns, row, "<", |ordering| ordering == Ordering::Less),
+    Expression::LessThanOrEqual(left, right) => {
+      compare(left, right, columns, row, "<=", |ordering| ordering != Ordering::Greater)
+    }
+
+    Expression::Add(left, right) => arithmetic(left, right, columns, row, "+"),
+    Expression::Subtract(left, right) => arithmetic(left, right, columns, row, "-"),
+    Expression::Multiply(left, right) => arithmetic(left, right, columns, row, "*"),
+    Expression::Divide(left, right) => arithmetic(left, right, columns, row, "/"),
+
+    Expression::And(left, right) => {
+      match (as_boolean(evaluate(left, columns, row)?, "AND")?, as_boolean(evaluate(right, columns, row)?, "AND")?) {
+        (Some(false), _) | (_, Some(false)) => Ok(Value::Boolean(false)),
+        (Some(true), Some(true)) => Ok(Value::Boolean(true)),
+        _ => Ok(Value::Null),
+      }
+    }
+    Expression::Not(expression) => match as_boolean(evaluate(expression, columns, row)?, "NOT")? {
+      Some(b) => Ok(Value::Boolean(!b)),
+      None => Ok(Value::Null),
+    },
+    Expression::Negate(expression) => match evaluate(expression, columns, row)? {
+      Value::Int(i) => i.checked_neg().map(Value::Int).ok_or(ExecutionError::NumericOverflow),
+      Value::Float(f) => Ok(Value::Float(-f)),
+      Value::Decimal(d) => Ok(Value::Decimal(-d)),
+      Value::Interval(i) => Ok(Value::Interval(-i)),
+      Value::Null => Ok(Value::Null),
+      value => Err(ExecutionError::InvalidOperands {
+        operator: "-".to_string(),
+        left: value.data_type(),
+        right: crate::sql::catalog::DataType::Int,
+      }),
+    },
+    Expression::Or(left, right) => {
+      match (as_boolean(evaluate(left, columns, row)?, "OR")?, as_boolean(evaluate(right, columns, row)?, "OR")?) {
+        (Some(true), _) | (_, Some(true)) => Ok(Value::Boolean(true)),
+        (Some(false), Some(false)) => Ok(Value::Boolean(false)),
+        _ => Ok(Value::Null),
+      }
+    }
+  }
+}
+
+// INFO: Only TRUE passes a filter, both FALSE and NULL reject the row
+pub fn is_true(value: &Value) -> bool {
+  matches!(value, Value::Boolean(true))
+}
+
+fn as_boolean(value: Value, operator: &str) -> Result<Option<bool>, ExecutionError> {
+  match value {
+    Value::Boolean(b) => Ok(Some(b)),
+    Value::Null => Ok(None),
+    value => Err(ExecutionError::InvalidOperands {
+      operator: operator.to_string(),
+      left: value.data_type(),
+      right: crate::sql::catalog::DataType::Boolean,
+    }),
+  }
+}
+
+// INFO: TRUE when a list item is equal to the value, otherwise NULL if the value or an item is NULL and FALSE if not
+fn in_list(expression: &Expression, list: &[Expression], columns: &[Field], row: &[Value]) -> Result<Value, ExecutionError> {
+  let value = evaluate(expression, columns, row)?;
+
+  if value.is_null() {
+    return Ok(Value::Null);
+  }
+
+  let mut found_null = false;
+  for item in list {
+    let item = evaluate(item, columns, row)?;
+
+    match value.compare(&item) {
+      Some(Ordering::Equal) => return Ok(Value::Boolean(true)),
+      Some(_) => {}
+      None if item.is_null() => found_null = true,
+      None => {
+        return Err(ExecutionError::InvalidOperands {
+          operator: "IN".to_string(),
+          left: value.data_type(),
+          right: item.data_type(),
+        })
+      }
+    }
+  }
+
+  Ok(if found_null { Value::Null } else { Value::Boolean(false) })
+}
+
+fn compare(
+  left: &Expression,
+  right: &Expression,
+  columns: &[Field],
+  row: &[Value],
+  operator: &str,
+  predicate: impl Fn(Ordering) -> bool,
+) -> Result<Value, ExecutionError> {
+  compare_values(&evaluate(left, columns, row)?, &evaluate(right, columns, row)?, operator, predicate)
+}
+
+fn compare_values(
+  left: &Value,
+  right: &Value,
+  operator: &str,
+  predicate: impl Fn(Ordering) -> bool,
+) -> Result<Value, ExecutionError> {
+  if left.is_null() || right.is_null() {
+    return Ok(Value::Null);
+  }
+
+  match left.compare(right) {
+    Some(ordering) => Ok(Value::Boolean(predicate(ordering))),
+    None => {
+      Err(ExecutionError::InvalidOperands { operator: operator.to_string(), left: left.data_type(), right: right.data_type() })
+    }
+  }
+}
+
+fn like(
+  expression: &Expression,
+  pattern: &Expression,
+  escape: Option<char>,
+  case_insensitive: bool,
+  columns: &[Field],
+  row: &[Value],
+) -> Result<Value, ExecutionError> {
+  match (evaluate(expression, columns, row)?, evaluate(pattern, columns, row)?) {
+    (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+    (Value::Text(text), Value::Text(pattern)) => {
+      let text: Vec<char> = if case_insensitive { text.to_lowercase().chars().collect() } else { text.chars().collect() };
+      let pattern = like_pattern(&pattern, escape, case_insensitive)?;
+
+      Ok(Value::Boolean(like_matches(&text, &pattern)))
+    }
+    (left, right) => Err(ExecutionError::InvalidOperands {
+      operator: if case_insensitive { "ILIKE" } else { "LIKE" }.to_string(),
+      left: left.data_type(),
+      right: right.data_type(),
+    }),
+  }
+}
+
+enum LikeToken {
+  Char(char),
+  AnyChar,   // INFO: `_`
+  AnyString, // INFO: `%`
+}
+
+fn like_pattern(pattern: &str, escape: Option<char>, case_insensitive: bool) -> Result<Vec<LikeToken>, ExecutionError> {
+  let mut tokens = Vec::new();
+  let mut chars = pattern.chars();
+
+  while let Some(ch) = chars.next() {
+    let ch = match ch {
+      '%' => {
+        tokens.push(LikeToken::AnyString);
+        continue;
+      }
+      '_' => {
+        tokens.push(LikeToken::AnyChar);
+        continue;
+      }
+      ch if Some(ch) == escape => chars.next().ok_or_else(|| ExecutionError::InvalidPattern(pattern.to_string()))?,
+      ch => ch,
+    };
+
+    if case_insensitive {
+      tokens.extend(ch.to_lowercase().map(LikeToken::Char));
+    } else {
+      tokens.push(LikeToken::Char(ch));
+    }
+  }
+
+  Ok(tokens)
+}
+
+// INFO: Wildcard matching that backtracks only to the last `%`
+fn like_matches(text: &[char], pattern: &[LikeToken]) -> bool {
+  let (mut t, mut p) = (0, 0);
+  let mut backtrack: Option<(usize, usize)> = None;
+
+  while t < text.len() {
+    match pattern.get(p) {
+      Some(LikeToken::AnyString) => {
+        backtrack = Some((p, t));
+        p += 1;
+        continue;
+      }
+      Some(LikeToken::AnyChar) => {
+        (t, p) = (t + 1, p + 1);
+        continue;
+      }
+      Some(LikeToken::Char(ch)) if *ch == text[t] => {
+        (t, p) = (t + 1, p + 1);
+        continue;
+      }
+      _ => {}
+    }
+
+    match backtrack {
+      Some((star, start)) => {
+        backtrack = Some((star, start + 1));
+        (t, p) = (start + 1, star + 1);
+      }
+      None => return false,
+    }
+  }
+
+  pattern[p..].iter().all(|token| matches!(token, LikeToken::AnyString))
+}
+
+fn arithmetic(
+  left: &Expression,
+  right: &Expression,
+  columns: &[Field],
+  row: &[Value],
+  operator: &str,
+) -> Result<Value, ExecutionError> {
+  let left = evaluate(left, columns, row)?;
+  let right = evaluate(right, columns, row)?;
+
+  match (&left, &right) {
+    (Value::Null, _) | (_, Value::Null) => Ok(Value::Null),
+    (Value::Int(a), Value::Int(b)) => {
+      let result = match operator {
+        "+" => a.checked_add(*b),
+        "-" => a.checked_sub(*b),
+        "*" => a.checked_mul(*b),
+        _ if *b == 0 => return Err(ExecutionError::DivisionByZero),
+        _ => a.checked_div(*b),
+      };
+
+      result.map(Value::Int).ok_or(ExecutionError::NumericOverflow)
+    }
+    (Value::Int(_) | Value::Float(_) | Value::Decimal(_), Value::Int(_) | Value::Float(_) | Value::Decimal(_)) => {
+      if let Some((a, b)) = decimal_operands(&left, &right) {
+        let result = match operator {
+          "+" => a.checked_add(b),
+          "-" => a.checked_sub(b),
+          "*" => a.checked_mul(b),
+          _ if b.is_zero() => return Err(ExecutionError::DivisionByZero),
+          _ => a.checked_div(b),
+        };
+
+        return result.map(Value::Decimal).ok_or(ExecutionError::NumericOverflow);
+      }
+
+      let (a, b) = (to_float(&left), to_float(&right));
+
+      match operator {
+        "+" => Ok(Value::Float(a + b)),
+        "-" => Ok(Value::Float(a - b)),
+        "*" => Ok(Value::Float(a * b)),
+        _ if b == 0.0 => Err(ExecutionError::DivisionByZero),
+        _ => Ok(Value::Float(a / b)),
+      }
+    }
+    _ => temporal_arithmetic(&left, &right, operator),
+  }
+}
+
+// INFO: A date plus or minus a number of days stays a date and two dates differ by a number of days. A date, timestamp
+// or TIMESTAMPTZ moved by an interval keeps its type, except a date which becomes a timestamp
+fn temporal_arithmetic(left: &Value, right: &Value, operator: &str) -> Result<Value, ExecutionError> {
+  let result = match (left, right, operator) {
+    (Value::Date(date), days, "+" | "-") | (days, Value::Date(date), "+") if whole_days(days).is_some() => {
+      let days = whole_days(days).unwrap_or_default();
+      match (days < 0) != (operator == "-") {
+        true => date.checked_sub_days(Days::new(days.unsigned_abs())),
+        false => date.checked_add_days(Days::new(days.unsigned_abs())),
+      }
+      .map(Value::Date)
+    }
+    (Value::Date(a), Value::Date(b), "-") => Some(Value::Int((*a - *b).num_days())),
+    (_, Value::Interval(interval), "+" | "-") if left.to_naive().is_some() => {
+      shift(left, if operator == "-" { -*interval } else { *interval })
+    }
+    (Value::Interval(interval), _, "+") if right.to_naive().is_some() => shift(right, *interval),
+    (_, _, "-") if left.to_naive().is_some() && right.to_naive().is_some() => {
+      left.to_naive().zip(right.to_naive()).and_then(|(a, b)| Interval::between(a, b)).map(Value::Interval)
+    }
+    (Value::Interval(a), Value::Interval(b), "+") => a.checked_add(*b).map(Value::Interval),
+    (Value::Interval(a), Value::Interval(b), "-") => a.checked_sub(*b).map(Value::Interval),
+    (Value::Interval(interval), number, "*") | (number, Value::Interval(interval), "*") if is_number(number) => {
+      interval.checked_mul(to_float(number)).map(Value::Interval)
+    }
+    (Value::Interval(_), number, "/") if is_number(number) && to_float(number) == 0.0 => {
+      return Err(ExecutionError::DivisionByZero)
+    }
+    (Value::Interval(interval), number, "/") if is_number(number) => interval.checked_div(to_float(number)).map(Value::Interval),
+    _ => {
+      return Err(ExecutionError::InvalidOperands {
+        operator: operator.to_string(),
+        left: left.data_type(),
+        right: right.data_type(),
+      })
+    }
+  };
+
+  result.ok_or(ExecutionError::DateTimeOverflow)
+}
+
+// INFO: The interval is added in UTC to a TIMESTAMPTZ
+fn shift(value: &Value, interval: Interval) -> Option<Value> {
+  match value {
+    Value::TimestampTz(t) => interval.add_to(t.naive_utc()).map(|t| Value::TimestampTz(t.and_utc())),
+    value => interval.add_to(value.to_naive()?).map(Value::Timestamp),
+  }
+}
+
+fn is_number(value: &Value) -> bool {
+  matches!(value, Value::Int(_) | Value::Float(_) | Value::Decimal(_))
+}
+
+// INFO: A number without a fraction counts as a whole number of days
+fn whole_days(value: &Value) -> Option<i64> {
+  match value {
+    Value::Int(i) => Some(*i),
+    Value::Float(f) if f.fract() == 0.0 && f.abs() <= i64::MAX as f64 => Some(*f as i64),
+    Value::Decimal(d) => d.to_i64(),
+    _ => None,
+  }
+}
+
+fn to_float(value: &Value) -> f64 {
+  match value {
+    Value::Int(i) => *i as f64,
+    Value::Float(f) => *f,
+    Value::Decimal(d) => d.to_f64(),
+    _ => f64::NAN,
+  }
+}
+
+// INFO: Arithmetic of a DECIMAL with an INT or another DECIMAL stays exact, None when neither operand is a DECIMAL or
+// one of them is a FLOAT, which makes the result a FLOAT
+pub fn decimal_operands(left: &Value, right: &Value) -> Option<(Decimal, Decimal)> {
+  let decimal = |value: &Value| match value {
+    Value::Int(i) => Some(Decimal::from(*i)),
+    Value::Decimal(d) => Some(*d),
+    _ => None,
+  };
+
+  match (left, right) {
+    (Value::Decimal(_), _) | (_, Value::Decimal(_)) => Some((decimal(left)?, decimal(right)?)),
+    _ => None,
+  }
+}
@@ -1,31 +1,1494 @@
-use crate::storage::manager::BufferPool;
-use super::optimizer::optimizer::{Op, PhysicalPlan};
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Local, Utc};
+
+use super::catalog::{self, ColumnId, ReferentialAction, Value};
+use super::constants::MAX_RECURSIVE_ITERATIONS;
+use super::function::builtin;
+use super::optimizer::optimizer::{KeyRange, Op, Optimizer, PhysicalPlan};
+use super::planner::plan::{
+  self, AlterAction, Check, ConflictAction, Expression, JoinType, Node, Plan, SetOperator, EXCLUDED_TABLE,
+};
+use crate::storage::manager::{BufferPool, RowId, ROW_TOO_LARGE};
+
+use aggregate::Accumulator;
+use error::ExecutionError;
+use evaluator::{evaluate, is_true};
+use result::{resolve_column, Field, ResultSet};
+use window::WindowRow;
+
+pub mod aggregate;
+pub mod error;
+pub mod evaluator;
+pub mod result;
+pub mod window;
+
+// INFO: Columns of a table together with its rows and their locations
+type ScannedRows = (Vec<Field>, Vec<(RowId, Vec<Value>)>);
+
+// INFO: Table that references another one with its column names and the rows that hold a referenced key, next to that key
+type ReferencingRows = (catalog::Table, Vec<String>, Vec<(RowId, Vec<Value>, Vec<Value>)>);
+
+// INFO: ON CONFLICT DO UPDATE of an INSERT, the checks are the CHECK constraints of the table
+struct Upsert<'a> {
+  values: &'a [(String, Expression)],
+  condition: Option<&'a Expression>,
+  checks: &'a [Check],
+}
 
 #[derive(Debug)]
 pub struct Executor<'a> {
-    pub plan: PhysicalPlan,
-    pub buffer_pool: &'a mut BufferPool
+  pub plan: PhysicalPlan,
+  pub buffer_pool: &'a mut BufferPool,
+  working_tables: HashMap<String, ResultSet>, // INFO: rows of the previous round of every recursive CTE that is running
 }
 
 impl<'a> Executor<'a> {
-    pub fn new(plan: PhysicalPlan, bp: &'a mut BufferPool) -> Self {
-        Self { plan, buffer_pool: bp}
+  pub fn new(plan: PhysicalPlan, bp: &'a mut BufferPool) -> Self {
+    Self { plan, buffer_pool: bp, working_tables: HashMap::new() }
+  }
+
+  pub fn execute(&mut self) -> Result<ResultSet, ExecutionError> {
+    let mut plan = self.plan.clone();
+    let started = Utc::now();
+    for expression in plan.expressions_mut() {
+      *expression = statement_time(expression.clone(), &started);
     }
 
-    pub fn execute(&mut self) {
-        match &self.plan.node {
-            Op::CreateTable { table } => {
-                self.buffer_pool.add_table_to_catalog(table.clone());
-                // self.buffer_pool.
+    let result = self.execute_plan(&plan);
+
+    self.buffer_pool.flush()?;
+
+    result
+  }
+
+  fn execute_plan(&mut self, plan: &PhysicalPlan) -> Result<ResultSet, ExecutionError> {
+    match &plan.node {
+      Op::CreateTable { table } => {
+        self.buffer_pool.add_table_to_catalog(table.clone());
+        Ok(ResultSet::default())
+      }
+      Op::DropTable { table_name } => {
+        self.buffer_pool.remove_table_data(table_name)?;
+        self.buffer_pool.remove_table_from_catalog(table_name);
+        Ok(ResultSet::default())
+      }
+      Op::Describe { table } => Ok(describe(&self.get_table(table)?)),
+      Op::AlterTable { table, schema, action, constraints } => {
+        let (columns, rows) = self.scan_rows(table, &None, None, None)?;
+        let names: Vec<String> = columns.into_iter().map(|field| field.name).collect();
+        let new_names = schema.column_names();
+
+        let default = match action {
+          AlterAction::AddColumn { column, default: Some(default) } => {
+            Some(coerce_value(schema, column, self.evaluate(default, &[], &[])?)?)
+          }
+          _ => None,
+        };
+
+        // INFO: stored rows are checked against the new definition of the table, every row replaces itself
+        let mut altered = Vec::with_capacity(rows.len());
+        for (row_id, row) in rows {
+          let row = alter_row(schema, action, default.as_ref(), &names, &new_names, &row)?;
+          self.check_row(schema, &new_names, &constraints.checks, &row)?;
+          altered.push((row_id, row));
+        }
+        let stored: Vec<(Option<RowId>, &[Value])> =
+          altered.iter().map(|(row_id, row)| (Some(*row_id), row.as_slice())).collect();
+        self.check_unique(schema, &new_names, &stored)?;
+        self.check_references(schema, &new_names, &stored.iter().map(|(_, row)| *row).collect::<Vec<_>>())?;
+
+        self.alter_storage(table, schema, action, &new_names, &altered)?;
+        Ok(ResultSet::default())
+      }
+      Op::TableScan { data_source, alias, filter } => self.scan(data_source, alias, None, filter.as_ref()),
+      Op::IndexScan { data_source, alias, index, range, filter } => {
+        self.scan(data_source, alias, Some((index, range)), filter.as_ref())
+      }
+      Op::Values { rows } => {
+        let rows = rows
+          .iter()
+          .map(|row| row.iter().map(|expression| self.evaluate(expression, &[], &[])).collect())
+          .collect::<Result<_, _>>()?;
+
+        Ok(ResultSet::new(Vec::new(), rows))
+      }
+      Op::Filter { condition } => {
+        let mut input = self.execute_child(plan)?;
+        input.rows = self.filter_rows(input.rows, &input.columns, condition)?;
+
+        Ok(input)
+      }
+      Op::Projection { columns } => {
+        let input = self.execute_child(plan)?;
+        self.project(columns, &input.columns, &input.rows)
+      }
+      Op::Sort { order } => {
+        let mut input = self.execute_child(plan)?;
+        let expressions = order.iter().map(|(expression, _)| self.prepare(expression)).collect::<Result<Vec<_>, _>>()?;
+
+        let mut keyed = Vec::with_capacity(input.rows.len());
+        for row in input.rows {
+          let key = expressions
+            .iter()
+            .map(|expression| self.evaluate(expression, &input.columns, &row))
+            .collect::<Result<Vec<_>, _>>()?;
+          keyed.push((key, row));
+        }
+
+        keyed.sort_by(|(a, _), (b, _)| {
+          a.iter()
+            .zip(b)
+            .zip(order)
+            .map(|((a, b), (_, ascending))| if *ascending { a.sort_cmp(b) } else { b.sort_cmp(a) })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        input.rows = keyed.into_iter().map(|(_, row)| row).collect();
+
+        Ok(input)
+      }
+      Op::SubqueryScan { alias } => {
+        let mut input = self.execute_child(plan)?;
+        input.columns = input.columns.into_iter().map(|field| Field::new(Some(alias.clone()), field.name)).collect();
+
+        Ok(input)
+      }
+      Op::NestedLoopJoin { join_type, condition } => {
+        let (left, right) = self.execute_join_sides(plan)?;
+        let columns: Vec<Field> = left.columns.iter().chain(&right.columns).cloned().collect();
+        let condition = self.prepare(condition)?;
+
+        let mut rows = Vec::new();
+        for left_row in &left.rows {
+          let matches = self.join_matches(*join_type, left_row, right.rows.iter(), &columns, Some(&condition))?;
+          push_joined(&mut rows, *join_type, left_row, matches);
+        }
+
+        Ok(join_result(*join_type, left.columns, columns, rows))
+      }
+      Op::HashJoin { join_type, left_keys, right_keys, condition } => {
+        let (left, right) = self.execute_join_sides(plan)?;
+        let columns: Vec<Field> = left.columns.iter().chain(&right.columns).cloned().collect();
+        let condition = condition.as_ref().map(|condition| self.prepare(condition)).transpose()?;
+
+        let mut buckets: HashMap<Vec<Value>, Vec<&Vec<Value>>> = HashMap::new();
+        for row in &right.rows {
+          if let Some(key) = join_key(right_keys, &right.columns, row)? {
+            buckets.entry(key).or_default().push(row);
+          }
+        }
+
+        let mut rows = Vec::new();
+        for left_row in &left.rows {
+          let candidates = match join_key(left_keys, &left.columns, left_row)? {
+            Some(key) => buckets.get(&key).map_or(&[][..], Vec::as_slice),
+            None => &[],
+          };
+
+          let matches = self.join_matches(*join_type, left_row, candidates.iter().copied(), &columns, condition.as_ref())?;
+          push_joined(&mut rows, *join_type, left_row, matches);
+        }
+
+        Ok(join_result(*join_type, left.columns, columns, rows))
+      }
+      Op::RecursiveUnion { name, distinct } => {
+        let [anchor, recursive] = plan.children() else { unreachable!() };
+
+        // INFO: a CTE with the same name can run inside of the recursive query, its working table is put back afterwards
+        let outer = self.working_tables.remove(name);
+        let result = self.execute_recursive(name, *distinct, anchor, recursive);
+
+        match outer {
+          Some(outer) => self.working_tables.insert(name.clone(), outer),
+          None => self.working_tables.remove(name),
+        };
+
+        result
+      }
+      Op::HashSetOperation { operator, all } => {
+        let (left, right) = self.execute_join_sides(plan)?;
+        Ok(ResultSet::new(left.columns, set_operation(*operator, *all, left.rows, right.rows)))
+      }
+      Op::HashAggregate { group_by, aggregates } => {
+        let input = self.execute_child(plan)?;
+        self.aggregate(input, group_by, aggregates)
+      }
+      Op::Window { functions } => {
+        let input = self.execute_child(plan)?;
+        self.window(input, functions)
+      }
+      Op::HashDistinct { on } => {
+        let mut input = self.execute_child(plan)?;
+        let keys = self.distinct_keys(on.as_deref(), &input)?;
+
+        let mut seen = HashSet::new();
+        input.rows = input.rows.into_iter().zip(keys).filter_map(|(row, key)| seen.insert(key).then_some(row)).collect();
+
+        Ok(input)
+      }
+      Op::StreamingDistinct { on } => {
+        let mut input = self.execute_child(plan)?;
+        let keys = self.distinct_keys(on.as_deref(), &input)?;
+
+        let mut previous = None;
+        input.rows = input
+          .rows
+          .into_iter()
+          .zip(keys)
+          .filter_map(|(row, key)| {
+            let first = previous.as_ref() != Some(&key);
+            previous = Some(key);
+            first.then_some(row)
+          })
+          .collect();
+
+        Ok(input)
+      }
+      Op::WorkingTableScan { name } => {
+        self.working_tables.get(name).cloned().ok_or_else(|| ExecutionError::TableNotFound(name.clone()))
+      }
+      Op::Limit { limit } => {
+        let mut input = self.execute_child(plan)?;
+        input.rows.truncate(row_count(limit)?);
+
+        Ok(input)
+      }
+      Op::Offset { offset } => {
+        let mut input = self.execute_child(plan)?;
+        input.rows = input.rows.into_iter().skip(row_count(offset)?).collect();
+
+        Ok(input)
+      }
+      Op::Insert { data_source, columns, on_conflict, returning, constraints } => {
+        let input = self.execute_child(plan)?;
+        let table = self.get_table(data_source)?;
+        let names = table.column_names();
+
+        // INFO: every row is checked before the first one is written so a bad row doesn't leave a partial insert
+        let mut rows = Vec::with_capacity(input.rows.len());
+        for values in input.rows {
+          let mut row = vec![Value::Null; names.len()];
+
+          for (column, default) in &constraints.defaults {
+            let value = self.evaluate(default, &[], &[])?;
+            row[column_index(&names, column)?] = coerce_value(&table, column, value)?;
+          }
+
+          for (column, value) in columns.iter().zip(values) {
+            row[column_index(&names, column)?] = coerce_value(&table, column, value)?;
+          }
+
+          self.check_row(&table, &names, &constraints.checks, &row)?;
+          rows.push(row);
+        }
+
+        let new_rows: Vec<&[Value]> = rows.iter().map(Vec::as_slice).collect();
+        self.check_references(&table, &names, &new_rows)?;
+
+        // INFO: with ON CONFLICT every row is checked right before it is written, it can depend on the rows before it
+        if on_conflict.is_none() {
+          let new_rows: Vec<(Option<RowId>, &[Value])> = rows.iter().map(|row| (None, row.as_slice())).collect();
+          self.check_unique(&table, &names, &new_rows)?;
+        }
+
+        // INFO: a row too large for a page is found before anything is written as well
+        for row in &rows {
+          if !self.buffer_pool.row_fits(&encode_row(&table, &names, row)?) {
+            return Err(ExecutionError::Storage(ROW_TOO_LARGE.to_string()));
+          }
+        }
+
+        // INFO: like in PostgreSQL a DO UPDATE can't change a row the statement already inserted or updated
+        let mut affected = HashSet::new();
+        let mut written = Vec::with_capacity(rows.len());
+        for row in rows {
+          let conflict = match on_conflict {
+            Some(on_conflict) => self.find_conflict(&table, &names, &on_conflict.indexes, &row)?,
+            None => None,
+          };
+
+          match (conflict, on_conflict.as_ref().map(|on_conflict| &on_conflict.action)) {
+            (Some(conflict), Some(ConflictAction::DoUpdate { values, condition })) => {
+              if affected.contains(&conflict.0) {
+                return Err(ExecutionError::RowAffectedTwice(table.name.clone()));
+              }
+
+              let action = Upsert { values, condition: condition.as_ref(), checks: &constraints.checks };
+              if let Some((row_id, updated)) = self.upsert_row(&table, &names, conflict, row, action)? {
+                affected.insert(row_id);
+                written.push(updated);
+              }
             }
-            Op::DropTable { table_name } => {
-                self.buffer_pool.remove_table_from_catalog(table_name)
+            (Some(_), _) => {} // INFO: DO NOTHING
+            (None, action) => {
+              if action.is_some() {
+                self.check_unique(&table, &names, &[(None, &row)])?;
+              }
+
+              affected.insert(self.insert_record(&table, &names, &row)?);
+              written.push(row);
             }
-            Op::Insert { data_source, rows } => {
+          }
+        }
+
+        self.dml_result(data_source, &names, written, returning)
+      }
+      Op::Update { data_source, values, returning, constraints } => {
+        let (columns, rows) = self.execute_dml_source(plan)?;
+        let table = self.get_table(data_source)?;
+        let names = table.column_names();
+
+        let mut updated = Vec::with_capacity(rows.len());
+        for (row_id, row) in rows {
+          let mut new_row = row.clone();
+
+          for (column, expression) in values {
+            let value = self.evaluate(expression, &columns, &row)?;
+            new_row[column_index(&names, column)?] = coerce_value(&table, column, value)?;
+          }
+
+          self.check_row(&table, &names, &constraints.checks, &new_row)?;
+          updated.push((row_id, row, new_row));
+        }
+
+        let new_rows: Vec<(Option<RowId>, &[Value])> =
+          updated.iter().map(|(row_id, _, new_row)| (Some(*row_id), new_row.as_slice())).collect();
+        self.check_unique(&table, &names, &new_rows)?;
+        self.check_references(&table, &names, &new_rows.iter().map(|(_, row)| *row).collect::<Vec<_>>())?;
+        self.update_rows(&table, &names, &updated)?;
+
+        self.dml_result(data_source, &names, updated.into_iter().map(|(_, _, row)| row).collect(), returning)
+      }
+      Op::Delete { data_source, returning } => {
+        let (_, rows) = self.execute_dml_source(plan)?;
+        let table = self.get_table(data_source)?;
+        let names = table.column_names();
+
+        self.delete_rows(&table, &names, &rows)?;
+
+        self.dml_result(data_source, &names, rows.into_iter().map(|(_, row)| row).collect(), returning)
+      }
+    }
+  }
+
+  fn execute_child(&mut self, plan: &PhysicalPlan) -> Result<ResultSet, ExecutionError> {
+    match plan.children().first() {
+      Some(child) => self.execute_plan(child),
+      None => Ok(ResultSet::default()),
+    }
+  }
+
+  // INFO: UNION drops the rows that were already returned, UNION ALL keeps them and relies on the recursive query to stop
+  fn execute_recursive(
+    &mut self,
+    name: &str,
+    distinct: bool,
+    anchor: &PhysicalPlan,
+    recursive: &PhysicalPlan,
+  ) -> Result<ResultSet, ExecutionError> {
+    let mut result = self.execute_plan(anchor)?;
+    let mut seen = HashSet::new();
+
+    if distinct {
+      result.rows.retain(|row| seen.insert(row.clone()));
+    }
+
+    let mut working = result.rows.clone();
+    let mut iterations = 0;
+
+    while !working.is_empty() {
+      if iterations == MAX_RECURSIVE_ITERATIONS {
+        return Err(ExecutionError::RecursionLimitExceeded(name.to_string()));
+      }
+      iterations += 1;
+
+      self.working_tables.insert(name.to_string(), ResultSet::new(result.columns.clone(), working));
+      let mut rows = self.execute_plan(recursive)?.rows;
+
+      if distinct {
+        rows.retain(|row| seen.insert(row.clone()));
+      }
+
+      result.rows.extend(rows.iter().cloned());
+      working = rows;
+    }
+
+    Ok(result)
+  }
+
+  fn execute_join_sides(&mut self, plan: &PhysicalPlan) -> Result<(ResultSet, ResultSet), ExecutionError> {
+    match plan.children() {
+      [left, right] => Ok((self.execute_plan(left)?, self.execute_plan(right)?)),
+      _ => unreachable!(),
+    }
+  }
+
+  // INFO: Joined rows of the left row and the candidates that pass the condition, semi and anti joins stop at the first one
+  fn join_matches<'r>(
+    &mut self,
+    join_type: JoinType,
+    left_row: &[Value],
+    candidates: impl Iterator<Item = &'r Vec<Value>>,
+    columns: &[Field],
+    condition: Option<&Expression>,
+  ) -> Result<Vec<Vec<Value>>, ExecutionError> {
+    let mut matches = Vec::new();
+
+    for right_row in candidates {
+      let row: Vec<Value> = left_row.iter().chain(right_row).cloned().collect();
+
+      if let Some(condition) = condition {
+        if !is_true(&self.evaluate(condition, columns, &row)?) {
+          continue;
+        }
+      }
+
+      matches.push(row);
+
+      if join_type != JoinType::Inner {
+        break;
+      }
+    }
+
+    Ok(matches)
+  }
+
+  // INFO: Groups the rows in the order their groups first appear, without GROUP BY there is always exactly one group
+  fn aggregate(
+    &mut self,
+    input: ResultSet,
+    group_by: &[Expression],
+    aggregates: &[Expression],
+  ) -> Result<ResultSet, ExecutionError> {
+    let group_by = group_by.iter().map(|expression| self.prepare(expression)).collect::<Result<Vec<_>, _>>()?;
+    let aggregates = aggregates.iter().map(|expression| self.prepare(expression)).collect::<Result<Vec<_>, _>>()?;
+    let accumulators = || {
+      aggregates
+        .iter()
+        .map(|aggregate| match aggregate {
+          Expression::Aggregate(function, _, distinct) => Accumulator::new(function.clone(), *distinct),
+          _ => unreachable!(),
+        })
+        .collect::<Vec<_>>()
+    };
+
+    let mut groups: Vec<(Vec<Value>, Vec<Accumulator>)> = Vec::new();
+    let mut positions: HashMap<Vec<Value>, usize> = HashMap::new();
+
+    if group_by.is_empty() {
+      groups.push((Vec::new(), accumulators()));
+      positions.insert(Vec::new(), 0);
+    }
+
+    for row in &input.rows {
+      let key =
+        group_by.iter().map(|expression| self.evaluate(expression, &input.columns, row)).collect::<Result<Vec<_>, _>>()?;
+      let position = match positions.get(&key) {
+        Some(position) => *position,
+        None => {
+          groups.push((key.clone(), accumulators()));
+          positions.insert(key, groups.len() - 1);
+          groups.len() - 1
+        }
+      };
+
+      for (accumulator, aggregate) in groups[position].1.iter_mut().zip(&aggregates) {
+        let Expression::Aggregate(_, args, _) = aggregate else { unreachable!() };
+        let args = args.iter().map(|arg| self.evaluate(arg, &input.columns, row)).collect::<Result<_, _>>()?;
+
+        accumulator.accumulate(args)?;
+      }
+    }
+
+    let mut columns = Vec::with_capacity(group_by.len() + aggregates.len());
+    for (index, expression) in group_by.iter().enumerate() {
+      columns.push(match expression {
+        Expression::Identifier(name) => input.columns[resolve_column(&input.columns, name)?].clone(),
+        _ => Field::new(None, plan::group_column(index)),
+      });
+    }
+    columns.extend((0..aggregates.len()).map(|index| Field::new(None, plan::aggregate_column(index))));
+
+    let rows = groups
+      .into_iter()
+      .map(|(key, accumulators)| {
+        let values = accumulators.into_iter().map(Accumulator::finalize).collect::<Result<Vec<_>, _>>()?;
+        Ok(key.into_iter().chain(values).collect())
+      })
+      .collect::<Result<_, ExecutionError>>()?;
+
+    Ok(ResultSet::new(columns, rows))
+  }
+
+  // INFO: Appends a column for every window function, the rows come out in the partition and sort order of the first one
+  fn window(&mut self, input: ResultSet, functions: &[Expression]) -> Result<ResultSet, ExecutionError> {
+    let mut fields = input.columns.clone();
+    let mut results = Vec::with_capacity(functions.len());
+    let mut order = None;
+
+    for (index, function) in functions.iter().enumerate() {
+      let Expression::Window(function, args, window) = self.prepare(function)? else { unreachable!() };
+      let columns = &input.columns;
+
+      let mut rows = Vec::with_capacity(input.rows.len());
+      for row in &input.rows {
+        let partition = window.partition_by.iter().map(|e| self.evaluate(e, columns, row)).collect::<Result<Vec<_>, _>>()?;
+        let order = window.order_by.iter().map(|(e, _)| self.evaluate(e, columns, row)).collect::<Result<Vec<_>, _>>()?;
+        let args = args.iter().map(|e| self.evaluate(e, columns, row)).collect::<Result<Vec<_>, _>>()?;
+
+        rows.push(WindowRow { partition, order, args });
+      }
+
+      let (values, sorted) = window::compute(&function, &window, &rows)?;
+      order.get_or_insert(sorted);
+      results.push(values);
+      fields.push(Field::new(None, plan::window_column(index)));
+    }
+
+    let mut rows = input
+      .rows
+      .into_iter()
+      .enumerate()
+      .map(|(index, mut row)| {
+        row.extend(results.iter().map(|values| values[index].clone()));
+        row
+      })
+      .collect::<Vec<_>>();
+
+    let order = order.unwrap_or_else(|| (0..rows.len()).collect());
+    let rows = order.into_iter().map(|index| std::mem::take(&mut rows[index])).collect();
+
+    Ok(ResultSet::new(fields, rows))
+  }
+
+  // INFO: Values that decide whether two rows are duplicates, the whole row when there is no DISTINCT ON
+  fn distinct_keys(&mut self, on: Option<&[Expression]>, input: &ResultSet) -> Result<Vec<Vec<Value>>, ExecutionError> {
+    let Some(on) = on else {
+      return Ok(input.rows.clone());
+    };
+
+    let on = on.iter().map(|expression| self.prepare(expression)).collect::<Result<Vec<_>, _>>()?;
+    input.rows.iter().map(|row| on.iter().map(|expression| self.evaluate(expression, &input.columns, row)).collect()).collect()
+  }
+
+  // INFO: Evaluates the expression for one row, the subqueries in it are run with the values of that row
+  fn evaluate(&mut self, expression: &Expression, columns: &[Field], row: &[Value]) -> Result<Value, ExecutionError> {
+    if !expression.has_subquery() {
+      return evaluate(expression, columns, row);
+    }
+
+    let resolved = self.resolve_subqueries(expression.clone(), Some((columns, row)))?;
+    evaluate(&resolved, columns, row)
+  }
+
+  // INFO: Runs the subqueries that don't depend on the current row once, instead of once for every row
+  fn prepare(&mut self, expression: &Expression) -> Result<Expression, ExecutionError> {
+    if !expression.has_subquery() {
+      return Ok(expression.clone());
+    }
+
+    self.resolve_subqueries(expression.clone(), None)
+  }
+
+  // INFO: Replaces subqueries with their results, without a row the correlated ones are left for later
+  fn resolve_subqueries(
+    &mut self,
+    expression: Expression,
+    outer: Option<(&[Field], &[Value])>,
+  ) -> Result<Expression, ExecutionError> {
+    expression.try_transform(&mut |expression| {
+      let mut node = match expression.subquery() {
+        Some(node) => node.clone(),
+        None => return Ok(expression),
+      };
+
+      match outer {
+        Some((columns, row)) => bind_outer(&mut node, 1, columns, row)?,
+        None if node.references_outer(1) => return Ok(expression),
+        None => {}
+      }
+
+      let result = self.execute_subquery(node)?;
+
+      Ok(match expression {
+        Expression::Exists(_) => Expression::Constant(plan::Value::Boolean(!result.rows.is_empty())),
+        Expression::InSubquery(expression, _) => {
+          let values = result.rows.into_iter().flatten().map(|value| Expression::Constant(value.into())).collect();
+          Expression::InList(expression, values)
+        }
+        _ if result.rows.len() > 1 => return Err(ExecutionError::SubqueryReturnedMultipleRows),
+        _ => Expression::Constant(result.rows.into_iter().flatten().next().unwrap_or(Value::Null).into()),
+      })
+    })
+  }
+
+  fn execute_subquery(&mut self, node: Node) -> Result<ResultSet, ExecutionError> {
+    let plan = Optimizer::new(Plan(node), self.buffer_pool.get_catalog()).optimize()?;
+    self.execute_plan(&plan)
+  }
+
+  fn project(&mut self, expressions: &[Expression], columns: &[Field], rows: &[Vec<Value>]) -> Result<ResultSet, ExecutionError> {
+    let expressions = expressions.iter().map(|expression| self.prepare(expression)).collect::<Result<Vec<_>, _>>()?;
+    let fields = expressions.iter().map(|expression| projection_field(expression, columns)).collect();
+
+    let mut result = Vec::with_capacity(rows.len());
+    for row in rows {
+      result.push(expressions.iter().map(|expression| self.evaluate(expression, columns, row)).collect::<Result<_, _>>()?);
+    }
+
+    Ok(ResultSet::new(fields, result))
+  }
+
+  fn filter_rows(
+    &mut self,
+    rows: Vec<Vec<Value>>,
+    columns: &[Field],
+    condition: &Expression,
+  ) -> Result<Vec<Vec<Value>>, ExecutionError> {
+    let condition = self.prepare(condition)?;
+    let mut result = Vec::new();
+
+    for row in rows {
+      if is_true(&self.evaluate(&condition, columns, &row)?) {
+        result.push(row);
+      }
+    }
+
+    Ok(result)
+  }
+
+  // INFO: DML returns the projected rows when it has a RETURNING clause and the number of rows otherwise
+  fn dml_result(
+    &mut self,
+    table_name: &str,
+    names: &[String],
+    rows: Vec<Vec<Value>>,
+    returning: &Option<Vec<Expression>>,
+  ) -> Result<ResultSet, ExecutionError> {
+    match returning {
+      Some(returning) => {
+        let columns: Vec<Field> = names.iter().map(|name| Field::new(Some(table_name.to_string()), name.clone())).collect();
+        self.project(returning, &columns, &rows)
+      }
+      None => Ok(ResultSet::affected(rows.len())),
+    }
+  }
+
+  fn get_table(&mut self, table_name: &str) -> Result<catalog::Table, ExecutionError> {
+    self
+      .buffer_pool
+      .get_catalog()
+      .get_table(table_name)
+      .cloned()
+      .ok_or_else(|| ExecutionError::TableNotFound(table_name.to_string()))
+  }
+
+  fn scan(
+    &mut self,
+    table_name: &str,
+    alias: &Option<String>,
+    index: Option<(&String, &KeyRange)>,
+    filter: Option<&Expression>,
+  ) -> Result<ResultSet, ExecutionError> {
+    let (columns, rows) = self.scan_rows(table_name, alias, index, filter)?;
+    Ok(ResultSet::new(columns, rows.into_iter().map(|(_, row)| row).collect()))
+  }
+
+  // INFO: Like a scan but keeps the location of every row so it can be changed afterwards,
+  // with an index only the rows in its key range are read
+  fn scan_rows(
+    &mut self,
+    table_name: &str,
+    alias: &Option<String>,
+    index: Option<(&String, &KeyRange)>,
+    filter: Option<&Expression>,
+  ) -> Result<ScannedRows, ExecutionError> {
+    let table = self.get_table(table_name)?;
+    let names = table.column_names();
+    let qualifier = alias.clone().unwrap_or_else(|| table_name.to_string());
+
+    let columns: Vec<Field> = names.iter().map(|name| Field::new(Some(qualifier.clone()), name.clone())).collect();
+    let filter = filter.map(|condition| self.prepare(condition)).transpose()?;
+    let mut rows = Vec::new();
+
+    let stored = match index {
+      Some((index, range)) => self.index_range_rows(table_name, index, range)?,
+      None => self.buffer_pool.scan_table(table_name)?,
+    };
+
+    for (row_id, data) in stored {
+      let row = decode_row(&data, &table, &names)?;
+
+      if let Some(condition) = &filter {
+        if !is_true(&self.evaluate(condition, &columns, &row)?) {
+          continue;
+        }
+      }
+
+      rows.push((row_id, row));
+    }
+
+    Ok((columns, rows))
+  }
+
+  fn index_range_rows(
+    &mut self,
+    table_name: &str,
+    index: &str,
+    range: &KeyRange,
+  ) -> Result<Vec<(RowId, Vec<u8>)>, ExecutionError> {
+    let row_ids = match range {
+      KeyRange::Between(low, high) => {
+        let high = Value::from(high.clone());
+        let within = |key: &Value| key.compare(&high).is_some_and(|ordering| ordering.is_le());
+        self.buffer_pool.index_scan(index, &[Value::from(low.clone())], within)?
+      }
+      KeyRange::Prefix(prefix) => self.buffer_pool.index_scan(
+        index,
+        &[Value::Text(prefix.clone())],
+        |key| matches!(key, Value::Text(text) if text.starts_with(prefix.as_str())),
+      )?,
+    };
+
+    let mut stored = Vec::with_capacity(row_ids.len());
+    for row_id in row_ids {
+      if let Some(data) = self.buffer_pool.read_row(table_name, row_id)? {
+        stored.push((row_id, data));
+      }
+    }
+
+    Ok(stored)
+  }
+
+  // INFO: Writes a new row and adds it to every index of the table
+  fn insert_record(&mut self, table: &catalog::Table, names: &[String], row: &[Value]) -> Result<RowId, ExecutionError> {
+    let row_id = self.buffer_pool.insert_row(&table.name, encode_row(table, names, row)?)?;
+
+    for index in &table.indexes {
+      self.buffer_pool.index_insert(&index.name, index_key(index, names, row), row_id)?;
+    }
+
+    Ok(row_id)
+  }
 
-                unimplemented!()
+  fn update_record(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    row_id: RowId,
+    old_row: &[Value],
+    new_row: &[Value],
+  ) -> Result<RowId, ExecutionError> {
+    let new_row_id = self.buffer_pool.update_row(&table.name, row_id, encode_row(table, names, new_row)?)?;
+
+    for index in &table.indexes {
+      self.buffer_pool.index_remove(&index.name, &index_key(index, names, old_row), row_id)?;
+      self.buffer_pool.index_insert(&index.name, index_key(index, names, new_row), new_row_id)?;
+    }
+
+    Ok(new_row_id)
+  }
+
+  fn delete_record(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    row_id: RowId,
+    row: &[Value],
+  ) -> Result<(), ExecutionError> {
+    self.buffer_pool.delete_row(&table.name, row_id)?;
+
+    for index in &table.indexes {
+      self.buffer_pool.index_remove(&index.name, &index_key(index, names, row), row_id)?;
+    }
+
+    Ok(())
+  }
+
+  // INFO: Rows are rewritten when their values change, a dropped or renamed column keeps the rows as they are since they
+  // refer to columns by id. Indexes are rebuilt when their keys may have changed
+  fn alter_storage(
+    &mut self,
+    table: &str,
+    schema: &catalog::Table,
+    action: &AlterAction,
+    names: &[String],
+    rows: &[(RowId, Vec<Value>)],
+  ) -> Result<(), ExecutionError> {
+    let previous = self.get_table(table)?;
+    if *action == AlterAction::RenameTable {
+      self.buffer_pool.rename_table_data(table, &schema.name)?;
+    }
+
+    let rewrite = matches!(action, AlterAction::AddColumn { default: Some(_), .. } | AlterAction::ModifyColumn(_));
+    for index in previous.indexes.iter().filter(|index| rewrite || !schema.indexes.contains(index)) {
+      self.buffer_pool.remove_index(&index.name)?;
+    }
+
+    let rebuilt: Vec<&catalog::Index> =
+      schema.indexes.iter().filter(|index| rewrite || !previous.indexes.contains(index)).collect();
+    for (row_id, row) in rows {
+      let row_id = match rewrite {
+        true => self.buffer_pool.update_row(&schema.name, *row_id, encode_row(schema, names, row)?)?,
+        false => *row_id,
+      };
+
+      for index in &rebuilt {
+        self.buffer_pool.index_insert(&index.name, index_key(index, names, row), row_id)?;
+      }
+    }
+
+    // INFO: foreign keys of other tables follow a renamed table or column
+    let catalog = self.buffer_pool.get_catalog();
+    match action {
+      AlterAction::RenameTable => catalog.rename_table(table, &schema.name),
+      AlterAction::RenameColumn { from, to } => catalog.rename_referenced_column(table, from, to),
+      _ => {}
+    }
+    self.buffer_pool.update_table_in_catalog(schema.clone());
+
+    Ok(())
+  }
+
+  // INFO: Stored row that has the same key as the new row in one of the given indexes, keys with a NULL never conflict
+  fn find_conflict(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    indexes: &[String],
+    row: &[Value],
+  ) -> Result<Option<(RowId, Vec<Value>)>, ExecutionError> {
+    for index in table.indexes.iter().filter(|index| indexes.contains(&index.name)) {
+      let key = index_key(index, names, row);
+
+      if key.iter().any(Value::is_null) {
+        continue;
+      }
+
+      for row_id in self.buffer_pool.index_lookup(&index.name, &key)? {
+        if let Some(data) = self.buffer_pool.read_row(&table.name, row_id)? {
+          return Ok(Some((row_id, decode_row(&data, table, names)?)));
+        }
+      }
+    }
+
+    Ok(None)
+  }
+
+  // INFO: ON CONFLICT DO UPDATE, the SET expressions see the stored row and the proposed one as `excluded`
+  fn upsert_row(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    (row_id, existing): (RowId, Vec<Value>),
+    proposed: Vec<Value>,
+    Upsert { values, condition, checks }: Upsert,
+  ) -> Result<Option<(RowId, Vec<Value>)>, ExecutionError> {
+    let columns: Vec<Field> = names
+      .iter()
+      .map(|name| Field::new(Some(table.name.clone()), name.clone()))
+      .chain(names.iter().map(|name| Field::new(Some(EXCLUDED_TABLE.to_string()), name.clone())))
+      .collect();
+    let row: Vec<Value> = existing.iter().cloned().chain(proposed).collect();
+
+    if let Some(condition) = condition {
+      if !is_true(&self.evaluate(condition, &columns, &row)?) {
+        return Ok(None);
+      }
+    }
+
+    let mut updated = existing.clone();
+    for (column, expression) in values {
+      let value = self.evaluate(expression, &columns, &row)?;
+      updated[column_index(names, column)?] = coerce_value(table, column, value)?;
+    }
+
+    self.check_row(table, names, checks, &updated)?;
+    self.check_unique(table, names, &[(Some(row_id), &updated)])?;
+    self.check_references(table, names, &[&updated])?;
+    let row_ids = self.update_rows(table, names, &[(row_id, existing, updated.clone())])?;
+
+    Ok(Some((row_ids[0], updated)))
+  }
+
+  // INFO: NOT NULL and CHECK constraints, a CHECK passes when its condition is TRUE or NULL
+  fn check_row(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    checks: &[Check],
+    row: &[Value],
+  ) -> Result<(), ExecutionError> {
+    for (name, value) in names.iter().zip(row) {
+      if value.is_null() && table.get_column(name).is_some_and(|column| !column.nullable) {
+        return Err(ExecutionError::NotNullViolation { table: table.name.clone(), column: name.clone() });
+      }
+    }
+
+    let columns: Vec<Field> = names.iter().map(|name| Field::new(Some(table.name.clone()), name.clone())).collect();
+    for check in checks {
+      if matches!(self.evaluate(&check.condition, &columns, row)?, Value::Boolean(true) | Value::Null) {
+        continue;
+      }
+
+      let constraint = check.name.clone();
+      return Err(match check.columns.as_slice() {
+        [column] => {
+          let value = row[column_index(names, column)?].clone();
+          ExecutionError::CheckViolation { constraint, column: column.clone(), value }
+        }
+        _ => ExecutionError::RowCheckViolation { table: table.name.clone(), constraint },
+      });
+    }
+
+    Ok(())
+  }
+
+  // INFO: Rows about to be stored, each with the id of the row it replaces, can't share a unique key with each other
+  // or with a stored row that is not replaced. Keys with a NULL never conflict
+  fn check_unique(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    rows: &[(Option<RowId>, &[Value])],
+  ) -> Result<(), ExecutionError> {
+    let replaced: HashSet<RowId> = rows.iter().filter_map(|(row_id, _)| *row_id).collect();
+
+    for index in table.unique_indexes() {
+      let mut keys = HashSet::new();
+
+      for (_, row) in rows {
+        let key = index_key(index, names, row);
+        if key.iter().any(Value::is_null) {
+          continue;
+        }
+
+        let stored = self.buffer_pool.index_lookup(&index.name, &key)?;
+        if stored.iter().any(|row_id| !replaced.contains(row_id)) || !keys.insert(key.clone()) {
+          return Err(ExecutionError::UniqueViolation { constraint: index.name.clone(), columns: index.keys(), key });
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  // INFO: Every foreign key of the rows without a NULL has to match a stored row of the referenced table,
+  // a table that references itself can also point to one of the rows that are written with it
+  fn check_references(&mut self, table: &catalog::Table, names: &[String], rows: &[&[Value]]) -> Result<(), ExecutionError> {
+    for (name, reference) in table.foreign_keys() {
+      let positions = column_positions(names, &reference.columns)?;
+      let parent = match reference.table_name == table.name {
+        true => table.clone(),
+        false => self.get_table(&reference.table_name)?,
+      };
+      let Some(index) = parent.find_unique_index(&reference.referenced_columns) else {
+        let columns = reference.referenced_columns.join(", ");
+        return Err(ExecutionError::ColumnNotFound(format!("{}.{}", reference.table_name, columns)));
+      };
+
+      let written: HashSet<Vec<Value>> = match parent.name == table.name {
+        true => {
+          let referenced = column_positions(names, &reference.referenced_columns)?;
+          rows.iter().map(|row| row_key(row, &referenced)).collect()
+        }
+        false => HashSet::new(),
+      };
+
+      for row in rows {
+        let key = row_key(row, &positions);
+        if key.iter().any(Value::is_null) || written.contains(&key) {
+          continue;
+        }
+
+        // INFO: the index keeps the referenced columns in its own order
+        let lookup: Vec<Value> = index
+          .columns
+          .iter()
+          .filter_map(|column| reference.referenced_columns.iter().position(|referenced| referenced == column))
+          .map(|i| key[i].clone())
+          .collect();
+
+        if self.buffer_pool.index_lookup(&index.name, &lookup)?.is_empty() {
+          return Err(ExecutionError::ForeignKeyViolation {
+            constraint: name.clone(),
+            columns: reference.columns.clone(),
+            key,
+            table: parent.name.clone(),
+          });
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  // INFO: Deletes the rows after the ON DELETE action of every foreign key that references them is checked,
+  // CASCADE and SET NULL are applied to the referencing rows once the rows are gone
+  fn delete_rows(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    rows: &[(RowId, Vec<Value>)],
+  ) -> Result<(), ExecutionError> {
+    let mut actions = Vec::new();
+    for (child, name, reference) in self.buffer_pool.get_catalog().referencing_constraints(&table.name) {
+      let positions = column_positions(names, &reference.referenced_columns)?;
+      let nulls = vec![Value::Null; positions.len()];
+      let keys: HashMap<Vec<Value>, Vec<Value>> = rows.iter().map(|(_, row)| (row_key(row, &positions), nulls.clone())).collect();
+
+      let excluded: HashSet<RowId> = rows.iter().map(|(row_id, _)| *row_id).filter(|_| child == table.name).collect();
+      let (child, child_names, matches) = self.referencing_rows(&child, &reference.columns, &keys, &excluded)?;
+      if matches.is_empty() {
+        continue;
+      }
+
+      match reference.on_delete {
+        ReferentialAction::NoAction | ReferentialAction::Restrict => {
+          return Err(referenced_key(&name, &reference, &child.name, &table.name, &matches[0].2));
+        }
+        ReferentialAction::Cascade => actions.push((child, child_names, None, matches)),
+        ReferentialAction::SetNull => actions.push((child, child_names, Some(reference.columns), matches)),
+      }
+    }
+
+    for (row_id, row) in rows {
+      self.delete_record(table, names, *row_id, row)?;
+    }
+
+    for (child, child_names, columns, matches) in actions {
+      match columns {
+        Some(columns) => {
+          let positions = column_positions(&child_names, &columns)?;
+          let updates: Vec<(RowId, Vec<Value>, Vec<Value>)> = matches
+            .into_iter()
+            .map(|(row_id, row, _)| {
+              let mut new_row = row.clone();
+              for position in &positions {
+                new_row[*position] = Value::Null;
+              }
+              (row_id, row, new_row)
+            })
+            .collect();
+
+          for (_, _, new_row) in &updates {
+            self.check_row(&child, &child_names, &[], new_row)?;
+          }
+          self.update_rows(&child, &child_names, &updates)?;
+        }
+        None => {
+          let rows: Vec<(RowId, Vec<Value>)> = matches.into_iter().map(|(row_id, row, _)| (row_id, row)).collect();
+          self.delete_rows(&child, &child_names, &rows)?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  // INFO: Same as deleting but only the foreign keys whose referenced key changed are affected,
+  // CASCADE gives the referencing rows the new key. Returns where the updated rows are stored now
+  fn update_rows(
+    &mut self,
+    table: &catalog::Table,
+    names: &[String],
+    updates: &[(RowId, Vec<Value>, Vec<Value>)],
+  ) -> Result<Vec<RowId>, ExecutionError> {
+    let mut actions = Vec::new();
+    for (child, name, reference) in self.buffer_pool.get_catalog().referencing_constraints(&table.name) {
+      let positions = column_positions(names, &reference.referenced_columns)?;
+      let keys: HashMap<Vec<Value>, Vec<Value>> = updates
+        .iter()
+        .map(|(_, old_row, new_row)| (row_key(old_row, &positions), row_key(new_row, &positions)))
+        .filter(|(old_key, new_key)| old_key != new_key)
+        .collect();
+
+      let excluded: HashSet<RowId> = updates.iter().map(|(row_id, ..)| *row_id).filter(|_| child == table.name).collect();
+      let (child, child_names, matches) = self.referencing_rows(&child, &reference.columns, &keys, &excluded)?;
+      if matches.is_empty() {
+        continue;
+      }
+
+      let child_positions = column_positions(&child_names, &reference.columns)?;
+      let child_updates: Vec<(RowId, Vec<Value>, Vec<Value>)> = match reference.on_update {
+        ReferentialAction::NoAction | ReferentialAction::Restrict => {
+          return Err(referenced_key(&name, &reference, &child.name, &table.name, &matches[0].2));
+        }
+        ReferentialAction::Cascade | ReferentialAction::SetNull => matches
+          .into_iter()
+          .map(|(row_id, row, key)| {
+            let mut new_row = row.clone();
+            for (i, position) in child_positions.iter().enumerate() {
+              new_row[*position] = match reference.on_update {
+                ReferentialAction::Cascade => keys[&key][i].clone(),
+                _ => Value::Null,
+              };
             }
-            _ => unimplemented!()
+            (row_id, row, new_row)
+          })
+          .collect(),
+      };
+
+      actions.push((child, child_names, child_updates));
+    }
+
+    let mut row_ids = Vec::with_capacity(updates.len());
+    for (row_id, old_row, new_row) in updates {
+      row_ids.push(self.update_record(table, names, *row_id, old_row, new_row)?);
+    }
+
+    for (child, child_names, updates) in actions {
+      for (_, _, new_row) in &updates {
+        self.check_row(&child, &child_names, &[], new_row)?;
+      }
+      let new_rows: Vec<(Option<RowId>, &[Value])> =
+        updates.iter().map(|(row_id, _, new_row)| (Some(*row_id), new_row.as_slice())).collect();
+      self.check_unique(&child, &child_names, &new_rows)?;
+
+      self.update_rows(&child, &child_names, &updates)?;
+    }
+
+    Ok(row_ids)
+  }
+
+  // INFO: Stored rows of a table whose columns hold one of the keys, each one with the key it matched. The keys map a
+  // referenced key to the one that replaces it. A key with a NULL never matches, the excluded rows are the ones being
+  // changed when a table references itself
+  fn referencing_rows(
+    &mut self,
+    table_name: &str,
+    columns: &[String],
+    keys: &HashMap<Vec<Value>, Vec<Value>>,
+    excluded: &HashSet<RowId>,
+  ) -> Result<ReferencingRows, ExecutionError> {
+    let (fields, rows) = self.scan_rows(table_name, &None, None, None)?;
+    let table = self.get_table(table_name)?;
+    let names: Vec<String> = fields.into_iter().map(|field| field.name).collect();
+    let positions = column_positions(&names, columns)?;
+
+    let matches = rows
+      .into_iter()
+      .filter(|(row_id, _)| !excluded.contains(row_id))
+      .filter_map(|(row_id, row)| {
+        let key = row_key(&row, &positions);
+        match !key.iter().any(Value::is_null) && keys.contains_key(&key) {
+          true => Some((row_id, row, key)),
+          false => None,
+        }
+      })
+      .collect();
+
+    Ok((table, names, matches))
+  }
+
+  // INFO: UPDATE and DELETE read their rows from a table scan, a filter above it is applied here
+  fn execute_dml_source(&mut self, plan: &PhysicalPlan) -> Result<ScannedRows, ExecutionError> {
+    let (source, condition) = match plan.children().first() {
+      Some(PhysicalPlan { node: Op::Filter { condition }, .. }) => (&plan.children()[0].children()[0], Some(condition)),
+      Some(source) => (source, None),
+      None => return Ok((Vec::new(), Vec::new())),
+    };
+
+    let (columns, mut rows) = match &source.node {
+      Op::TableScan { data_source, alias, filter } => self.scan_rows(data_source, alias, None, filter.as_ref())?,
+      Op::IndexScan { data_source, alias, index, range, filter } => {
+        self.scan_rows(data_source, alias, Some((index, range)), filter.as_ref())?
+      }
+      _ => return Err(ExecutionError::InvalidDmlSource),
+    };
+
+    if let Some(condition) = condition {
+      let condition = self.prepare(condition)?;
+      let mut filtered = Vec::new();
+      for (row_id, row) in rows {
+        if is_true(&self.evaluate(&condition, &columns, &row)?) {
+          filtered.push((row_id, row));
         }
+      }
+      rows = filtered;
+    }
+
+    Ok((columns, rows))
+  }
+}
+
+// INFO: Rows are compared by hashing, the ALL forms keep as many copies of a row as the rules of the operator allow
+fn set_operation(operator: SetOperator, all: bool, left: Vec<Vec<Value>>, right: Vec<Vec<Value>>) -> Vec<Vec<Value>> {
+  let mut counts: HashMap<Vec<Value>, usize> = HashMap::new();
+  if operator != SetOperator::Union {
+    for row in right.iter().cloned() {
+      *counts.entry(row).or_default() += 1;
+    }
+  }
+
+  let mut seen = HashSet::new();
+  let mut rows = Vec::new();
+
+  let candidates = match operator {
+    SetOperator::Union => left.into_iter().chain(right).collect(),
+    SetOperator::Intersect | SetOperator::Except => left,
+  };
+
+  for row in candidates {
+    let keep = match (operator, counts.get_mut(&row)) {
+      (SetOperator::Union, _) => true,
+      (SetOperator::Intersect, Some(count)) if *count > 0 => {
+        *count -= 1;
+        true
+      }
+      (SetOperator::Intersect, _) => false,
+      (SetOperator::Except, Some(count)) if *count > 0 && all => {
+        *count -= 1;
+        false
+      }
+      (SetOperator::Except, Some(_)) if !all => false,
+      (SetOperator::Except, _) => true,
+    };
+
+    if keep && (all || seen.insert(row.clone())) {
+      rows.push(row);
+    }
+  }
+
+  rows
+}
+
+// INFO: One row per column with its type and constraints, NOT NULL and DEFAULT get their own columns
+fn describe(table: &catalog::Table) -> ResultSet {
+  let fields = ["column", "type", "nullable", "default", "constraints"];
+  let fields = fields.iter().map(|name| Field::new(None, name.to_string())).collect();
+
+  let rows = table
+    .columns
+    .iter()
+    .map(|column| {
+      vec![
+        Value::Text(column.name.clone()),
+        Value::Text(column.data_type.to_string()),
+        Value::Boolean(column.nullable),
+        column.default.clone().map_or(Value::Null, Value::Text),
+        Value::Text(constraints(table, &column.name)),
+      ]
+    })
+    .collect();
+
+  ResultSet::new(fields, rows)
+}
+
+// INFO: Every constraint the column takes part in except NOT NULL and DEFAULT
+fn constraints(table: &catalog::Table, column: &str) -> String {
+  let constraints = table.constraints.iter().filter(|constraint| constraint.columns().iter().any(|name| name == column));
+  constraints.map(|constraint| constraint.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+// INFO: Inner joins return the columns of both sides, semi and anti joins only the left ones
+fn join_result(join_type: JoinType, left: Vec<Field>, columns: Vec<Field>, rows: Vec<Vec<Value>>) -> ResultSet {
+  match join_type {
+    JoinType::Inner => ResultSet::new(columns, rows),
+    JoinType::Semi | JoinType::Anti => ResultSet::new(left, rows),
+  }
+}
+
+fn push_joined(rows: &mut Vec<Vec<Value>>, join_type: JoinType, left_row: &[Value], matches: Vec<Vec<Value>>) {
+  match join_type {
+    JoinType::Inner => rows.extend(matches),
+    JoinType::Semi if !matches.is_empty() => rows.push(left_row.to_vec()),
+    JoinType::Anti if matches.is_empty() => rows.push(left_row.to_vec()),
+    _ => {}
+  }
+}
+
+// INFO: None when a part of the key is NULL, such a row can't be equal to any other
+fn join_key(keys: &[Expression], columns: &[Field], row: &[Value]) -> Result<Option<Vec<Value>>, ExecutionError> {
+  let key = keys.iter().map(|key| evaluate(key, columns, row)).collect::<Result<Vec<_>, _>>()?;
+  Ok(if key.iter().any(Value::is_null) { None } else { Some(key) })
+}
+
+// INFO: NOW and CURRENT_DATE give the time the statement started at, so all of its rows and subqueries see the same time
+fn statement_time(expression: Expression, started: &DateTime<Utc>) -> Expression {
+  expression.transform(&mut |expression| match expression {
+    Expression::Function(function, _) if function.name == builtin::NOW => {
+      Expression::Constant(plan::Value::TimestampTz(*started))
+    }
+    Expression::Function(function, _) if function.name == builtin::CURRENT_DATE => {
+      Expression::Constant(plan::Value::Date(started.with_timezone(&Local).date_naive()))
+    }
+    Expression::Subquery(mut node) => {
+      node_statement_time(&mut node, started);
+      Expression::Subquery(node)
+    }
+    Expression::Exists(mut node) => {
+      node_statement_time(&mut node, started);
+      Expression::Exists(node)
+    }
+    Expression::InSubquery(expression, mut node) => {
+      node_statement_time(&mut node, started);
+      Expression::InSubquery(expression, node)
     }
-}
\ No newline at end of file
+    expression => expression,
+  })
+}
+
+fn node_statement_time(node: &mut Node, started: &DateTime<Utc>) {
+  for expression in node.expressions_mut() {
+    *expression = statement_time(expression.clone(), started);
+  }
+
+  for child in node.children_mut() {
+    node_statement_time(child, started);
+  }
+}
+
+// INFO: Puts the values of the row in place of the outer references that point `depth` queries up from the node
+fn bind_outer(node: &mut Node, depth: usize, columns: &[Field], row: &[Value]) -> Result<(), ExecutionError> {
+  for expression in node.expressions_mut() {
+    let bound = expression.clone().try_transform(&mut |expression| match expression {
+      Expression::Outer(name, level) if level == depth => {
+        let index = resolve_column(columns, &name)?;
+        Ok::<_, ExecutionError>(Expression::Constant(row[index].clone().into()))
+      }
+      Expression::Subquery(mut node) => {
+        bind_outer(&mut node, depth + 1, columns, row)?;
+        Ok(Expression::Subquery(node))
+      }
+      Expression::Exists(mut node) => {
+        bind_outer(&mut node, depth + 1, columns, row)?;
+        Ok(Expression::Exists(node))
+      }
+      Expression::InSubquery(expression, mut node) => {
+        bind_outer(&mut node, depth + 1, columns, row)?;
+        Ok(Expression::InSubquery(expression, node))
+      }
+      expression => Ok(expression),
+    })?;
+
+    *expression = bound;
+  }
+
+  for child in node.children_mut() {
+    bind_outer(child, depth, columns, row)?;
+  }
+
+  Ok(())
+}
+
+fn projection_field(expression: &Expression, columns: &[Field]) -> Field {
+  match expression {
+    Expression::Alias(_, alias) => Field::new(None, alias.clone()),
+    Expression::Identifier(name) => {
+      columns.iter().find(|field| field.matches(name)).cloned().unwrap_or_else(|| Field::new(None, name.clone()))
+    }
+    _ => Field::new(None, plan::output_name(expression)),
+  }
+}
+
+fn row_count(expression: &Expression) -> Result<usize, ExecutionError> {
+  match evaluate(expression, &[], &[])? {
+    Value::Int(i) if i >= 0 => Ok(i as usize),
+    Value::Float(f) if f >= 0.0 && f.fract() == 0.0 => Ok(f as usize),
+    value => Err(ExecutionError::InvalidLimit(value)),
+  }
+}
+
+fn referenced_key(name: &str, reference: &catalog::Reference, child: &str, table: &str, key: &[Value]) -> ExecutionError {
+  ExecutionError::ReferencedKey {
+    constraint: name.to_string(),
+    columns: reference.referenced_columns.clone(),
+    key: key.to_vec(),
+    table: table.to_string(),
+    referencing: child.to_string(),
+  }
+}
+
+fn column_positions(names: &[String], columns: &[String]) -> Result<Vec<usize>, ExecutionError> {
+  columns.iter().map(|column| column_index(names, column)).collect()
+}
+
+fn row_key(row: &[Value], positions: &[usize]) -> Vec<Value> {
+  positions.iter().map(|position| row[*position].clone()).collect()
+}
+
+fn column_index(names: &[String], column: &str) -> Result<usize, ExecutionError> {
+  names.iter().position(|name| name == column).ok_or_else(|| ExecutionError::ColumnNotFound(column.to_string()))
+}
+
+fn index_key(index: &catalog::Index, names: &[String], row: &[Value]) -> Vec<Value> {
+  index
+    .columns
+    .iter()
+    .map(|column| names.iter().position(|name| name == column).map_or(Value::Null, |i| row[i].clone()))
+    .map(|value| match &index.path {
+      Some(path) => path.extract(&value),
+      None => value,
+    })
+    .collect()
+}
+
+fn coerce_value(table: &catalog::Table, column: &str, value: Value) -> Result<Value, ExecutionError> {
+  let data_type = match table.get_column(column) {
+    Some(column) => &column.data_type,
+    None => return Err(ExecutionError::ColumnNotFound(column.to_string())),
+  };
+
+  let coerced = value.clone().coerce_to(&data_type.value_type()).ok_or_else(|| ExecutionError::TypeMismatch {
+    column: column.to_string(),
+    expected: data_type.clone(),
+    value: value.clone(),
+  })?;
+
+  let (column, data_type) = (column.to_string(), data_type.clone());
+  data_type.fit(coerced).ok_or(ExecutionError::OutOfRange { column, data_type, value })
+}
+
+// INFO: Rows are stored as a map from column id to value
+fn encode_row(table: &catalog::Table, names: &[String], row: &[Value]) -> Result<Vec<u8>, ExecutionError> {
+  let mut record: HashMap<ColumnId, &Value> = HashMap::with_capacity(names.len());
+  for (name, value) in names.iter().zip(row) {
+    let id = table.column_id(name).ok_or_else(|| ExecutionError::ColumnNotFound(name.clone()))?;
+    record.insert(id, value);
+  }
+
+  Ok(bincode::serialize(&record)?)
+}
+
+// INFO: The stored row as the new definition of the table sees it, a modified column has its value cast to the new type
+// and checked against its range or length
+fn alter_row(
+  schema: &catalog::Table,
+  action: &AlterAction,
+  default: Option<&Value>,
+  names: &[String],
+  new_names: &[String],
+  row: &[Value],
+) -> Result<Vec<Value>, ExecutionError> {
+  let mut altered = Vec::with_capacity(new_names.len());
+
+  for name in new_names {
+    let source = match action {
+      AlterAction::RenameColumn { from, to } if to == name => from,
+      _ => name,
+    };
+    let value = names.iter().position(|column| column == source).map_or(Value::Null, |i| row[i].clone());
+
+    let value = match action {
+      AlterAction::AddColumn { column, .. } if column == name => default.cloned().unwrap_or(Value::Null),
+      AlterAction::ModifyColumn(column) if column == name => {
+        let expected = schema.get_column(name).ok_or_else(|| ExecutionError::ColumnNotFound(name.clone()))?.data_type.clone();
+        let cast = value.clone().convert(&expected);
+        coerce_value(schema, name, cast.ok_or_else(|| ExecutionError::TypeMismatch { column: name.clone(), expected, value })?)?
+      }
+      _ => value,
+    };
+    altered.push(value);
+  }
+
+  Ok(altered)
+}
+
+// INFO: Columns that are missing from the stored row are NULL, values of dropped columns are skipped
+fn decode_row(data: &[u8], table: &catalog::Table, names: &[String]) -> Result<Vec<Value>, ExecutionError> {
+  let mut record: HashMap<ColumnId, Value> = bincode::deserialize(data)?;
+  Ok(names.iter().map(|name| table.column_id(name).and_then(|id| record.remove(&id)).unwrap_or(Value::Null)).collect())
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+
+use crate::sql::catalog::Value;
+
+use super::error::ExecutionError;
+
+// INFO: Output column of an operator, table is the table name or alias the column comes from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+  pub table: Option<String>,
+  pub name: String,
+}
+
+impl Field {
+  pub fn new(table: Option<String>, name: String) -> Self {
+    Self { table, name }
+  }
+
+  // INFO: Matches both `column` and `table.column` identifiers
+  pub fn matches(&self, identifier: &str) -> bool {
+    match identifier.split_once('.') {
+      Some((table, name)) => self.table.as_deref() == Some(table) && self.name == name,
+      None => self.name == identifier,
+    }
+  }
+}
+
+pub fn resolve_column(columns: &[Field], identifier: &str) -> Result<usize, ExecutionError> {
+  let mut matches = columns.iter().enumerate().filter(|(_, field)| field.matches(identifier)).map(|(i, _)| i);
+
+  match (matches.next(), matches.next()) {
+    (Some(index), None) => Ok(index),
+    (Some(_), Some(_)) => Err(ExecutionError::AmbiguousColumn(identifier.to_string())),
+    (None, _) => Err(ExecutionError::ColumnNotFound(identifier.to_string())),
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResultSet {
+  pub columns: Vec<Field>,
+  pub rows: Vec<Vec<Value>>,
+}
+
+impl ResultSet {
+  pub fn new(columns: Vec<Field>, rows: Vec<Vec<Value>>) -> Self {
+    Self { columns, rows }
+  }
+
+  // INFO: Result of statements that don't return rows but change some of them
+  pub fn affected(count: usize) -> Self {
+    Self::new(vec![Field::new(None, "count".to_string())], vec![vec![Value::Int(count as i64)]])
+  }
+}
+
+impl fmt::Display for ResultSet {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.columns.is_empty() {
+      return write!(f, "OK");
+    }
+
+    let rows: Vec<Vec<String>> = self.rows.iter().map(|row| row.iter().map(|value| value.to_string()).collect()).collect();
+
+    let mut widths: Vec<usize> = self.columns.iter().map(|column| column.name.len()).collect();
+    for row in &rows {
+      for (width, value) in widths.iter_mut().zip(row) {
+        *width = (*width).max(value.len());
+      }
+    }
+
+    let header: Vec<String> =
+      self.columns.iter().zip(&widths).map(|(column, width)| format!(" {:width$} ", column.name)).collect();
+    writeln!(f, "{}", header.join("|"))?;
+
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(width + 2)).collect();
+    writeln!(f, "{}", separator.join("+"))?;
+
+    for row in rows {
+      let row: Vec<String> = row.iter().zip(&widths).map(|(value, width)| format!(" {:width$} ", value)).collect();
+      writeln!(f, "{}", row.join("|"))?;
+    }
+
+    write!(f, "({} {})", self.rows.len(), if self.rows.len() == 1 { "row" } else { "rows" })
+  }
+}
@@ -0,0 +1,256 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::sql::catalog::{DataType, Value};
+use crate::sql::parser::ast::{FrameBound, FrameUnits};
+use crate::sql::planner::plan::{Window, WindowFunction};
+
+use super::aggregate::Accumulator;
+use super::error::ExecutionError;
+
+// INFO: Values of one input row that a window function needs, they are computed before the rows are partitioned
+#[derive(Debug)]
+pub struct WindowRow {
+  pub partition: Vec<Value>,
+  pub order: Vec<Value>,
+  pub args: Vec<Value>,
+}
+
+// INFO: Returns the result for every row in the input order, and the input positions in partition and sort order
+pub fn compute(
+  function: &WindowFunction,
+  window: &Window,
+  rows: &[WindowRow],
+) -> Result<(Vec<Value>, Vec<usize>), ExecutionError> {
+  let mut results = vec![Value::Null; rows.len()];
+  let mut sorted = Vec::with_capacity(rows.len());
+
+  for mut partition in partitions(rows) {
+    partition.sort_by(|a, b| compare_order(window, &rows[*a].order, &rows[*b].order));
+
+    let peers = peer_groups(window, rows, &partition);
+    let mut dense_rank = 0;
+
+    // INFO: A frame that starts at the first row only grows, so its aggregate keeps accumulating the rows entering the frame
+    let mut running = match function {
+      WindowFunction::Aggregate(aggregate, distinct) if window.frame.start == FrameBound::UnboundedPreceding => {
+        Some((Accumulator::new(aggregate.clone(), *distinct), 0))
+      }
+      _ => None,
+    };
+
+    for (position, row) in partition.iter().enumerate() {
+      if peers[position].0 == position {
+        dense_rank += 1;
+      }
+
+      results[*row] = match function {
+        WindowFunction::RowNumber => rank(position + 1)?,
+        WindowFunction::Rank => rank(peers[position].0 + 1)?,
+        WindowFunction::DenseRank => rank(dense_rank)?,
+        WindowFunction::Lag | WindowFunction::Lead => shifted(function, rows, &partition, position)?,
+        WindowFunction::FirstValue => {
+          let (start, end) = frame(window, rows, &partition, &peers, position)?;
+          if start < end {
+            rows[partition[start]].args[0].clone()
+          } else {
+            Value::Null
+          }
+        }
+        WindowFunction::Aggregate(aggregate, distinct) => {
+          let (start, end) = frame(window, rows, &partition, &peers, position)?;
+
+          if let Some((accumulator, accumulated)) = &mut running {
+            for row in &partition[*accumulated..end.max(*accumulated)] {
+              accumulator.accumulate(rows[*row].args.clone())?;
+            }
+            *accumulated = end.max(*accumulated);
+
+            results[*row] = accumulator.current()?;
+            continue;
+          }
+
+          let mut accumulator = Accumulator::new(aggregate.clone(), *distinct);
+          for row in &partition[start..end.max(start)] {
+            accumulator.accumulate(rows[*row].args.clone())?;
+          }
+
+          accumulator.finalize()?
+        }
+      };
+    }
+
+    sorted.extend(partition);
+  }
+
+  Ok((results, sorted))
+}
+
+// INFO: Input positions grouped by the partition key, in the order the partitions first appear
+fn partitions(rows: &[WindowRow]) -> Vec<Vec<usize>> {
+  let mut partitions: Vec<Vec<usize>> = Vec::new();
+  let mut positions: HashMap<&[Value], usize> = HashMap::new();
+
+  for (index, row) in rows.iter().enumerate() {
+    match positions.get(row.partition.as_slice()) {
+      Some(position) => partitions[*position].push(index),
+      None => {
+        positions.insert(&row.partition, partitions.len());
+        partitions.push(vec![index]);
+      }
+    }
+  }
+
+  partitions
+}
+
+fn compare_order(window: &Window, a: &[Value], b: &[Value]) -> Ordering {
+  a.iter()
+    .zip(b)
+    .zip(&window.order_by)
+    .map(|((a, b), (_, ascending))| if *ascending { a.sort_cmp(b) } else { b.sort_cmp(a) })
+    .find(|ordering| ordering.is_ne())
+    .unwrap_or(Ordering::Equal)
+}
+
+// INFO: For every position of the sorted partition the first position of its peers and the one after the last
+fn peer_groups(window: &Window, rows: &[WindowRow], partition: &[usize]) -> Vec<(usize, usize)> {
+  let mut peers = Vec::with_capacity(partition.len());
+  let mut start = 0;
+
+  while start < partition.len() {
+    let order = &rows[partition[start]].order;
+    let end = (start + 1..partition.len())
+      .find(|position| compare_order(window, order, &rows[partition[*position]].order).is_ne())
+      .unwrap_or(partition.len());
+
+    peers.extend(std::iter::repeat_n((start, end), end - start));
+    start = end;
+  }
+
+  peers
+}
+
+// INFO: Positions of the sorted partition inside the frame of the current row, the end is exclusive
+fn frame(
+  window: &Window,
+  rows: &[WindowRow],
+  partition: &[usize],
+  peers: &[(usize, usize)],
+  position: usize,
+) -> Result<(usize, usize), ExecutionError> {
+  let length = partition.len();
+  let frame = &window.frame;
+
+  if frame.units == FrameUnits::Rows {
+    let start = match frame.start {
+      FrameBound::UnboundedPreceding => 0,
+      FrameBound::Preceding(offset) => position.saturating_sub(offset as usize),
+      FrameBound::CurrentRow => position,
+      FrameBound::Following(offset) => position.saturating_add(offset as usize).min(length),
+      FrameBound::UnboundedFollowing => length,
+    };
+    let end = match frame.end {
+      FrameBound::UnboundedPreceding => 0,
+      FrameBound::Preceding(offset) => (position + 1).saturating_sub(offset as usize),
+      FrameBound::CurrentRow => position + 1,
+      FrameBound::Following(offset) => position.saturating_add(offset as usize).saturating_add(1).min(length),
+      FrameBound::UnboundedFollowing => length,
+    };
+
+    return Ok((start, end));
+  }
+
+  let distances = range_distances(window, rows, partition, position)?;
+  let start = match frame.start {
+    FrameBound::UnboundedPreceding => 0,
+    FrameBound::CurrentRow => peers[position].0,
+    FrameBound::Preceding(offset) => bound(&distances, peers[position].0, |distance| distance < -(offset as f64)),
+    FrameBound::Following(offset) => bound(&distances, peers[position].0, |distance| distance < offset as f64),
+    FrameBound::UnboundedFollowing => length,
+  };
+  let end = match frame.end {
+    FrameBound::UnboundedPreceding => 0,
+    FrameBound::CurrentRow => peers[position].1,
+    FrameBound::Preceding(offset) => bound(&distances, peers[position].1, |distance| distance <= -(offset as f64)),
+    FrameBound::Following(offset) => bound(&distances, peers[position].1, |distance| distance <= offset as f64),
+    FrameBound::UnboundedFollowing => length,
+  };
+
+  Ok((start, end))
+}
+
+// INFO: First position whose distance doesn't satisfy the predicate, without distances the offset is measured from the peers
+fn bound(distances: &Option<Vec<f64>>, peer: usize, before: impl Fn(f64) -> bool) -> usize {
+  match distances {
+    Some(distances) => distances.partition_point(|distance| before(*distance)),
+    None => peer,
+  }
+}
+
+// INFO: How far the ORDER BY value of every row is from the current one in the sort direction, NULLs are infinitely far
+// INFO: None when the current value is NULL or the window has no offsets, then only the peers are in range
+fn range_distances(
+  window: &Window,
+  rows: &[WindowRow],
+  partition: &[usize],
+  position: usize,
+) -> Result<Option<Vec<f64>>, ExecutionError> {
+  let [(_, ascending)] = window.order_by.as_slice() else {
+    return Ok(None);
+  };
+
+  let Some(current) = numeric(&rows[partition[position]].order[0])? else {
+    return Ok(None);
+  };
+
+  let direction = if *ascending { 1.0 } else { -1.0 };
+  partition
+    .iter()
+    .map(|row| {
+      Ok(match numeric(&rows[*row].order[0])? {
+        Some(value) => (value - current) * direction,
+        None => f64::INFINITY * direction,
+      })
+    })
+    .collect::<Result<Vec<_>, _>>()
+    .map(Some)
+}
+
+fn numeric(value: &Value) -> Result<Option<f64>, ExecutionError> {
+  match value {
+    Value::Int(i) => Ok(Some(*i as f64)),
+    Value::Float(f) => Ok(Some(*f)),
+    Value::Decimal(d) => Ok(Some(d.to_f64())),
+    Value::Null => Ok(None),
+    value => {
+      Err(ExecutionError::InvalidOperands { operator: "RANGE".to_string(), left: value.data_type(), right: DataType::Float })
+    }
+  }
+}
+
+// INFO: LAG reads the row `offset` rows before the current one and LEAD the one after it, outside the partition it is the default
+fn shifted(function: &WindowFunction, rows: &[WindowRow], partition: &[usize], position: usize) -> Result<Value, ExecutionError> {
+  let args = &rows[partition[position]].args;
+  let offset = match args.get(1).cloned().unwrap_or(Value::Int(1)) {
+    Value::Null => return Ok(Value::Null),
+    offset => match offset.clone().coerce_to(&DataType::Int) {
+      Some(Value::Int(offset)) => offset,
+      _ => return Err(ExecutionError::InvalidArgumentValue { function: function.to_string(), value: offset }),
+    },
+  };
+
+  let target = match function {
+    WindowFunction::Lag => (position as i64).saturating_sub(offset),
+    _ => (position as i64).saturating_add(offset),
+  };
+
+  match usize::try_from(target).ok().and_then(|target| partition.get(target)) {
+    Some(row) => Ok(rows[*row].args[0].clone()),
+    None => Ok(args.get(2).cloned().unwrap_or(Value::Null)),
+  }
+}
+
+fn rank(rank: usize) -> Result<Value, ExecutionError> {
+  i64::try_from(rank).map(Value::Int).map_err(|_| ExecutionError::NumericOverflow)
+}
@@ -0,0 +1,378 @@
+use std::sync::Arc;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+use crate::sql::catalog::{parse_path, DataType, Json, Uuid, Value};
+use crate::sql::engine::error::ExecutionError;
+use crate::sql::engine::evaluator::decimal_operands;
+use crate::sql::planner::plan::{JSON_EXTRACT, JSON_EXTRACT_PATH, JSON_EXTRACT_PATH_TEXT};
+
+use super::{ArgumentType, ReturnType, ScalarFunction, Signature};
+
+const TEXT: ArgumentType = ArgumentType::Exact(DataType::Text);
+const INT: ArgumentType = ArgumentType::Exact(DataType::Int);
+const JSON: ArgumentType = ArgumentType::Exact(DataType::Json);
+const F64_EXACT_INTEGER: f64 = 4503599627370496.0; // INFO: 2^52, every f64 from here on is a whole number
+
+// INFO: Functions the executor replaces with the time the statement started at
+pub const NOW: &str = "NOW";
+pub const CURRENT_DATE: &str = "CURRENT_DATE";
+
+pub fn functions() -> Vec<ScalarFunction> {
+  let text = || ReturnType::Exact(DataType::Text);
+
+  vec![
+    scalar("LOWER", vec![TEXT], 1, text(), lower),
+    scalar("UPPER", vec![TEXT], 1, text(), upper),
+    scalar("LENGTH", vec![ArgumentType::String], 1, ReturnType::Exact(DataType::Int), length),
+    scalar("OCTET_LENGTH", vec![ArgumentType::String], 1, ReturnType::Exact(DataType::Int), octet_length),
+    scalar("SUBSTR", vec![ArgumentType::String, INT, INT], 2, ReturnType::FirstArgument, substr),
+    scalar("TRIM", vec![TEXT, TEXT], 1, text(), trim),
+    scalar("REPLACE", vec![TEXT, TEXT, TEXT], 3, text(), replace),
+    ScalarFunction {
+      name: "CONCAT".to_string(),
+      signature: Signature { arguments: vec![ArgumentType::Any], required: 1, variadic: true, returns: text() },
+      strict: false,
+      call: Arc::new(concat),
+    },
+    scalar("ABS", vec![ArgumentType::Numeric], 1, ReturnType::FirstArgument, abs),
+    scalar("ROUND", vec![ArgumentType::Numeric, INT], 1, ReturnType::FirstArgument, round),
+    scalar("FLOOR", vec![ArgumentType::Numeric], 1, ReturnType::FirstArgument, floor),
+    scalar("CEIL", vec![ArgumentType::Numeric], 1, ReturnType::FirstArgument, ceil),
+    scalar("CEILING", vec![ArgumentType::Numeric], 1, ReturnType::FirstArgument, ceil),
+    scalar("MOD", vec![ArgumentType::Numeric, ArgumentType::Numeric], 2, ReturnType::Numeric, modulo),
+    scalar("POWER", vec![ArgumentType::Numeric, ArgumentType::Numeric], 2, ReturnType::Exact(DataType::Float), power),
+    scalar(NOW, vec![], 0, ReturnType::Exact(DataType::TimestampTz), now),
+    scalar(CURRENT_DATE, vec![], 0, ReturnType::Exact(DataType::Date), current_date),
+    scalar("DATE_TRUNC", vec![TEXT, ArgumentType::Temporal], 2, ReturnType::Exact(DataType::DateTime), date_trunc),
+    scalar("EXTRACT", vec![TEXT, ArgumentType::Temporal], 2, ReturnType::Exact(DataType::Float), extract),
+    variadic(JSON_EXTRACT_PATH, vec![JSON, ArgumentType::Any], 2, ReturnType::Exact(DataType::Json), json_extract_path),
+    variadic(JSON_EXTRACT_PATH_TEXT, vec![JSON, ArgumentType::Any], 2, text(), json_extract_path_text),
+    scalar(JSON_EXTRACT, vec![JSON, TEXT], 2, ReturnType::Exact(DataType::Json), json_extract),
+    scalar("JSON_ARRAY_LENGTH", vec![JSON], 1, ReturnType::Exact(DataType::Int), json_array_length),
+    scalar("JSON_TYPEOF", vec![JSON], 1, text(), json_typeof),
+    scalar("GEN_RANDOM_UUID", vec![], 0, ReturnType::Exact(DataType::Uuid), gen_random_uuid),
+  ]
+}
+
+type Builtin = fn(&[Value]) -> Result<Value, ExecutionError>;
+
+fn scalar(name: &str, arguments: Vec<ArgumentType>, required: usize, returns: ReturnType, call: Builtin) -> ScalarFunction {
+  let signature = Signature { arguments, required, variadic: false, returns };
+  ScalarFunction { name: name.to_string(), signature, strict: true, call: Arc::new(call) }
+}
+
+// INFO: The last argument can be repeated
+fn variadic(name: &str, arguments: Vec<ArgumentType>, required: usize, returns: ReturnType, call: Builtin) -> ScalarFunction {
+  let signature = Signature { arguments, required, variadic: true, returns };
+  ScalarFunction { name: name.to_string(), signature, strict: true, call: Arc::new(call) }
+}
+
+fn lower(args: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::Text(text("LOWER", &args[0])?.to_lowercase()))
+}
+
+fn upper(args: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::Text(text("UPPER", &args[0])?.to_uppercase()))
+}
+
+// INFO: Characters of a string and bytes of a byte string
+fn length(args: &[Value]) -> Result<Value, ExecutionError> {
+  let length = match &args[0] {
+    Value::Bytes(bytes) => bytes.len(),
+    value => text("LENGTH", value)?.chars().count(),
+  };
+  i64::try_from(length).map(Value::Int).map_err(|_| ExecutionError::NumericOverflow)
+}
+
+// INFO: Bytes a string takes in UTF-8
+fn octet_length(args: &[Value]) -> Result<Value, ExecutionError> {
+  let length = match &args[0] {
+    Value::Bytes(bytes) => bytes.len(),
+    value => text("OCTET_LENGTH", value)?.len(),
+  };
+  i64::try_from(length).map(Value::Int).map_err(|_| ExecutionError::NumericOverflow)
+}
+
+// INFO: Positions start at 1, a start before the first character still counts towards the length.
+// Byte strings are cut by bytes
+fn substr(args: &[Value]) -> Result<Value, ExecutionError> {
+  let start = integer("SUBSTR", &args[1])?;
+  let end = match args.get(2) {
+    Some(length) => match integer("SUBSTR", length)? {
+      length if length < 0 => return Err(invalid_value("SUBSTR", &args[2])),
+      length => start.saturating_add(length),
+    },
+    None => i64::MAX,
+  };
+
+  let (from, to) = (start.max(1) - 1, end.max(1) - 1);
+  let (skip, take) = (from as usize, (to - from).max(0) as usize);
+
+  match &args[0] {
+    Value::Bytes(bytes) => Ok(Value::Bytes(bytes.iter().skip(skip).take(take).copied().collect())),
+    value => Ok(Value::Text(text("SUBSTR", value)?.chars().skip(skip).take(take).collect())),
+  }
+}
+
+fn trim(args: &[Value]) -> Result<Value, ExecutionError> {
+  let string = text("TRIM", &args[0])?;
+  let characters = match args.get(1) {
+    Some(characters) => text("TRIM", characters)?,
+    None => " ",
+  };
+
+  Ok(Value::Text(string.trim_matches(|c| characters.contains(c)).to_string()))
+}
+
+fn replace(args: &[Value]) -> Result<Value, ExecutionError> {
+  let (string, from, to) = (text("REPLACE", &args[0])?, text("REPLACE", &args[1])?, text("REPLACE", &args[2])?);
+
+  if from.is_empty() {
+    return Ok(Value::Text(string.to_string()));
+  }
+
+  Ok(Value::Text(string.replace(from, to)))
+}
+
+// INFO: NULL arguments are skipped instead of making the whole result NULL
+fn concat(args: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::Text(args.iter().filter(|value| !value.is_null()).map(Value::to_string).collect()))
+}
+
+fn abs(args: &[Value]) -> Result<Value, ExecutionError> {
+  match &args[0] {
+    Value::Int(i) => i.checked_abs().map(Value::Int).ok_or(ExecutionError::NumericOverflow),
+    Value::Float(f) => Ok(Value::Float(f.abs())),
+    Value::Decimal(d) => Ok(Value::Decimal(d.abs())),
+    value => Err(invalid_argument("ABS", value)),
+  }
+}
+
+// INFO: Halves are rounded away from zero, negative digits round to tens, hundreds and so on
+fn round(args: &[Value]) -> Result<Value, ExecutionError> {
+  let digits = args.get(1).map(|digits| integer("ROUND", digits)).transpose()?.unwrap_or(0);
+
+  match &args[0] {
+    Value::Int(i) if digits >= 0 => Ok(Value::Int(*i)),
+    Value::Int(i) => to_int(round_float(*i as f64, digits)),
+    Value::Float(f) => Ok(Value::Float(round_float(*f, digits))),
+    Value::Decimal(d) => d.round(digits).map(Value::Decimal).ok_or(ExecutionError::NumericOverflow),
+    value => Err(invalid_argument("ROUND", value)),
+  }
+}
+
+// INFO: A scale past the precision of f64 leaves nothing to round, one below the largest power of ten rounds everything to zero
+fn round_float(f: f64, digits: i64) -> f64 {
+  let factor = 10f64.powi(digits.clamp(-400, 400) as i32);
+  let scaled = f * factor;
+
+  if factor == 0.0 {
+    0.0
+  } else if !scaled.is_finite() || scaled.abs() >= F64_EXACT_INTEGER {
+    f
+  } else {
+    scaled.round() / factor
+  }
+}
+
+fn floor(args: &[Value]) -> Result<Value, ExecutionError> {
+  match &args[0] {
+    Value::Float(f) => Ok(Value::Float(f.floor())),
+    Value::Decimal(d) => d.floor().map(Value::Decimal).ok_or(ExecutionError::NumericOverflow),
+    Value::Int(i) => Ok(Value::Int(*i)),
+    value => Err(invalid_argument("FLOOR", value)),
+  }
+}
+
+fn ceil(args: &[Value]) -> Result<Value, ExecutionError> {
+  match &args[0] {
+    Value::Float(f) => Ok(Value::Float(f.ceil())),
+    Value::Decimal(d) => d.ceil().map(Value::Decimal).ok_or(ExecutionError::NumericOverflow),
+    Value::Int(i) => Ok(Value::Int(*i)),
+    value => Err(invalid_argument("CEIL", value)),
+  }
+}
+
+fn modulo(args: &[Value]) -> Result<Value, ExecutionError> {
+  if let Some((a, b)) = decimal_operands(&args[0], &args[1]) {
+    if b.is_zero() {
+      return Err(ExecutionError::DivisionByZero);
+    }
+
+    return a.checked_rem(b).map(Value::Decimal).ok_or(ExecutionError::NumericOverflow);
+  }
+
+  match (&args[0], &args[1]) {
+    (Value::Int(_), Value::Int(0)) => Err(ExecutionError::DivisionByZero),
+    (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.checked_rem(*b).unwrap_or(0))),
+    (a, b) => match (number("MOD", a)?, number("MOD", b)?) {
+      (_, 0.0) => Err(ExecutionError::DivisionByZero),
+      (a, b) => Ok(Value::Float(a % b)),
+    },
+  }
+}
+
+fn power(args: &[Value]) -> Result<Value, ExecutionError> {
+  let result = number("POWER", &args[0])?.powf(number("POWER", &args[1])?);
+
+  match result {
+    result if result.is_nan() => Err(invalid_value("POWER", &args[0])),
+    result if result.is_infinite() => Err(ExecutionError::NumericOverflow),
+    result => Ok(Value::Float(result)),
+  }
+}
+
+fn now(_: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::TimestampTz(Utc::now()))
+}
+
+fn current_date(_: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::Date(Local::now().date_naive()))
+}
+
+fn date_trunc(args: &[Value]) -> Result<Value, ExecutionError> {
+  let timestamp = timestamp("DATE_TRUNC", &args[1])?;
+  let (date, time) = (timestamp.date(), timestamp.time());
+
+  let (date, (hour, minute, second)) = match text("DATE_TRUNC", &args[0])?.to_lowercase().as_str() {
+    "year" => (NaiveDate::from_ymd_opt(date.year(), 1, 1), (0, 0, 0)),
+    "quarter" => (NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1), (0, 0, 0)),
+    "month" => (NaiveDate::from_ymd_opt(date.year(), date.month(), 1), (0, 0, 0)),
+    "week" => (date.checked_sub_signed(Duration::days(date.weekday().num_days_from_monday() as i64)), (0, 0, 0)),
+    "day" => (Some(date), (0, 0, 0)),
+    "hour" => (Some(date), (time.hour(), 0, 0)),
+    "minute" => (Some(date), (time.hour(), time.minute(), 0)),
+    "second" => (Some(date), (time.hour(), time.minute(), time.second())),
+    _ => return Err(invalid_value("DATE_TRUNC", &args[0])),
+  };
+
+  date
+    .and_then(|date| date.and_hms_opt(hour, minute, second))
+    .map(Value::Timestamp)
+    .ok_or_else(|| invalid_value("DATE_TRUNC", &args[1]))
+}
+
+fn extract(args: &[Value]) -> Result<Value, ExecutionError> {
+  let timestamp = timestamp("EXTRACT", &args[1])?;
+  let seconds = timestamp.second() as f64 + timestamp.nanosecond() as f64 / 1e9;
+
+  let value = match text("EXTRACT", &args[0])?.to_lowercase().as_str() {
+    "year" => timestamp.year() as f64,
+    "quarter" => (timestamp.month0() / 3 + 1) as f64,
+    "month" => timestamp.month() as f64,
+    "week" => timestamp.iso_week().week() as f64,
+    "day" => timestamp.day() as f64,
+    "dow" => timestamp.weekday().num_days_from_sunday() as f64,
+    "doy" => timestamp.ordinal() as f64,
+    "hour" => timestamp.hour() as f64,
+    "minute" => timestamp.minute() as f64,
+    "second" => seconds,
+    "epoch" => timestamp.and_utc().timestamp() as f64 + timestamp.nanosecond() as f64 / 1e9,
+    _ => return Err(invalid_value("EXTRACT", &args[0])),
+  };
+
+  Ok(Value::Float(value))
+}
+
+fn json_extract_path(args: &[Value]) -> Result<Value, ExecutionError> {
+  let json = json_path(JSON_EXTRACT_PATH, args)?;
+  Ok(json.map_or(Value::Null, |json| Value::Json(json.clone())))
+}
+
+// INFO: A string comes without its quotes, a JSON null is NULL
+fn json_extract_path_text(args: &[Value]) -> Result<Value, ExecutionError> {
+  let json = json_path(JSON_EXTRACT_PATH_TEXT, args)?;
+  Ok(json.and_then(Json::to_text).map_or(Value::Null, Value::Text))
+}
+
+// INFO: The path is written as `{a,b,0}` or `$.a.b[0]`
+fn json_extract(args: &[Value]) -> Result<Value, ExecutionError> {
+  let steps = parse_path(text(JSON_EXTRACT, &args[1])?).ok_or_else(|| invalid_value(JSON_EXTRACT, &args[1]))?;
+  let json = json(JSON_EXTRACT, &args[0])?.get_path(&steps);
+
+  Ok(json.map_or(Value::Null, |json| Value::Json(json.clone())))
+}
+
+fn json_array_length(args: &[Value]) -> Result<Value, ExecutionError> {
+  match json("JSON_ARRAY_LENGTH", &args[0])? {
+    Json::Array(items) => i64::try_from(items.len()).map(Value::Int).map_err(|_| ExecutionError::NumericOverflow),
+    _ => Err(invalid_value("JSON_ARRAY_LENGTH", &args[0])),
+  }
+}
+
+fn json_typeof(args: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::Text(json("JSON_TYPEOF", &args[0])?.type_name().to_string()))
+}
+
+// INFO: Follows the keys after the document, a key is an object key or a position in an array
+fn json_path<'a>(function: &str, args: &'a [Value]) -> Result<Option<&'a Json>, ExecutionError> {
+  let mut json = Some(json(function, &args[0])?);
+
+  for key in &args[1..] {
+    let key = match key {
+      Value::Text(key) => key.clone(),
+      Value::Int(i) => i.to_string(),
+      Value::Float(f) if f.fract() == 0.0 => (*f as i64).to_string(),
+      key => return Err(invalid_value(function, key)),
+    };
+    json = json.and_then(|json| json.get(&key));
+  }
+
+  Ok(json)
+}
+
+fn json<'a>(function: &str, value: &'a Value) -> Result<&'a Json, ExecutionError> {
+  match value {
+    Value::Json(json) => Ok(json),
+    value => Err(invalid_argument(function, value)),
+  }
+}
+
+fn gen_random_uuid(_: &[Value]) -> Result<Value, ExecutionError> {
+  Ok(Value::Uuid(Uuid::new_v4()))
+}
+
+fn text<'a>(function: &str, value: &'a Value) -> Result<&'a str, ExecutionError> {
+  match value {
+    Value::Text(s) => Ok(s),
+    value => Err(invalid_argument(function, value)),
+  }
+}
+
+fn integer(function: &str, value: &Value) -> Result<i64, ExecutionError> {
+  match value {
+    Value::Int(i) => Ok(*i),
+    value => Err(invalid_argument(function, value)),
+  }
+}
+
+fn number(function: &str, value: &Value) -> Result<f64, ExecutionError> {
+  match value {
+    Value::Int(i) => Ok(*i as f64),
+    Value::Float(f) => Ok(*f),
+    Value::Decimal(d) => Ok(d.to_f64()),
+    value => Err(invalid_argument(function, value)),
+  }
+}
+
+// INFO: A date is the midnight at its start and a TIMESTAMPTZ is read in UTC
+fn timestamp(function: &str, value: &Value) -> Result<NaiveDateTime, ExecutionError> {
+  value.to_naive().ok_or_else(|| invalid_argument(function, value))
+}
+
+fn to_int(value: f64) -> Result<Value, ExecutionError> {
+  if value < i64::MIN as f64 || value > i64::MAX as f64 {
+    return Err(ExecutionError::NumericOverflow);
+  }
+
+  Ok(Value::Int(value as i64))
+}
+
+fn invalid_argument(function: &str, value: &Value) -> ExecutionError {
+  ExecutionError::InvalidFunctionArgument { function: function.to_string(), data_type: value.data_type() }
+}
+
+fn invalid_value(function: &str, value: &Value) -> ExecutionError {
+  ExecutionError::InvalidArgumentValue { function: function.to_string(), value: value.clone() }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::sql::catalog::{DataType, Value};
+use crate::sql::engine::error::ExecutionError;
+use crate::sql::planner::error::PlannerError;
+use crate::sql::planner::plan::{AggregateFunction, WindowFunction};
+
+pub mod builtin;
+pub mod udf;
+
+pub use udf::{AggregateUdf, ScalarUdf, UserAggregate};
+
+pub type Implementation = Arc<dyn Fn(&[Value]) -> Result<Value, ExecutionError> + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentType {
+  Exact(DataType), // INFO: values are coerced to the type before the call
+  Numeric,
+  Temporal,
+  String, // INFO: TEXT or BYTEA
+  Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReturnType {
+  Exact(DataType),
+  FirstArgument,
+  Numeric, // INFO: INT when every argument is an INT, FLOAT when one of them is a FLOAT and DECIMAL otherwise
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+  pub arguments: Vec<ArgumentType>,
+  pub required: usize,
+  pub variadic: bool, // INFO: the last argument can be repeated
+  pub returns: ReturnType,
+}
+
+#[derive(Clone)]
+pub struct ScalarFunction {
+  pub name: String,
+  pub signature: Signature,
+  pub strict: bool, // INFO: a NULL argument makes the result NULL without calling the function
+  pub call: Implementation,
+}
+
+#[derive(Debug)]
+pub struct FunctionRegistry {
+  functions: HashMap<String, ScalarFunction>,
+  aggregates: HashMap<String, UserAggregate>,
+}
+
+impl ArgumentType {
+  // INFO: NULL is accepted everywhere and any number where a number is expected. Types are compared by the kind of value
+  // they hold, so the size of an integer or the length of a string doesn't matter until the value is coerced
+  pub fn accepts(&self, data_type: &DataType) -> bool {
+    let number = |data_type: &DataType| matches!(data_type, DataType::Int | DataType::Float | DataType::Decimal(_));
+    let temporal = |data_type: &DataType| matches!(data_type, DataType::Date | DataType::DateTime | DataType::TimestampTz);
+
+    match (self, data_type.value_type()) {
+      (_, DataType::Null) | (ArgumentType::Any, _) => true,
+      (ArgumentType::Numeric, found) => number(&found),
+      (ArgumentType::Temporal, found) => temporal(&found),
+      (ArgumentType::String, found) => matches!(found, DataType::Text | DataType::Bytea),
+      (ArgumentType::Exact(expected), found) => match expected.value_type() {
+        expected if number(&expected) => number(&found),
+        DataType::DateTime => temporal(&found),
+        DataType::Json => matches!(found, DataType::Text | DataType::Json), // INFO: the text is parsed when the function is called
+        expected => expected == found,
+      },
+    }
+  }
+
+  fn coerce(&self, value: Value) -> Option<Value> {
+    match self {
+      ArgumentType::Exact(data_type) => value.coerce_to(data_type).and_then(|value| data_type.fit(value)),
+      argument => argument.accepts(&value.data_type()).then_some(value),
+    }
+  }
+}
+
+impl Signature {
+  pub fn argument(&self, position: usize) -> Option<&ArgumentType> {
+    match self.arguments.get(position) {
+      None if self.variadic => self.arguments.last(),
+      argument => argument,
+    }
+  }
+
+  pub fn check(&self, function: &str, types: &[Option<DataType>]) -> Result<(), PlannerError> {
+    if types.len() < self.required || (!self.variadic && types.len() > self.arguments.len()) {
+      return Err(PlannerError::FunctionArgumentCount {
+        function: function.to_string(),
+        expected: self.arity(),
+        found: types.len(),
+      });
+    }
+
+    for (position, data_type) in types.iter().enumerate() {
+      let (Some(expected), Some(found)) = (self.argument(position), data_type) else { continue };
+
+      if !expected.accepts(found) {
+        return Err(PlannerError::FunctionArgumentType {
+          function: function.to_string(),
+          position: position + 1,
+          expected: expected.to_string(),
+          found: found.clone(),
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  // INFO: None when the result depends on argument types that are not known yet
+  pub fn return_type(&self, types: &[Option<DataType>]) -> Option<DataType> {
+    match &self.returns {
+      ReturnType::Exact(data_type) => Some(data_type.clone()),
+      ReturnType::FirstArgument => types.first().cloned().flatten(),
+      ReturnType::Numeric => {
+        let types = types.iter().map(|data_type| data_type.as_ref().map(DataType::value_type)).collect::<Vec<_>>();
+
+        if types.iter().all(|data_type| data_type == &Some(DataType::Int)) {
+          Some(DataType::Int)
+        } else if types.contains(&Some(DataType::Float)) {
+          Some(DataType::Float)
+        } else {
+          types.contains(&Some(DataType::Decimal(None))).then_some(DataType::Decimal(None))
+        }
+      }
+    }
+  }
+
+  pub fn coerce(&self, function: &str, args: Vec<Value>) -> Result<Vec<Value>, ExecutionError> {
+    args
+      .into_iter()
+      .enumerate()
+      .map(|(position, value)| {
+        let data_type = value.data_type();
+
+        self
+          .argument(position)
+          .and_then(|argument| argument.coerce(value))
+          .ok_or_else(|| ExecutionError::InvalidFunctionArgument { function: function.to_string(), data_type })
+      })
+      .collect()
+  }
+
+  // INFO: A result of a declared type is coerced to it, so a function can't return a value the planner doesn't expect
+  pub fn check_result(&self, function: &str, value: Value) -> Result<Value, ExecutionError> {
+    match &self.returns {
+      ReturnType::Exact(data_type) => value.clone().coerce_to(data_type).ok_or_else(|| ExecutionError::InvalidFunctionResult {
+        function: function.to_string(),
+        expected: data_type.clone(),
+        value,
+      }),
+      _ => Ok(value),
+    }
+  }
+
+  // INFO: Every declared argument is required
+  pub fn exact(arguments: Vec<DataType>, returns: DataType) -> Self {
+    let arguments = arguments.into_iter().map(ArgumentType::Exact).collect::<Vec<_>>();
+    Signature { required: arguments.len(), arguments, variadic: false, returns: ReturnType::Exact(returns) }
+  }
+
+  fn arity(&self) -> String {
+    match self.arguments.len() - self.required {
+      _ if self.variadic => format!("at least {}", self.required),
+      0 => self.required.to_string(),
+      1 => format!("{} or {}", self.required, self.arguments.len()),
+      _ => format!("{} to {}", self.required, self.arguments.len()),
+    }
+  }
+}
+
+impl ScalarFunction {
+  pub fn invoke(&self, args: Vec<Value>) -> Result<Value, ExecutionError> {
+    if self.strict && args.iter().any(Value::is_null) {
+      return Ok(Value::Null);
+    }
+
+    let args = self.signature.coerce(&self.name, args)?;
+    self.signature.check_result(&self.name, (self.call)(&args)?)
+  }
+}
+
+impl FunctionRegistry {
+  pub fn new() -> Self {
+    let functions = builtin::functions().into_iter().map(|function| (function.name.to_uppercase(), function)).collect();
+    Self { functions, aggregates: HashMap::new() }
+  }
+
+  // INFO: A name can only be used once, built-in functions, aggregates and window functions can't be replaced
+  pub fn register(&mut self, function: ScalarFunction) -> Result<(), PlannerError> {
+    self.check_name(&function.name)?;
+    self.functions.insert(function.name.to_uppercase(), function);
+
+    Ok(())
+  }
+
+  pub fn register_aggregate(&mut self, aggregate: UserAggregate) -> Result<(), PlannerError> {
+    self.check_name(&aggregate.name)?;
+    self.aggregates.insert(aggregate.name.to_uppercase(), aggregate);
+
+    Ok(())
+  }
+
+  fn check_name(&self, name: &str) -> Result<(), PlannerError> {
+    if self.get(name).is_some() || self.aggregate(name).is_some() || WindowFunction::from_name(name).is_some() {
+      return Err(PlannerError::FunctionAlreadyExists(name.to_string()));
+    }
+
+    Ok(())
+  }
+
+  pub fn get(&self, name: &str) -> Option<&ScalarFunction> {
+    self.functions.get(&name.to_uppercase())
+  }
+
+  pub fn aggregate(&self, name: &str) -> Option<AggregateFunction> {
+    AggregateFunction::from_name(name).or_else(|| self.aggregates.get(&name.to_uppercase()).cloned().map(AggregateFunction::User))
+  }
+}
+
+impl Default for FunctionRegistry {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl fmt::Debug for ScalarFunction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name)
+  }
+}
+
+impl PartialEq for ScalarFunction {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name
+  }
+}
+
+impl fmt::Display for ArgumentType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ArgumentType::Exact(data_type) => write!(f, "{}", data_type),
+      ArgumentType::Numeric => write!(f, "a number"),
+      ArgumentType::Temporal => write!(f, "a date or a timestamp"),
+      ArgumentType::String => write!(f, "a string or bytes"),
+      ArgumentType::Any => write!(f, "any type"),
+    }
+  }
+}
@@ -0,0 +1,127 @@
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::sql::catalog::{DataType, Value};
+use crate::sql::engine::error::ExecutionError;
+
+use super::{ScalarFunction, Signature};
+
+// INFO: Scalar function implemented by the host, the arguments are coerced to the declared types before the call
+pub trait ScalarUdf: Send + Sync + 'static {
+  fn name(&self) -> &str;
+  fn arguments(&self) -> Vec<DataType>;
+  fn return_type(&self) -> DataType;
+  fn invoke(&self, args: &[Value]) -> Result<Value, ExecutionError>;
+
+  // INFO: Whether a NULL argument makes the result NULL without calling the function
+  fn strict(&self) -> bool {
+    true
+  }
+}
+
+// INFO: Aggregate implemented by the host, rows with a NULL argument are skipped like for the built-in aggregates
+pub trait AggregateUdf: Send + Sync + 'static {
+  type State: Clone + Send + 'static; // INFO: cloned to read the result of a window frame that keeps growing
+
+  fn name(&self) -> &str;
+  fn arguments(&self) -> Vec<DataType>;
+  fn return_type(&self) -> DataType;
+  fn init(&self) -> Self::State;
+  fn accumulate(&self, state: &mut Self::State, args: &[Value]) -> Result<(), ExecutionError>;
+
+  // INFO: Combines the state of another part of the same rows into this one. It isn't called yet since the executor
+  // accumulates every group and every window frame in a single state, it is there for when aggregation gets split up
+  fn merge(&self, state: &mut Self::State, other: Self::State) -> Result<(), ExecutionError>;
+  fn finalize(&self, state: Self::State) -> Result<Value, ExecutionError>;
+}
+
+pub type AggregateState = Box<dyn Any + Send>;
+
+// INFO: AggregateUdf with the state type erased, so aggregates with different states can be stored together
+trait Aggregate: Send + Sync {
+  fn init(&self) -> AggregateState;
+  fn accumulate(&self, state: &mut AggregateState, args: &[Value]) -> Result<(), ExecutionError>;
+  fn merge(&self, state: &mut AggregateState, other: AggregateState) -> Result<(), ExecutionError>;
+  fn finalize(&self, state: AggregateState) -> Result<Value, ExecutionError>;
+  fn copy(&self, state: &AggregateState) -> AggregateState;
+}
+
+impl<A: AggregateUdf> Aggregate for A {
+  fn init(&self) -> AggregateState {
+    Box::new(AggregateUdf::init(self))
+  }
+
+  fn accumulate(&self, state: &mut AggregateState, args: &[Value]) -> Result<(), ExecutionError> {
+    AggregateUdf::accumulate(self, state.downcast_mut().expect("state created by init"), args)
+  }
+
+  fn merge(&self, state: &mut AggregateState, other: AggregateState) -> Result<(), ExecutionError> {
+    let other = *other.downcast().expect("state created by init");
+    AggregateUdf::merge(self, state.downcast_mut().expect("state created by init"), other)
+  }
+
+  fn finalize(&self, state: AggregateState) -> Result<Value, ExecutionError> {
+    AggregateUdf::finalize(self, *state.downcast().expect("state created by init"))
+  }
+
+  fn copy(&self, state: &AggregateState) -> AggregateState {
+    Box::new(state.downcast_ref::<A::State>().expect("state created by init").clone())
+  }
+}
+
+#[derive(Clone)]
+pub struct UserAggregate {
+  pub name: String,
+  pub signature: Signature,
+  implementation: Arc<dyn Aggregate>,
+}
+
+impl ScalarFunction {
+  pub fn from_udf(function: impl ScalarUdf) -> Self {
+    let signature = Signature::exact(function.arguments(), function.return_type());
+    let (name, strict) = (function.name().to_string(), function.strict());
+
+    ScalarFunction { name, signature, strict, call: Arc::new(move |args| function.invoke(args)) }
+  }
+}
+
+impl UserAggregate {
+  pub fn new(aggregate: impl AggregateUdf) -> Self {
+    let signature = Signature::exact(aggregate.arguments(), aggregate.return_type());
+    UserAggregate { name: aggregate.name().to_string(), signature, implementation: Arc::new(aggregate) }
+  }
+
+  pub fn init(&self) -> AggregateState {
+    self.implementation.init()
+  }
+
+  pub fn accumulate(&self, state: &mut AggregateState, args: Vec<Value>) -> Result<(), ExecutionError> {
+    let args = self.signature.coerce(&self.name, args)?;
+    self.implementation.accumulate(state, &args)
+  }
+
+  pub fn merge(&self, state: &mut AggregateState, other: AggregateState) -> Result<(), ExecutionError> {
+    self.implementation.merge(state, other)
+  }
+
+  pub fn finalize(&self, state: AggregateState) -> Result<Value, ExecutionError> {
+    self.signature.check_result(&self.name, self.implementation.finalize(state)?)
+  }
+
+  pub fn copy(&self, state: &AggregateState) -> AggregateState {
+    self.implementation.copy(state)
+  }
+}
+
+impl fmt::Debug for UserAggregate {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name)
+  }
+}
+
+impl PartialEq for UserAggregate {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name
+  }
+}
@@ -1,9 +1,9 @@
-
-pub mod parser;
-pub mod planner;
-pub mod optimizer;
 pub mod catalog;
+pub mod constants;
 pub mod cost;
 pub mod engine;
-pub mod constants;
-mod schema;
\ No newline at end of file
+pub mod function;
+pub mod optimizer;
+pub mod parser;
+pub mod planner;
+mod schema;
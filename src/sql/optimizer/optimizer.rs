@@ -1,28 +1,201 @@
-#![allow(unused)]
-use std::collections::{BTreeMap, HashMap};
-
 use crate::sql::{
-  catalog::{self, Catalog},
-  planner::plan::{Expression, Node, Plan, Value},
+  catalog::{self, Catalog, DataType, JsonPath},
+  planner::{
+    error::PlannerError,
+    plan::{
+      join_conjunction, json_path, split_conjunction, AlterAction, ConflictAction, Expression, JoinType, Node, OnConflict, Plan,
+      RowConstraints, SetOperator, Value,
+    },
+  },
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PhysicalPlan {
   pub node: Op,
-  cost: Option<i32>,
   childern: Option<Vec<PhysicalPlan>>,
 }
 
-#[derive(Debug)]
+impl PhysicalPlan {
+  fn new(node: Op, childern: Option<Vec<PhysicalPlan>>) -> Self {
+    Self { node, childern }
+  }
+
+  pub fn children(&self) -> &[PhysicalPlan] {
+    self.childern.as_deref().unwrap_or_default()
+  }
+
+  // INFO: Expressions of the whole plan, the ones of the children included
+  pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+    let mut expressions = self.node.expressions_mut();
+    for child in self.childern.iter_mut().flatten() {
+      expressions.extend(child.expressions_mut());
+    }
+
+    expressions
+  }
+}
+
+impl Op {
+  // INFO: Expressions held by this operation, the stored defaults and checks it applies to the rows included
+  pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+    match self {
+      Op::SubqueryScan { .. }
+      | Op::RecursiveUnion { .. }
+      | Op::WorkingTableScan { .. }
+      | Op::HashSetOperation { .. }
+      | Op::CreateTable { .. }
+      | Op::DropTable { .. }
+      | Op::Describe { .. } => vec![],
+      Op::TableScan { filter, .. } | Op::IndexScan { filter, .. } => filter.iter_mut().collect(),
+      Op::Projection { columns: expressions } | Op::Window { functions: expressions } => expressions.iter_mut().collect(),
+      Op::Filter { condition: expression }
+      | Op::NestedLoopJoin { condition: expression, .. }
+      | Op::Limit { limit: expression }
+      | Op::Offset { offset: expression } => vec![expression],
+      Op::Sort { order } => order.iter_mut().map(|(expression, _)| expression).collect(),
+      Op::Values { rows } => rows.iter_mut().flatten().collect(),
+      Op::HashJoin { left_keys, right_keys, condition, .. } => left_keys.iter_mut().chain(right_keys).chain(condition).collect(),
+      Op::HashAggregate { group_by, aggregates } => group_by.iter_mut().chain(aggregates).collect(),
+      Op::HashDistinct { on } | Op::StreamingDistinct { on } => on.iter_mut().flatten().collect(),
+      Op::AlterTable { action, constraints, .. } => {
+        let mut expressions = constraints.expressions_mut();
+        if let AlterAction::AddColumn { default: Some(default), .. } = action {
+          expressions.push(default);
+        }
+
+        expressions
+      }
+      Op::Insert { on_conflict, returning, constraints, .. } => {
+        let mut expressions = constraints.expressions_mut();
+        expressions.extend(returning.iter_mut().flatten());
+
+        if let Some(OnConflict { action: ConflictAction::DoUpdate { values, condition }, .. }) = on_conflict {
+          expressions.extend(values.iter_mut().map(|(_, value)| value).chain(condition));
+        }
+
+        expressions
+      }
+      Op::Update { values, returning, constraints, .. } => {
+        let mut expressions = constraints.expressions_mut();
+        expressions.extend(values.iter_mut().map(|(_, value)| value).chain(returning.iter_mut().flatten()));
+
+        expressions
+      }
+      Op::Delete { returning, .. } => returning.iter_mut().flatten().collect(),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
 pub enum Op {
-  TableScan { data_source: String, alias: Option<String> }, //filter
-  IndexScan { data_source: String, alias: Option<String>, index: String }, // filter
-  Projection { columns: Vec<String> },
+  TableScan {
+    data_source: String,
+    alias: Option<String>,
+    filter: Option<Expression>,
+  },
+  IndexScan {
+    data_source: String,
+    alias: Option<String>,
+    index: String,
+    range: KeyRange,
+    filter: Option<Expression>,
+  },
+  Projection {
+    columns: Vec<Expression>,
+  },
+  Filter {
+    condition: Expression,
+  },
+  Sort {
+    order: Vec<(Expression, bool)>,
+  },
+  Limit {
+    limit: Expression,
+  },
+  Offset {
+    offset: Expression,
+  },
+  Values {
+    rows: Vec<Vec<Expression>>,
+  },
+  SubqueryScan {
+    alias: String,
+  },
+  NestedLoopJoin {
+    join_type: JoinType,
+    condition: Expression,
+  },
+  HashJoin {
+    join_type: JoinType,
+    left_keys: Vec<Expression>,
+    right_keys: Vec<Expression>,
+    condition: Option<Expression>,
+  },
+  RecursiveUnion {
+    name: String,
+    distinct: bool,
+  },
+  WorkingTableScan {
+    name: String,
+  },
+  HashSetOperation {
+    operator: SetOperator,
+    all: bool,
+  },
+  HashAggregate {
+    group_by: Vec<Expression>,
+    aggregates: Vec<Expression>,
+  },
+  Window {
+    functions: Vec<Expression>,
+  }, // INFO: partitions and sorts its input separately for every function
+  HashDistinct {
+    on: Option<Vec<Expression>>,
+  },
+  StreamingDistinct {
+    on: Option<Vec<Expression>>,
+  }, // INFO: input is sorted on the key, so duplicates are next to each other
+
+  CreateTable {
+    table: catalog::Table,
+  },
+  DropTable {
+    table_name: String,
+  },
+  Describe {
+    table: String,
+  },
+  AlterTable {
+    table: String,
+    schema: catalog::Table,
+    action: AlterAction,
+    constraints: RowConstraints,
+  }, // INFO: new definition
 
-  CreateTable { table: catalog::Table },
-  DropTable { table_name: String },
+  Insert {
+    data_source: String,
+    columns: Vec<String>,
+    on_conflict: Option<OnConflict>,
+    returning: Option<Vec<Expression>>,
+    constraints: RowConstraints,
+  },
+  Update {
+    data_source: String,
+    values: Vec<(String, Expression)>,
+    returning: Option<Vec<Expression>>,
+    constraints: RowConstraints,
+  },
+  Delete {
+    data_source: String,
+    returning: Option<Vec<Expression>>,
+  },
+}
 
-  Insert { data_source: String, rows: Vec<HashMap<String, catalog::Value>> },
+// INFO: Values of the first index column an index scan reads, the rows it finds are still checked against the whole filter
+#[derive(Debug, Clone)]
+pub enum KeyRange {
+  Between(Value, Value),
+  Prefix(String),
 }
 
 pub(crate) struct Optimizer<'a> {
@@ -40,95 +213,327 @@ impl<'a> Optimizer<'a> {
     Self { plan, catalog }
   }
 
-  // sad predicate pushdown :( doesn work as expected
+  // INFO: Moves a filter that sits directly on a scan into the scan itself and one on an inner join into the join condition
   pub fn predicate_pushdown(&mut self, node: &mut Node) {
     let mut new_node: Option<Box<Node>> = None;
 
     match node {
-      Node::Filter { source, condition } => {
-        if let Node::Scan { filter, .. } = &mut **source {
+      Node::Filter { source, condition } => match &mut **source {
+        Node::Scan { filter, .. } => {
           if filter.is_none() {
             *filter = Some(condition.clone());
             new_node = Some(source.clone());
           }
         }
-      }
+        Node::NestedLoopJoin { condition: on, join_type: JoinType::Inner, .. } => {
+          let conjuncts = split_conjunction(on.clone()).into_iter().chain(split_conjunction(condition.clone()));
+          let conjuncts = conjuncts.filter(|conjunct| !matches!(conjunct, Expression::Constant(Value::Boolean(true))));
+
+          if let Some(conjunction) = join_conjunction(conjuncts.collect()) {
+            *on = conjunction;
+          }
+
+          self.predicate_pushdown(source);
+          new_node = Some(source.clone());
+        }
+        _ => self.predicate_pushdown(source),
+      },
       Node::Limit { source, .. }
       | Node::Offset { source, .. }
       | Node::Projection { source, .. }
       | Node::GroupBy { source, .. }
+      | Node::Window { source, .. }
+      | Node::Distinct { source, .. }
       | Node::Having { source, .. }
-      | Node::NestedLoopJoin { left: source, .. }
-      | Node::HashJoin { left: source, .. }
+      | Node::Insert { source, .. }
+      | Node::Update { source, .. }
+      | Node::Delete { source, .. }
+      | Node::Subquery { source, .. }
       | Node::Sort { source, .. } => {
-        new_node = Some(source.clone());
+        self.predicate_pushdown(source);
+      }
+      Node::NestedLoopJoin { left, right, .. }
+      | Node::HashJoin { left, right, .. }
+      | Node::Recursive { anchor: left, recursive: right, .. }
+      | Node::SetOperation { left, right, .. } => {
+        self.predicate_pushdown(left);
+        self.predicate_pushdown(right);
       }
       _ => {}
     }
 
-    if let Some(mut new_node) = new_node {
-      self.predicate_pushdown(&mut *new_node);
+    if let Some(new_node) = new_node {
       *node = *new_node;
     }
   }
 
-  pub fn optimize(&mut self) -> PhysicalPlan {
+  pub fn optimize(&mut self) -> Result<PhysicalPlan, PlannerError> {
     let mut plan = self.plan.0.clone();
     self.predicate_pushdown(&mut plan);
     self.plan.0 = plan;
     self.create_physical_plan()
   }
 
-  pub fn create_physical_plan(&self) -> PhysicalPlan {
-    match &self.plan.0 {
-      Node::CreateTable { schema } => {
-        PhysicalPlan { node: Op::CreateTable { table: schema.clone() }, cost: None, childern: None }
+  pub fn create_physical_plan(&self) -> Result<PhysicalPlan, PlannerError> {
+    self.create_physical_node(&self.plan.0)
+  }
+
+  fn create_physical_node(&self, node: &Node) -> Result<PhysicalPlan, PlannerError> {
+    let plan = match node {
+      Node::CreateTable { schema } => PhysicalPlan::new(Op::CreateTable { table: schema.clone() }, None),
+      Node::AlterTable { table, schema, action, constraints } => {
+        let (table, schema, action, constraints) = (table.clone(), schema.clone(), action.clone(), constraints.clone());
+        let op = Op::AlterTable { table, schema, action, constraints };
+        PhysicalPlan::new(op, None)
+      }
+      Node::DropTable { table } => PhysicalPlan::new(Op::DropTable { table_name: table.to_string() }, None),
+      Node::Describe { table } => PhysicalPlan::new(Op::Describe { table: table.clone() }, None),
+      Node::Insert { table, columns, source, on_conflict, returning, constraints } => {
+        let op = Op::Insert {
+          data_source: table.to_string(),
+          columns: columns.clone(),
+          on_conflict: on_conflict.clone(),
+          returning: returning.clone(),
+          constraints: constraints.clone(),
+        };
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(source)?]))
       }
-      Node::AlterTable { table, values } => {
-        unimplemented!() // ovo ne radi ni planiranje majstore
+      Node::Update { table, source, values, returning, constraints } => {
+        let values = values
+          .iter()
+          .map(|(column, value)| match column {
+            Expression::Identifier(column) => Ok((column.clone(), value.clone())),
+            _ => Err(PlannerError::Unsupported("Assigning to an expression".to_string())),
+          })
+          .collect::<Result<_, _>>()?;
+
+        let op =
+          Op::Update { data_source: table.to_string(), values, returning: returning.clone(), constraints: constraints.clone() };
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(source)?]))
       }
-      Node::DropTable { table } => {
-        PhysicalPlan { node: Op::DropTable { table_name: table.to_string() }, cost: None, childern: None }
+      Node::Delete { table, source, returning } => {
+        let op = Op::Delete { data_source: table.to_string(), returning: returning.clone() };
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(source)?]))
       }
-      Node::Insert { table, values } => {
-        let data_source = table.to_string();
-        let transformed_values = transform_values(values.clone());
+      Node::Values { rows } => PhysicalPlan::new(Op::Values { rows: rows.clone() }, None),
+      Node::Scan { table, alias, filter } => {
+        let op = match filter.as_ref().and_then(|filter| self.index_range(table, alias.as_deref(), filter)) {
+          Some((index, range)) => {
+            Op::IndexScan { data_source: table.to_string(), alias: alias.clone(), index, range, filter: filter.clone() }
+          }
+          None => Op::TableScan { data_source: table.to_string(), alias: alias.clone(), filter: filter.clone() },
+        };
 
-        PhysicalPlan { node: Op::Insert { data_source, rows: transformed_values }, cost: None, childern: None }
+        PhysicalPlan::new(op, None)
+      }
+      Node::Filter { source, condition } => {
+        PhysicalPlan::new(Op::Filter { condition: condition.clone() }, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Projection { source, columns } => {
+        PhysicalPlan::new(Op::Projection { columns: columns.clone() }, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Sort { source, order } => {
+        PhysicalPlan::new(Op::Sort { order: order.clone() }, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Limit { source, limit } => {
+        PhysicalPlan::new(Op::Limit { limit: limit.clone() }, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Offset { source, offset } => {
+        PhysicalPlan::new(Op::Offset { offset: offset.clone() }, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Subquery { source, alias } => {
+        PhysicalPlan::new(Op::SubqueryScan { alias: alias.clone() }, Some(vec![self.create_physical_node(source)?]))
       }
-      _ => unimplemented!(),
+      Node::NestedLoopJoin { left, right, condition, join_type } | Node::HashJoin { left, right, condition, join_type } => {
+        let childern = Some(vec![self.create_physical_node(left)?, self.create_physical_node(right)?]);
+        let (left_keys, right_keys, rest) = join_keys(left, right, condition);
+
+        // INFO: a join on equal columns is done by hashing the right side, anything else compares every pair of rows
+        let op = if left_keys.is_empty() {
+          Op::NestedLoopJoin { join_type: *join_type, condition: condition.clone() }
+        } else {
+          Op::HashJoin { join_type: *join_type, left_keys, right_keys, condition: rest }
+        };
+
+        PhysicalPlan::new(op, childern)
+      }
+      Node::Recursive { name, anchor, recursive, distinct } => {
+        let op = Op::RecursiveUnion { name: name.clone(), distinct: *distinct };
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(anchor)?, self.create_physical_node(recursive)?]))
+      }
+      Node::WorkingTable { name } => PhysicalPlan::new(Op::WorkingTableScan { name: name.clone() }, None),
+      Node::SetOperation { operator, all, left, right } => {
+        let op = Op::HashSetOperation { operator: *operator, all: *all };
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(left)?, self.create_physical_node(right)?]))
+      }
+      Node::GroupBy { source, values, aggregates } => {
+        let op = Op::HashAggregate { group_by: values.clone(), aggregates: aggregates.clone() };
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Window { source, functions } => {
+        PhysicalPlan::new(Op::Window { functions: functions.clone() }, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Distinct { source, on } => {
+        let op = if sorted_on(source, on.as_deref()) {
+          Op::StreamingDistinct { on: on.clone() }
+        } else {
+          Op::HashDistinct { on: on.clone() }
+        };
+
+        PhysicalPlan::new(op, Some(vec![self.create_physical_node(source)?]))
+      }
+      Node::Having { .. } => return Err(PlannerError::Unsupported("A standalone HAVING node".to_string())),
+      Node::IndexLookup { .. } => return Err(PlannerError::Unsupported("An index lookup node".to_string())),
+    };
+
+    Ok(plan)
+  }
+}
+
+// INFO: Whether the rows come out of a sort whose leading keys are the distinct key, with a projection in between for DISTINCT
+fn sorted_on(source: &Node, on: Option<&[Expression]>) -> bool {
+  let (order, keys) = match (source, on) {
+    (Node::Sort { order, .. }, Some(on)) => (order, on.to_vec()),
+    (Node::Projection { source, columns }, None) => match &**source {
+      Node::Sort { order, .. } => (order, columns.iter().map(unaliased).cloned().collect()),
+      _ => return false,
+    },
+    _ => return false,
+  };
+
+  keys.len() <= order.len()
+    && order[..keys.len()].iter().all(|(expression, _)| keys.contains(expression))
+    && keys.iter().all(|key| order[..keys.len()].iter().any(|(expression, _)| expression == key))
+}
+
+fn unaliased(expression: &Expression) -> &Expression {
+  match expression {
+    Expression::Alias(expression, _) => unaliased(expression),
+    expression => expression,
+  }
+}
+
+impl Optimizer<'_> {
+  // INFO: An index whose first key is compared with `=` or BETWEEN or matched with a LIKE pattern that has a fixed prefix.
+  // The key is a column or, for an index on a JSON path, the same extraction from the column
+  fn index_range(&self, table: &str, alias: Option<&str>, filter: &Expression) -> Option<(String, KeyRange)> {
+    let table = self.catalog.get_table(table)?;
+    let qualifier = alias.unwrap_or(&table.name);
+
+    split_conjunction(filter.clone()).into_iter().find_map(|conjunct| {
+      let (key, range) = match conjunct {
+        Expression::Equal(left, right) => match (*left, *right) {
+          (Expression::Constant(value), key) | (key, Expression::Constant(value)) => {
+            (key, KeyRange::Between(value.clone(), value))
+          }
+          _ => return None,
+        },
+        Expression::Between(key, low, high) => match (*low, *high) {
+          (Expression::Constant(low), Expression::Constant(high)) => (*key, KeyRange::Between(low, high)),
+          _ => return None,
+        },
+        Expression::Like(key, pattern, escape) => match *pattern {
+          Expression::Constant(Value::Text(pattern)) => (*key, KeyRange::Prefix(like_prefix(&pattern, escape)?)),
+          _ => return None,
+        },
+        _ => return None,
+      };
+
+      let (column, path) = match &key {
+        Expression::Identifier(column) => (column.clone(), None),
+        key => json_path(key).map(|(column, path)| (column, Some(path)))?,
+      };
+      let (column_qualifier, column) = column.split_once('.')?;
+      if column_qualifier != qualifier {
+        return None;
+      }
+
+      let index =
+        table.indexes.iter().find(|index| index.path == path && index.columns.first().is_some_and(|first| first == column))?;
+      let key_type = match &path {
+        Some(JsonPath { as_text: true, .. }) => DataType::Text,
+        Some(_) => DataType::Json,
+        None => table.get_column(column)?.data_type.value_type(),
+      };
+
+      // INFO: the bounds have to be of the type of the stored keys to find them
+      let range = match range {
+        KeyRange::Between(low, high) => KeyRange::Between(key_bound(low, &key_type)?, key_bound(high, &key_type)?),
+        KeyRange::Prefix(prefix) if key_type == DataType::Text => KeyRange::Prefix(prefix),
+        KeyRange::Prefix(_) => return None,
+      };
+
+      Some((index.name.clone(), range))
+    })
+  }
+}
+
+// INFO: None for NULL, which matches no key, and for a value that can't be a key of the type
+fn key_bound(value: Value, key_type: &DataType) -> Option<Value> {
+  match catalog::Value::from(value).coerce_to(key_type)? {
+    catalog::Value::Null => None,
+    value => Some(Value::from(value)),
+  }
+}
+
+// INFO: Characters before the first wildcard of the pattern, None when it starts with one
+fn like_prefix(pattern: &str, escape: Option<char>) -> Option<String> {
+  let mut prefix = String::new();
+  let mut chars = pattern.chars();
+
+  while let Some(ch) = chars.next() {
+    match ch {
+      '%' | '_' => break,
+      ch if Some(ch) == escape => prefix.push(chars.next()?),
+      ch => prefix.push(ch),
     }
   }
+
+  (!prefix.is_empty()).then_some(prefix)
 }
 
-fn transform_values(values: Vec<(Expression, Expression)>) -> Vec<HashMap<String, catalog::Value>> {
-  let mut result = vec![];
+// INFO: Splits the join condition into the equalities usable as hash keys and the rest of it
+fn join_keys(left: &Node, right: &Node, condition: &Expression) -> (Vec<Expression>, Vec<Expression>, Option<Expression>) {
+  let (left_tables, right_tables) = (qualifiers(left), qualifiers(right));
+  let (mut left_keys, mut right_keys, mut rest) = (Vec::new(), Vec::new(), Vec::new());
 
-  for (key_expr, value_expr) in values {
-    let key = match key_expr {
-      Expression::Identifier(identifier) => identifier,
-      _ => panic!("Key must be an identifier"),
-    };
+  for conjunct in split_conjunction(condition.clone()) {
+    if let Expression::Equal(a, b) = &conjunct {
+      if uses_only(a, &left_tables) && uses_only(b, &right_tables) {
+        left_keys.push(*a.clone());
+        right_keys.push(*b.clone());
+        continue;
+      }
 
-    let value = match value_expr {
-      Expression::Constant(constant) => convert_to_catalog_value(constant),
-      _ => panic!("Value must be a constant"),
-    };
+      if uses_only(b, &left_tables) && uses_only(a, &right_tables) {
+        left_keys.push(*b.clone());
+        right_keys.push(*a.clone());
+        continue;
+      }
+    }
 
-    let mut map = HashMap::new();
-    map.insert(key, value);
-    result.push(map);
+    rest.push(conjunct);
   }
 
-  result
+  (left_keys, right_keys, join_conjunction(rest))
 }
 
-fn convert_to_catalog_value(value: Value) -> catalog::Value {
-  match value {
-    Value::Int(i) => catalog::Value::Int(i as i32),
-    Value::Float(f) => catalog::Value::Float(f),
-    Value::Text(s) => catalog::Value::Text(s),
-    Value::Boolean(b) => catalog::Value::Boolean(b),
-    _ => unimplemented!("Null values are not supported yet"),
+// INFO: Table names and aliases whose columns come out of the node
+fn qualifiers(node: &Node) -> Vec<String> {
+  match node {
+    Node::Scan { table, alias, .. } => vec![alias.clone().unwrap_or_else(|| table.clone())],
+    Node::Subquery { alias, .. } => vec![alias.clone()],
+    Node::NestedLoopJoin { left, join_type: JoinType::Semi | JoinType::Anti, .. }
+    | Node::HashJoin { left, join_type: JoinType::Semi | JoinType::Anti, .. } => qualifiers(left),
+    node => node.tranverse().into_iter().skip(1).flat_map(qualifiers).collect(),
   }
 }
+
+fn uses_only(expression: &Expression, tables: &[String]) -> bool {
+  let identifiers = expression.identifiers();
+
+  !identifiers.is_empty()
+    && !expression.has_subquery()
+    && !expression.references_outer(1)
+    && identifiers.iter().all(|identifier| identifier.split_once('.').is_some_and(|(table, _)| tables.iter().any(|t| t == table)))
+}
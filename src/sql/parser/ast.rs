@@ -1,6 +1,9 @@
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::fmt;
 
-#[derive(Debug)]
+use crate::sql::catalog::{Decimal, Interval};
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
   // INFO: Transaction control
   Begin,
@@ -9,7 +12,8 @@ pub enum Statement {
 
   // INFO: Query
   Select {
-    from: Table,
+    distinct: Option<Distinct>,
+    from: FromItem,
     select: Vec<Expression>,
     where_clause: Option<Expression>,
     group_by: Option<Vec<Expression>>,
@@ -18,26 +22,46 @@ pub enum Statement {
     limit: Option<Expression>,
     offset: Option<Expression>,
   },
+  SetOperation {
+    operator: SetOperator,
+    all: bool,
+    left: Box<Statement>,
+    right: Box<Statement>,
+    order_by: Option<Vec<(Expression, Order)>>,
+    limit: Option<Expression>,
+    offset: Option<Expression>,
+  },
+  With {
+    recursive: bool,
+    ctes: Vec<CommonTableExpression>,
+    body: Box<Statement>,
+  },
 
   // INFO: DMLs
   Insert {
     table: Table,
-    entries: Vec<(Expression, Expression)>,
+    columns: Option<Vec<Expression>>,
+    source: InsertSource,
+    on_conflict: Option<OnConflict>,
+    returning: Option<Vec<Expression>>,
   },
   Update {
     table: Table,
     entries: Vec<(Expression, Expression)>,
     where_clause: Option<Expression>,
+    returning: Option<Vec<Expression>>,
   },
   Delete {
     table: Table,
     where_clause: Option<Expression>,
+    returning: Option<Vec<Expression>>,
   },
 
   // INFO: DDLs
   CreateTable {
     name: Expression,
     columns: Vec<ColumnDefinition>,
+    constraints: Vec<TableConstraint>,
   },
   DropTable {
     name: Expression,
@@ -46,58 +70,222 @@ pub enum Statement {
     name: Expression,
     operation: AlterTableOperation,
   },
+  CreateIndex {
+    name: Expression,
+    table: Expression,
+    unique: bool,
+    keys: Vec<Expression>, // INFO: columns, or a single value extracted from a JSON column
+  },
+  DropIndex {
+    name: Expression,
+  },
+  Describe {
+    name: Expression,
+  },
+}
+
+// INFO: SELECT DISTINCT compares whole rows, DISTINCT ON only the listed expressions
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distinct {
+  Rows,
+  On(Vec<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SetOperator {
+  Union,
+  Intersect,
+  Except,
+}
+
+// INFO: WITH name (columns) AS (query), in WITH RECURSIVE the query can be a UNION [ALL]
+// whose second query reads the rows of the CTE itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommonTableExpression {
+  pub name: String,
+  pub columns: Option<Vec<String>>,
+  pub query: Box<Statement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InsertSource {
+  Values(Vec<Vec<Expression>>),
+  Select(Box<Statement>),
+}
+
+// INFO: ON CONFLICT (columns) DO ..., without columns it applies to every unique constraint
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnConflict {
+  pub columns: Vec<Expression>,
+  pub action: ConflictAction,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictAction {
+  DoNothing,
+  DoUpdate { entries: Vec<(Expression, Expression)>, where_clause: Option<Expression> },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ColumnDefinition {
   pub name: Expression,
   pub data_type: DataType,
   pub constraints: Vec<ColumnConstraint>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ColumnConstraint {
   PrimaryKey,
   NotNull,
   Unique,
   Default(Expression),
   Check(Expression),
-  ForeignKey { table: Expression, child_column: Expression, parent_column: Expression },
+  ForeignKey { table: Expression, child_column: Expression, parent_column: Expression, actions: ReferentialActions },
+}
+
+// INFO: Constraint written as an element of CREATE TABLE or added by ALTER TABLE, a name is made up when it has none
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableConstraint {
+  pub name: Option<Expression>,
+  pub kind: TableConstraintKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableConstraintKind {
+  PrimaryKey(Vec<Expression>),
+  Unique(Vec<Expression>),
+  Check(Expression),
+  ForeignKey { columns: Vec<Expression>, table: Expression, referenced_columns: Vec<Expression>, actions: ReferentialActions },
+}
+
+// INFO: What happens to the referencing rows when the referenced row is deleted or its key is updated
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReferentialActions {
+  pub on_delete: ReferentialAction,
+  pub on_update: ReferentialAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReferentialAction {
+  #[default]
+  NoAction,
+  Restrict,
+  Cascade,
+  SetNull,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DataType {
+  SmallInt,
   Int,
+  BigInt,
+  Real,
+  Float,
+  Double,
   Text,
+  Varchar(Option<u32>),
+  Char(Option<u32>),
+  Decimal(Option<(u32, u32)>), // INFO: precision and scale, without them the value keeps the scale it has
+  Json,                        // INFO: JSONB is the same type
+  Bytea,                       // INFO: BLOB is the same type
+  Uuid,
   Date,
   Timestamp,
+  TimestampTz, // INFO: TIMESTAMP WITH TIME ZONE is the same type
+  Interval,
   Boolean,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlterTableOperation {
   AddColumn(ColumnDefinition),
   DropColumn(Expression),
   ModifyColumn(ColumnDefinition),
+  RenameTable(Expression),
+  RenameColumn { column: Expression, name: Expression },
+  AlterColumn { column: Expression, action: AlterColumnAction },
+  AddConstraint(TableConstraint),
+  DropConstraint(Expression),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterColumnAction {
+  SetDefault(Expression),
+  DropDefault,
+  SetNotNull,
+  DropNotNull,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Order {
   Asc,
   Desc,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Table {
   pub name: String,
   pub alias: Option<String>,
 }
 
+// INFO: What a SELECT reads from, a subquery in FROM always has an alias and items separated by commas are joined
+#[derive(Debug, Clone, PartialEq)]
+pub enum FromItem {
+  Table(Table),
+  Subquery { query: Box<Statement>, alias: String },
+  Join { left: Box<FromItem>, right: Box<FromItem> },
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
   Literal(Literal),
   Identifier(String),
   BinaryExpression { left: Box<Expression>, operator: Operator, right: Box<Expression> },
+  UnaryExpression { operator: Operator, expression: Box<Expression> },
+  Alias { expression: Box<Expression>, alias: String },
+  Subquery(Box<Statement>),
+  Exists(Box<Statement>),
+  InSubquery { expression: Box<Expression>, subquery: Box<Statement>, negated: bool },
+  InList { expression: Box<Expression>, list: Vec<Expression>, negated: bool },
+  Between { expression: Box<Expression>, low: Box<Expression>, high: Box<Expression>, negated: bool },
+  // INFO: escape is None after `ESCAPE ''`, without the clause it is a backslash
+  Like { expression: Box<Expression>, pattern: Box<Expression>, escape: Option<char>, case_insensitive: bool, negated: bool },
+  Function { name: String, args: Vec<Expression>, distinct: bool }, // INFO: `*` as the only argument is an Identifier("*")
+  IsNull { expression: Box<Expression>, negated: bool },
+  Case { operand: Option<Box<Expression>>, branches: Vec<(Expression, Expression)>, default: Option<Box<Expression>> },
+  Cast { expression: Box<Expression>, data_type: DataType },
+  Coalesce(Vec<Expression>),
+  NullIf { left: Box<Expression>, right: Box<Expression> },
+  Window { function: Box<Expression>, over: Box<Window> }, // INFO: the function is always a Function
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+  pub partition_by: Vec<Expression>,
+  pub order_by: Vec<(Expression, Order)>,
+  pub frame: Option<WindowFrame>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowFrame {
+  pub units: FrameUnits,
+  pub start: FrameBound,
+  pub end: FrameBound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameUnits {
+  Rows,
+  Range, // INFO: the bounds are distances between the values of the ORDER BY key, peers are always in the same frame
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameBound {
+  UnboundedPreceding,
+  Preceding(u64),
+  CurrentRow,
+  Following(u64),
+  UnboundedFollowing,
 }
 
 impl Expression {
@@ -107,17 +295,62 @@ impl Expression {
       _ => panic!("Expression is not an Identifier"),
     }
   }
+
+  // INFO: Renames a column everywhere in the expression, qualified or not. Subqueries are left as they are
+  pub fn rename_column(&mut self, from: &str, to: &str) {
+    if let Expression::Identifier(name) = self {
+      match name.rsplit_once('.') {
+        Some((qualifier, column)) if column == from => *name = format!("{}.{}", qualifier, to),
+        None if name == from => *name = to.to_string(),
+        _ => {}
+      }
+    }
+
+    for child in self.children_mut() {
+      child.rename_column(from, to);
+    }
+  }
+
+  fn children_mut(&mut self) -> Vec<&mut Expression> {
+    match self {
+      Expression::Literal(_) | Expression::Identifier(_) | Expression::Subquery(_) | Expression::Exists(_) => Vec::new(),
+      Expression::BinaryExpression { left, right, .. } | Expression::NullIf { left, right } => vec![left, right],
+      Expression::UnaryExpression { expression, .. }
+      | Expression::Alias { expression, .. }
+      | Expression::InSubquery { expression, .. }
+      | Expression::IsNull { expression, .. }
+      | Expression::Cast { expression, .. } => vec![expression],
+      Expression::InList { expression, list, .. } => std::iter::once(&mut **expression).chain(list).collect(),
+      Expression::Between { expression, low, high, .. } => vec![expression, low, high],
+      Expression::Like { expression, pattern, .. } => vec![expression, pattern],
+      Expression::Function { args, .. } | Expression::Coalesce(args) => args.iter_mut().collect(),
+      Expression::Case { operand, branches, default } => operand
+        .iter_mut()
+        .map(|operand| &mut **operand)
+        .chain(branches.iter_mut().flat_map(|(condition, result)| [condition, result]))
+        .chain(default.iter_mut().map(|default| &mut **default))
+        .collect(),
+      Expression::Window { function, over } => std::iter::once(&mut **function)
+        .chain(&mut over.partition_by)
+        .chain(over.order_by.iter_mut().map(|(expression, _)| expression))
+        .collect(),
+    }
+  }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
   String(String),
+  Integer(i64),     // INFO: a number without a fraction or an exponent that fits in 64 bits
+  Decimal(Decimal), // INFO: any other number with up to 38 digits, kept exactly as it is written
   Number(f64),
   Boolean(bool),
   Null,
   Date(NaiveDate),
   DateTime(NaiveDateTime),
   Timestamp(DateTime<Utc>),
+  Interval(Interval),
+  Bytes(Vec<u8>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -134,5 +367,506 @@ pub enum Operator {
   GreaterThanOrEqual,
   And,
   Or,
+  Not,
   Asterisk,
+  JsonGet,     // INFO: `->`
+  JsonGetText, // INFO: `->>`
+  JsonPath,    // INFO: `#>`
+}
+
+// INFO: Statements and expressions are printed back as SQL that parses to the same tree,
+// it is how CHECK and DEFAULT expressions are kept in the catalog
+impl fmt::Display for Statement {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Statement::Begin => write!(f, "BEGIN"),
+      Statement::Commit => write!(f, "COMMIT"),
+      Statement::Rollback => write!(f, "ROLLBACK"),
+      Statement::Select { distinct, from, select, where_clause, group_by, having, order_by, limit, offset } => {
+        write!(f, "SELECT ")?;
+        match distinct {
+          Some(Distinct::Rows) => write!(f, "DISTINCT ")?,
+          Some(Distinct::On(expressions)) => write!(f, "DISTINCT ON ({}) ", list(expressions))?,
+          None => {}
+        }
+        write!(f, "{} FROM {}", list(select), from)?;
+
+        if let Some(where_clause) = where_clause {
+          write!(f, " WHERE {}", where_clause)?;
+        }
+        if let Some(group_by) = group_by {
+          write!(f, " GROUP BY {}", list(group_by))?;
+        }
+        if let Some(having) = having {
+          write!(f, " HAVING {}", having)?;
+        }
+
+        write_tail(f, order_by, limit, offset)
+      }
+      Statement::SetOperation { operator, all, left, right, order_by, limit, offset } => {
+        write!(f, "{} {}{} {}", left, operator, if *all { " ALL" } else { "" }, right)?;
+        write_tail(f, order_by, limit, offset)
+      }
+      Statement::With { recursive, ctes, body } => {
+        write!(f, "WITH {}{} {}", if *recursive { "RECURSIVE " } else { "" }, list(ctes), body)
+      }
+      Statement::Insert { table, columns, source, on_conflict, returning } => {
+        write!(f, "INSERT INTO {}", table)?;
+        if let Some(columns) = columns {
+          write!(f, " ({})", list(columns))?;
+        }
+        match source {
+          InsertSource::Values(rows) => {
+            let rows = rows.iter().map(|row| format!("({})", list(row))).collect::<Vec<_>>();
+            write!(f, " VALUES {}", rows.join(", "))?;
+          }
+          InsertSource::Select(query) => write!(f, " {}", query)?,
+        }
+
+        if let Some(OnConflict { columns, action }) = on_conflict {
+          write!(f, " ON CONFLICT")?;
+          if !columns.is_empty() {
+            write!(f, " ({})", list(columns))?;
+          }
+          match action {
+            ConflictAction::DoNothing => write!(f, " DO NOTHING")?,
+            ConflictAction::DoUpdate { entries, where_clause } => {
+              write!(f, " DO UPDATE SET {}", assignments(entries))?;
+              if let Some(where_clause) = where_clause {
+                write!(f, " WHERE {}", where_clause)?;
+              }
+            }
+          }
+        }
+
+        write_returning(f, returning)
+      }
+      Statement::Update { table, entries, where_clause, returning } => {
+        write!(f, "UPDATE {} SET {}", table, assignments(entries))?;
+        if let Some(where_clause) = where_clause {
+          write!(f, " WHERE {}", where_clause)?;
+        }
+
+        write_returning(f, returning)
+      }
+      Statement::Delete { table, where_clause, returning } => {
+        write!(f, "DELETE FROM {}", table)?;
+        if let Some(where_clause) = where_clause {
+          write!(f, " WHERE {}", where_clause)?;
+        }
+
+        write_returning(f, returning)
+      }
+      Statement::CreateTable { name, columns, constraints } => match constraints.is_empty() {
+        true => write!(f, "CREATE TABLE {} ({})", name, list(columns)),
+        false => write!(f, "CREATE TABLE {} ({}, {})", name, list(columns), list(constraints)),
+      },
+      Statement::DropTable { name } => write!(f, "DROP TABLE {}", name),
+      Statement::AlterTable { name, operation } => write!(f, "ALTER TABLE {} {}", name, operation),
+      Statement::CreateIndex { name, table, unique, keys } => {
+        write!(f, "CREATE {}INDEX {} ON {} ({})", if *unique { "UNIQUE " } else { "" }, name, table, list(keys))
+      }
+      Statement::DropIndex { name } => write!(f, "DROP INDEX {}", name),
+      Statement::Describe { name } => write!(f, "DESCRIBE {}", name),
+    }
+  }
+}
+
+fn write_tail(
+  f: &mut fmt::Formatter,
+  order_by: &Option<Vec<(Expression, Order)>>,
+  limit: &Option<Expression>,
+  offset: &Option<Expression>,
+) -> fmt::Result {
+  if let Some(order_by) = order_by {
+    write!(f, " ORDER BY {}", ordering(order_by))?;
+  }
+  if let Some(limit) = limit {
+    write!(f, " LIMIT {}", limit)?;
+  }
+  if let Some(offset) = offset {
+    write!(f, " OFFSET {}", offset)?;
+  }
+
+  Ok(())
+}
+
+fn write_returning(f: &mut fmt::Formatter, returning: &Option<Vec<Expression>>) -> fmt::Result {
+  match returning {
+    Some(returning) => write!(f, " RETURNING {}", list(returning)),
+    None => Ok(()),
+  }
+}
+
+fn list(items: &[impl fmt::Display]) -> String {
+  items.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+fn ordering(order_by: &[(Expression, Order)]) -> String {
+  order_by.iter().map(|(expression, order)| format!("{} {}", expression, order)).collect::<Vec<_>>().join(", ")
+}
+
+fn assignments(entries: &[(Expression, Expression)]) -> String {
+  entries.iter().map(|(column, value)| format!("{} = {}", column, value)).collect::<Vec<_>>().join(", ")
+}
+
+impl fmt::Display for SetOperator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SetOperator::Union => write!(f, "UNION"),
+      SetOperator::Intersect => write!(f, "INTERSECT"),
+      SetOperator::Except => write!(f, "EXCEPT"),
+    }
+  }
+}
+
+impl fmt::Display for CommonTableExpression {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name)?;
+    if let Some(columns) = &self.columns {
+      write!(f, " ({})", columns.join(", "))?;
+    }
+
+    write!(f, " AS ({})", self.query)
+  }
+}
+
+impl fmt::Display for ColumnDefinition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{} {}", self.name, self.data_type)?;
+    for constraint in &self.constraints {
+      write!(f, " {}", constraint)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for ColumnConstraint {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ColumnConstraint::PrimaryKey => write!(f, "PRIMARY KEY"),
+      ColumnConstraint::NotNull => write!(f, "NOT NULL"),
+      ColumnConstraint::Unique => write!(f, "UNIQUE"),
+      ColumnConstraint::Default(expression) => write!(f, "DEFAULT {}", operand(expression, ADDITIVE)),
+      ColumnConstraint::Check(expression) => write!(f, "CHECK ({})", expression),
+      ColumnConstraint::ForeignKey { table, child_column, parent_column, actions } => {
+        write!(f, "FOREIGN KEY ({}) REFERENCES {} ({}){}", child_column, table, parent_column, actions)
+      }
+    }
+  }
+}
+
+impl fmt::Display for ReferentialAction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ReferentialAction::NoAction => write!(f, "NO ACTION"),
+      ReferentialAction::Restrict => write!(f, "RESTRICT"),
+      ReferentialAction::Cascade => write!(f, "CASCADE"),
+      ReferentialAction::SetNull => write!(f, "SET NULL"),
+    }
+  }
+}
+
+impl fmt::Display for DataType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      DataType::SmallInt => write!(f, "SMALLINT"),
+      DataType::Int => write!(f, "INT"),
+      DataType::BigInt => write!(f, "BIGINT"),
+      DataType::Real => write!(f, "REAL"),
+      DataType::Float => write!(f, "FLOAT"),
+      DataType::Double => write!(f, "DOUBLE PRECISION"),
+      DataType::Text => write!(f, "TEXT"),
+      DataType::Varchar(Some(length)) => write!(f, "VARCHAR({})", length),
+      DataType::Varchar(None) => write!(f, "VARCHAR"),
+      DataType::Char(Some(length)) => write!(f, "CHAR({})", length),
+      DataType::Char(None) => write!(f, "CHAR"),
+      DataType::Decimal(Some((precision, scale))) => write!(f, "DECIMAL({}, {})", precision, scale),
+      DataType::Decimal(None) => write!(f, "DECIMAL"),
+      DataType::Json => write!(f, "JSON"),
+      DataType::Bytea => write!(f, "BYTEA"),
+      DataType::Uuid => write!(f, "UUID"),
+      DataType::Date => write!(f, "DATE"),
+      DataType::Timestamp => write!(f, "TIMESTAMP"),
+      DataType::TimestampTz => write!(f, "TIMESTAMPTZ"),
+      DataType::Interval => write!(f, "INTERVAL"),
+      DataType::Boolean => write!(f, "BOOLEAN"),
+    }
+  }
+}
+
+impl fmt::Display for AlterTableOperation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      AlterTableOperation::AddColumn(column) => write!(f, "ADD COLUMN {}", column),
+      AlterTableOperation::DropColumn(column) => write!(f, "DROP COLUMN {}", column),
+      AlterTableOperation::ModifyColumn(column) => write!(f, "MODIFY COLUMN {}", column),
+      AlterTableOperation::RenameTable(name) => write!(f, "RENAME TO {}", name),
+      AlterTableOperation::RenameColumn { column, name } => write!(f, "RENAME COLUMN {} TO {}", column, name),
+      AlterTableOperation::AlterColumn { column, action } => match action {
+        AlterColumnAction::SetDefault(value) => write!(f, "ALTER COLUMN {} SET DEFAULT {}", column, value),
+        AlterColumnAction::DropDefault => write!(f, "ALTER COLUMN {} DROP DEFAULT", column),
+        AlterColumnAction::SetNotNull => write!(f, "ALTER COLUMN {} SET NOT NULL", column),
+        AlterColumnAction::DropNotNull => write!(f, "ALTER COLUMN {} DROP NOT NULL", column),
+      },
+      AlterTableOperation::AddConstraint(constraint) => write!(f, "ADD {}", constraint),
+      AlterTableOperation::DropConstraint(name) => write!(f, "DROP CONSTRAINT {}", name),
+    }
+  }
+}
+
+impl fmt::Display for TableConstraint {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if let Some(name) = &self.name {
+      write!(f, "CONSTRAINT {} ", name)?;
+    }
+
+    match &self.kind {
+      TableConstraintKind::PrimaryKey(columns) => write!(f, "PRIMARY KEY ({})", list(columns)),
+      TableConstraintKind::Unique(columns) => write!(f, "UNIQUE ({})", list(columns)),
+      TableConstraintKind::Check(condition) => write!(f, "CHECK ({})", condition),
+      TableConstraintKind::ForeignKey { columns, table, referenced_columns, actions } => {
+        write!(f, "FOREIGN KEY ({}) REFERENCES {} ({}){}", list(columns), table, list(referenced_columns), actions)
+      }
+    }
+  }
+}
+
+// INFO: Only the actions that are not NO ACTION, with a leading space
+impl fmt::Display for ReferentialActions {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    if self.on_delete != ReferentialAction::NoAction {
+      write!(f, " ON DELETE {}", self.on_delete)?;
+    }
+    if self.on_update != ReferentialAction::NoAction {
+      write!(f, " ON UPDATE {}", self.on_update)?;
+    }
+
+    Ok(())
+  }
+}
+
+impl fmt::Display for Order {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Order::Asc => write!(f, "ASC"),
+      Order::Desc => write!(f, "DESC"),
+    }
+  }
+}
+
+impl fmt::Display for Table {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match &self.alias {
+      Some(alias) => write!(f, "{} {}", self.name, alias),
+      None => write!(f, "{}", self.name),
+    }
+  }
+}
+
+impl fmt::Display for FromItem {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      FromItem::Table(table) => write!(f, "{}", table),
+      FromItem::Subquery { query, alias } => write!(f, "({}) {}", query, alias),
+      FromItem::Join { left, right } => write!(f, "{}, {}", left, right),
+    }
+  }
+}
+
+impl fmt::Display for Expression {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let not = |negated: &bool| if *negated { "NOT " } else { "" };
+
+    match self {
+      Expression::Literal(literal) => write!(f, "{}", literal),
+      Expression::Identifier(name) => write!(f, "{}", name),
+      Expression::BinaryExpression { left, operator, right } => {
+        // INFO: the operators are left associative and comparisons can't be chained
+        let precedence = operator_precedence(operator);
+        let left_precedence = if precedence == COMPARISON { precedence + 1 } else { precedence };
+
+        write!(f, "{} {} {}", operand(left, left_precedence), operator, operand(right, precedence + 1))
+      }
+      Expression::UnaryExpression { operator: Operator::Not, expression } => write!(f, "NOT {}", operand(expression, NOT)),
+      Expression::UnaryExpression { operator, expression } => write!(f, "{}{}", operator, operand(expression, PRIMARY)),
+      Expression::Alias { expression, alias } => write!(f, "{} AS {}", expression, alias),
+      Expression::Subquery(query) => write!(f, "({})", query),
+      Expression::Exists(query) => write!(f, "EXISTS ({})", query),
+      Expression::InSubquery { expression, subquery, negated } => {
+        write!(f, "{} {}IN ({})", operand(expression, ADDITIVE), not(negated), subquery)
+      }
+      Expression::InList { expression, list: items, negated } => {
+        write!(f, "{} {}IN ({})", operand(expression, ADDITIVE), not(negated), list(items))
+      }
+      Expression::Between { expression, low, high, negated } => {
+        let (low, high) = (operand(low, ADDITIVE), operand(high, ADDITIVE));
+        write!(f, "{} {}BETWEEN {} AND {}", operand(expression, ADDITIVE), not(negated), low, high)
+      }
+      Expression::Like { expression, pattern, escape, case_insensitive, negated } => {
+        let like = if *case_insensitive { "ILIKE" } else { "LIKE" };
+        write!(f, "{} {}{} {}", operand(expression, ADDITIVE), not(negated), like, operand(pattern, ADDITIVE))?;
+
+        match escape {
+          Some('\\') => Ok(()),
+          Some(escape) => write!(f, " ESCAPE {}", Literal::String(escape.to_string())),
+          None => write!(f, " ESCAPE ''"),
+        }
+      }
+      Expression::Function { name, args, .. } if name.eq_ignore_ascii_case("EXTRACT") && args.len() == 2 => {
+        write!(f, "{}({} FROM {})", name, args[0], args[1])
+      }
+      Expression::Function { name, args, distinct } => {
+        write!(f, "{}({}{})", name, if *distinct { "DISTINCT " } else { "" }, list(args))
+      }
+      Expression::IsNull { expression, negated } => write!(f, "{} IS {}NULL", operand(expression, COMPARISON), not(negated)),
+      Expression::Case { operand: case_operand, branches, default } => {
+        write!(f, "CASE")?;
+        if let Some(case_operand) = case_operand {
+          write!(f, " {}", case_operand)?;
+        }
+        for (condition, result) in branches {
+          write!(f, " WHEN {} THEN {}", condition, result)?;
+        }
+        if let Some(default) = default {
+          write!(f, " ELSE {}", default)?;
+        }
+
+        write!(f, " END")
+      }
+      Expression::Cast { expression, data_type } => write!(f, "CAST({} AS {})", expression, data_type),
+      Expression::Coalesce(args) => write!(f, "COALESCE({})", list(args)),
+      Expression::NullIf { left, right } => write!(f, "NULLIF({}, {})", left, right),
+      Expression::Window { function, over } => write!(f, "{} OVER ({})", function, over),
+    }
+  }
+}
+
+// INFO: Binding strength of the parser levels, an operand that binds looser than its position allows is put in parentheses
+const OR: u8 = 1;
+const AND: u8 = 2;
+const NOT: u8 = 3;
+const IS: u8 = 4;
+const COMPARISON: u8 = 5;
+const ADDITIVE: u8 = 6;
+const MULTIPLICATIVE: u8 = 7;
+const JSON: u8 = 8;
+const UNARY: u8 = 9;
+const PRIMARY: u8 = 10;
+
+fn operator_precedence(operator: &Operator) -> u8 {
+  match operator {
+    Operator::Or => OR,
+    Operator::And => AND,
+    Operator::Not => NOT,
+    Operator::Add | Operator::Subtract => ADDITIVE,
+    Operator::Multiply | Operator::Asterisk | Operator::Divide => MULTIPLICATIVE,
+    Operator::JsonGet | Operator::JsonGetText | Operator::JsonPath => JSON,
+    _ => COMPARISON,
+  }
+}
+
+fn precedence(expression: &Expression) -> u8 {
+  match expression {
+    Expression::BinaryExpression { operator, .. } => operator_precedence(operator),
+    Expression::UnaryExpression { operator: Operator::Not, .. } => NOT,
+    Expression::UnaryExpression { .. } => UNARY,
+    Expression::IsNull { .. } => IS,
+    Expression::InSubquery { .. } | Expression::InList { .. } => COMPARISON,
+    Expression::Between { .. } | Expression::Like { .. } => COMPARISON,
+    Expression::Literal(Literal::Integer(number)) if *number < 0 => UNARY,
+    Expression::Literal(Literal::Decimal(number)) if *number < Decimal::from(0) => UNARY,
+    Expression::Literal(Literal::Number(number)) if *number < 0.0 => UNARY,
+    _ => PRIMARY,
+  }
+}
+
+fn operand(expression: &Expression, minimum: u8) -> String {
+  match precedence(expression) < minimum {
+    true => format!("({})", expression),
+    false => expression.to_string(),
+  }
+}
+
+impl fmt::Display for Window {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut parts = Vec::new();
+
+    if !self.partition_by.is_empty() {
+      parts.push(format!("PARTITION BY {}", list(&self.partition_by)));
+    }
+    if !self.order_by.is_empty() {
+      parts.push(format!("ORDER BY {}", ordering(&self.order_by)));
+    }
+    if let Some(frame) = &self.frame {
+      parts.push(frame.to_string());
+    }
+
+    write!(f, "{}", parts.join(" "))
+  }
+}
+
+impl fmt::Display for WindowFrame {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let units = match self.units {
+      FrameUnits::Rows => "ROWS",
+      FrameUnits::Range => "RANGE",
+    };
+
+    write!(f, "{} BETWEEN {} AND {}", units, self.start, self.end)
+  }
+}
+
+impl fmt::Display for FrameBound {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      FrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+      FrameBound::Preceding(offset) => write!(f, "{} PRECEDING", offset),
+      FrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+      FrameBound::Following(offset) => write!(f, "{} FOLLOWING", offset),
+      FrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+    }
+  }
+}
+
+impl fmt::Display for Literal {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Literal::String(value) => write!(f, "'{}'", value.replace('\'', "''")),
+      Literal::Integer(value) => write!(f, "{}", value),
+      Literal::Decimal(value) => write!(f, "{}", value),
+      Literal::Number(value) => write!(f, "{}", value),
+      Literal::Boolean(value) => write!(f, "{}", if *value { "TRUE" } else { "FALSE" }),
+      Literal::Null => write!(f, "NULL"),
+      Literal::Date(value) => write!(f, "DATE '{}'", value),
+      Literal::DateTime(value) => write!(f, "TIMESTAMP '{}'", value.format("%Y-%m-%d %H:%M:%S%.f")),
+      Literal::Timestamp(value) => write!(f, "TIMESTAMPTZ '{}+00'", value.format("%Y-%m-%d %H:%M:%S%.f")),
+      Literal::Interval(value) => write!(f, "INTERVAL '{}'", value),
+      Literal::Bytes(value) => write!(f, "X'{}'", value.iter().map(|byte| format!("{:02X}", byte)).collect::<String>()),
+    }
+  }
+}
+
+impl fmt::Display for Operator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Operator::Add => write!(f, "+"),
+      Operator::Subtract => write!(f, "-"),
+      Operator::Multiply | Operator::Asterisk => write!(f, "*"),
+      Operator::Divide => write!(f, "/"),
+      Operator::Equal => write!(f, "="),
+      Operator::NotEqual => write!(f, "<>"),
+      Operator::LessThan => write!(f, "<"),
+      Operator::LessThanOrEqual => write!(f, "<="),
+      Operator::GreaterThan => write!(f, ">"),
+      Operator::GreaterThanOrEqual => write!(f, ">="),
+      Operator::And => write!(f, "AND"),
+      Operator::Or => write!(f, "OR"),
+      Operator::Not => write!(f, "NOT"),
+      Operator::JsonGet => write!(f, "->"),
+      Operator::JsonGetText => write!(f, "->>"),
+      Operator::JsonPath => write!(f, "#>"),
+    }
+  }
 }
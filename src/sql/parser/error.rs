@@ -1,5 +1,7 @@
 use std::fmt;
 
+use crate::sql::catalog::DECIMAL_MAX_PRECISION;
+
 #[derive(Debug)]
 pub enum ParserError {
   UnexpectedToken,
@@ -10,7 +12,16 @@ pub enum ParserError {
   NoColumnsSpecified,
   FailedToParseDate,
   FailedToParseTimestamp,
+  FailedToParseInterval,
   UnexpectedSymbol,
+  UnterminatedComment,
+  ExpectedSemicolon,
+  ExpectedConflictTarget,
+  ExpectedAlias,
+  InvalidEscape,
+  InvalidTypeLength,
+  InvalidTypePrecision,
+  InvalidHexString,
 }
 
 impl fmt::Display for ParserError {
@@ -24,7 +35,20 @@ impl fmt::Display for ParserError {
       ParserError::NoColumnsSpecified => write!(f, "Parsing Error: No columns specified."),
       ParserError::FailedToParseDate => write!(f, "Parsing Error: Failed to parse date."),
       ParserError::FailedToParseTimestamp => write!(f, "Parsing Error: Failed to parse timestamp."),
+      ParserError::FailedToParseInterval => write!(f, "Parsing Error: Failed to parse interval."),
       ParserError::UnexpectedSymbol => write!(f, "Parsing Error: Unexpected symbol."),
+      ParserError::UnterminatedComment => write!(f, "Parsing Error: Block comment is not closed with */."),
+      ParserError::ExpectedSemicolon => write!(f, "Parsing Error: Expected semicolon between statements."),
+      ParserError::InvalidEscape => write!(f, "Parsing Error: ESCAPE must be a single character or an empty string."),
+      ParserError::InvalidTypeLength => write!(f, "Parsing Error: Length of a character type has to be a positive integer."),
+      ParserError::InvalidTypePrecision => write!(
+        f,
+        "Parsing Error: Precision of a DECIMAL has to be between 1 and {} and its scale between 0 and the precision.",
+        DECIMAL_MAX_PRECISION
+      ),
+      ParserError::InvalidHexString => write!(f, "Parsing Error: A hex string has to be an even number of hex digits."),
+      ParserError::ExpectedAlias => write!(f, "Parsing Error: Subquery in FROM must have an alias."),
+      ParserError::ExpectedConflictTarget => write!(f, "Parsing Error: ON CONFLICT DO UPDATE requires conflict columns."),
     }
   }
 }
@@ -1,29 +1,83 @@
-use ast::{Expression, Literal, Operator, ColumnDefinition};
-use tokenizer::{Keyword, Token, Tokenizer};
+use ast::{Expression, Literal, Operator};
+use chrono::NaiveDate;
 use error::ParserError;
+use std::iter::Peekable;
+use std::vec::IntoIter;
+use tokenizer::{Keyword, Token, Tokenizer};
+
+use crate::sql::catalog;
 
 pub mod ast;
 mod error;
 mod tokenizer;
 
-pub struct Parser<'a> {
-  tokenizer: std::iter::Peekable<Tokenizer<'a>>,
+pub struct Parser {
+  tokenizer: Peekable<IntoIter<Token>>,
+  error: Option<ParserError>, // INFO: input the tokenizer couldn't read, it is reported before anything is parsed
 }
 
-impl<'a> Parser<'a> {
-  pub fn new(input: &'a str) -> Parser {
-    Parser { tokenizer: Tokenizer::new(input).peekable() }
+impl Parser {
+  // INFO: The whole input is tokenized up front so an unknown symbol or an unterminated string or comment anywhere in
+  // a script fails all of it instead of silently ending it there
+  pub fn new(input: &str) -> Parser {
+    match Tokenizer::new(input).collect::<Result<Vec<_>, _>>() {
+      Ok(tokens) => Parser { tokenizer: tokens.into_iter().peekable(), error: None },
+      Err(error) => Parser { tokenizer: Vec::new().into_iter().peekable(), error: Some(error) },
+    }
   }
 
   pub fn parse(&mut self) -> Result<ast::Statement, Box<dyn std::error::Error>> {
+    self.check_tokens()?;
     let statement = self.parse_statement()?;
     Ok(statement)
   }
 
+  fn check_tokens(&mut self) -> Result<(), ParserError> {
+    match self.error.take() {
+      Some(error) => Err(error),
+      None => Ok(()),
+    }
+  }
+
+  // INFO: A whole input that is one expression, like the DEFAULT and CHECK expressions stored in the catalog
+  pub fn parse_standalone_expression(&mut self) -> Result<Expression, ParserError> {
+    self.check_tokens()?;
+    let expression = self.parse_expression()?;
+
+    match self.tokenizer.peek() {
+      None => Ok(expression),
+      Some(_) => Err(ParserError::UnexpectedToken),
+    }
+  }
+
+  // INFO: Parses every statement of a script, statements are separated by semicolons
+  pub fn parse_script(&mut self) -> Result<Vec<ast::Statement>, Box<dyn std::error::Error>> {
+    self.check_tokens()?;
+    let mut statements = Vec::new();
+
+    loop {
+      while self.peek_check_if_next_token_is(Token::Semicolon) {}
+
+      if self.tokenizer.peek().is_none() {
+        break;
+      }
+
+      statements.push(self.parse_statement()?);
+
+      if !matches!(self.tokenizer.peek(), None | Some(Token::Semicolon)) {
+        return Err(Box::new(ParserError::ExpectedSemicolon));
+      }
+    }
+
+    Ok(statements)
+  }
+
   fn parse_statement(&mut self) -> Result<ast::Statement, ParserError> {
     match self.tokenizer.peek() {
       // Queries
-      Some(Token::Keyword(Keyword::SELECT)) => self.parse_select_statement(),
+      Some(Token::Keyword(Keyword::SELECT)) => self.parse_query(),
+      Some(Token::Keyword(Keyword::WITH)) => self.parse_query(),
+      Some(Token::OpenParen) => self.parse_query(),
 
       // DML
       Some(Token::Keyword(Keyword::INSERT)) => self.parse_dml_statement(),
@@ -34,6 +88,10 @@ impl<'a> Parser<'a> {
       Some(Token::Keyword(Keyword::CREATE)) => self.parse_ddl_statement(),
       Some(Token::Keyword(Keyword::DROP)) => self.parse_ddl_statement(),
       Some(Token::Keyword(Keyword::ALTER)) => self.parse_ddl_statement(),
+      Some(Token::Keyword(Keyword::DESCRIBE)) => {
+        self.tokenizer.next();
+        Ok(ast::Statement::Describe { name: self.parse_identifier_expression()? })
+      }
 
       // Transactions
       Some(Token::Keyword(Keyword::BEGIN)) => self.parse_transaction(),
@@ -44,24 +102,34 @@ impl<'a> Parser<'a> {
   }
 
   fn parse_select_columns(&mut self) -> Result<Vec<Expression>, ParserError> {
-    let mut select: Vec<Expression> = Vec::new();
-
-    while let Some(token) = self.tokenizer.next() {
-      match token {
-        Token::String(name) => select.push(Expression::Identifier(name)),
-        Token::Asterisk => select.push(Expression::Identifier("*".to_string())),
-        Token::Keyword(Keyword::FROM) => {
-          if select.is_empty() {
-            return Err(ParserError::NoColumnsSpecified);
-          }
-          break;
+    let select = self.parse_expression_list()?;
+    self.check_if_next_token_is_keyword(Keyword::FROM)?;
+
+    Ok(select)
+  }
+
+  // INFO: Comma separated expressions where `*` stands for every column, used by SELECT and RETURNING
+  fn parse_expression_list(&mut self) -> Result<Vec<Expression>, ParserError> {
+    let mut expressions = Vec::new();
+
+    loop {
+      if self.peek_check_if_next_token_is(Token::Asterisk) {
+        expressions.push(Expression::Identifier("*".to_string()));
+      } else {
+        let expression = self.parse_expression()?;
+
+        match self.parse_alias()? {
+          Some(alias) => expressions.push(Expression::Alias { expression: Box::new(expression), alias }),
+          None => expressions.push(expression),
         }
-        Token::Comma => {}
-        _ => return Err(ParserError::UnexpectedToken),
+      }
+
+      if !self.peek_check_if_next_token_is(Token::Comma) {
+        break;
       }
     }
 
-    Ok(select)
+    Ok(expressions)
   }
 
   fn parse_table(&mut self) -> Result<ast::Table, ParserError> {
@@ -71,26 +139,165 @@ impl<'a> Parser<'a> {
     };
 
     match token {
-      Token::String(name) => Ok(ast::Table { name, alias: None }),
+      Token::Identifier(name) => Ok(ast::Table { name, alias: self.parse_alias()? }),
       _ => Err(ParserError::ExpectedIdentifier),
     }
     // let token = self.parse_identifier_expression()?;
     // Ok(ast::Table { name: token, alias: None })
   }
 
-  fn parse_where_clause(&mut self) -> Result<Option<Expression>, ParserError> {
+  // INFO: `[AS] alias` after a table or a selected expression
+  fn parse_alias(&mut self) -> Result<Option<String>, ParserError> {
+    if self.peek_check_if_next_token_is(Token::Keyword(Keyword::AS)) {
+      return Ok(Some(self.parse_identifier_expression()?.parse_identifier()));
+    }
+
     match self.tokenizer.peek() {
-      Some(Token::Keyword(Keyword::WHERE)) => {
+      Some(Token::Identifier(_)) => Ok(Some(self.parse_identifier_expression()?.parse_identifier())),
+      _ => Ok(None),
+    }
+  }
+
+  fn parse_from_list(&mut self) -> Result<ast::FromItem, ParserError> {
+    let mut from = self.parse_from_item()?;
+
+    while self.peek_check_if_next_token_is(Token::Comma) {
+      from = ast::FromItem::Join { left: Box::new(from), right: Box::new(self.parse_from_item()?) };
+    }
+
+    Ok(from)
+  }
+
+  fn parse_from_item(&mut self) -> Result<ast::FromItem, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::OpenParen) {
+      return Ok(ast::FromItem::Table(self.parse_table()?));
+    }
+
+    let query = self.parse_subquery()?;
+    let alias = self.parse_alias()?.ok_or(ParserError::ExpectedAlias)?;
+
+    Ok(ast::FromItem::Subquery { query: Box::new(query), alias })
+  }
+
+  // INFO: SELECT inside of parentheses, the opening one is already consumed
+  fn parse_subquery(&mut self) -> Result<ast::Statement, ParserError> {
+    let query = self.parse_query()?;
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok(query)
+  }
+
+  // INFO: SELECTs combined by set operators that may be preceded by a WITH clause,
+  // ORDER BY and LIMIT after the last SELECT apply to the whole result
+  fn parse_query(&mut self) -> Result<ast::Statement, ParserError> {
+    if let Some(Token::Keyword(Keyword::WITH)) = self.tokenizer.peek() {
+      return self.parse_with_statement();
+    }
+
+    let mut query = self.parse_set_expression()?;
+    let order = self.parse_order_by()?;
+    let (limit_value, offset_value) = self.parse_limit_and_offset()?;
+
+    if let ast::Statement::Select { order_by, limit, offset, .. } | ast::Statement::SetOperation { order_by, limit, offset, .. } =
+      &mut query
+    {
+      // INFO: a parenthesized SELECT keeps its own ORDER BY and LIMIT unless they are given again outside
+      if order.is_some() {
+        *order_by = order;
+      }
+      if limit_value.is_some() {
+        *limit = limit_value;
+      }
+      if offset_value.is_some() {
+        *offset = offset_value;
+      }
+    }
+
+    Ok(query)
+  }
+
+  // INFO: UNION and EXCEPT are applied left to right after INTERSECT, which binds tighter
+  fn parse_set_expression(&mut self) -> Result<ast::Statement, ParserError> {
+    let mut query = self.parse_intersect_expression()?;
+
+    loop {
+      let operator = match self.tokenizer.peek() {
+        Some(Token::Keyword(Keyword::UNION)) => ast::SetOperator::Union,
+        Some(Token::Keyword(Keyword::EXCEPT)) => ast::SetOperator::Except,
+        _ => return Ok(query),
+      };
+      self.tokenizer.next();
+
+      let all = self.peek_check_if_next_token_is(Token::Keyword(Keyword::ALL));
+      let right = self.parse_intersect_expression()?;
+      query = set_operation(operator, all, query, right);
+    }
+  }
+
+  fn parse_intersect_expression(&mut self) -> Result<ast::Statement, ParserError> {
+    let mut query = self.parse_query_term()?;
+
+    while self.peek_check_if_next_token_is(Token::Keyword(Keyword::INTERSECT)) {
+      let all = self.peek_check_if_next_token_is(Token::Keyword(Keyword::ALL));
+      let right = self.parse_query_term()?;
+      query = set_operation(ast::SetOperator::Intersect, all, query, right);
+    }
+
+    Ok(query)
+  }
+
+  fn parse_query_term(&mut self) -> Result<ast::Statement, ParserError> {
+    match self.tokenizer.peek() {
+      Some(Token::Keyword(Keyword::SELECT)) => self.parse_select_statement(),
+      Some(Token::OpenParen) => {
         self.tokenizer.next();
+        self.parse_subquery()
+      }
+      _ => Err(ParserError::UnexpectedToken),
+    }
+  }
 
-        let mut condition = self.parse_condition()?;
+  fn parse_with_statement(&mut self) -> Result<ast::Statement, ParserError> {
+    self.check_if_next_token_is_keyword(Keyword::WITH)?;
+    let recursive = self.peek_check_if_next_token_is(Token::Keyword(Keyword::RECURSIVE));
 
-        while let Some(operator) = self.parse_logical_operator() {
-          let right_condition = self.parse_condition()?;
-          condition = Expression::BinaryExpression { left: Box::new(condition), operator, right: Box::new(right_condition) };
-        }
+    let mut ctes = Vec::new();
+    loop {
+      ctes.push(self.parse_common_table_expression()?);
 
-        Ok(Some(condition))
+      if !self.peek_check_if_next_token_is(Token::Comma) {
+        break;
+      }
+    }
+
+    let body = self.parse_query()?;
+
+    Ok(ast::Statement::With { recursive, ctes, body: Box::new(body) })
+  }
+
+  fn parse_common_table_expression(&mut self) -> Result<ast::CommonTableExpression, ParserError> {
+    let name = self.parse_identifier_expression()?.parse_identifier();
+
+    let columns = if self.peek_check_if_next_token_is(Token::OpenParen) {
+      Some(self.parse_intos()?.into_iter().map(Expression::parse_identifier).collect())
+    } else {
+      None
+    };
+
+    self.check_if_next_token_is_keyword(Keyword::AS)?;
+    self.check_if_next_token_is(Token::OpenParen)?;
+
+    let query = self.parse_subquery()?;
+
+    Ok(ast::CommonTableExpression { name, columns, query: Box::new(query) })
+  }
+
+  fn parse_where_clause(&mut self) -> Result<Option<Expression>, ParserError> {
+    match self.tokenizer.peek() {
+      Some(Token::Keyword(Keyword::WHERE)) => {
+        self.tokenizer.next();
+
+        Ok(Some(self.parse_expression()?))
       }
       _ => Ok(None),
     }
@@ -99,29 +306,339 @@ impl<'a> Parser<'a> {
   fn parse_literal(&self, token: Token) -> Result<Literal, ParserError> {
     match token {
       Token::String(val) => Ok(Literal::String(val)),
-      Token::Number(num) => num.parse().map(Literal::Number).map_err(|_| ParserError::FailedToParseNumber),
-      Token::Date(date) => date.parse().map(Literal::Date).map_err(|_| ParserError::FailedToParseDate),
-      Token::Timestamp(date) => date.parse().map(Literal::Timestamp).map_err(|_| ParserError::FailedToParseTimestamp),
+      Token::Number(num) => match (num.parse(), num.parse()) {
+        (Ok(integer), _) => Ok(Literal::Integer(integer)),
+        (_, Ok(decimal)) => Ok(Literal::Decimal(decimal)),
+        _ => num.parse().map(Literal::Number).map_err(|_| ParserError::FailedToParseNumber),
+      },
+      Token::Bytes(digits) => catalog::decode_hex(&digits).map(Literal::Bytes).ok_or(ParserError::InvalidHexString),
       Token::Boolean(val) => Ok(Literal::Boolean(val)),
       Token::Null => Ok(Literal::Null),
       _ => Err(ParserError::ExpectedValue),
     }
   }
 
-  fn parse_condition(&mut self) -> Result<Expression, ParserError> {
-    let left = match self.tokenizer.next() {
-      Some(Token::String(name)) => Box::new(Expression::Identifier(name)),
-      _ => return Err(ParserError::ExpectedIdentifier),
+  // INFO: Precedence from the loosest: OR, AND, NOT, IS, comparison, + -, * /, unary minus, ::
+  fn parse_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_and_expression()?;
+
+    while let Some(operator) = self.match_keyword_to_operator(Keyword::OR, Operator::Or) {
+      let right = self.parse_and_expression()?;
+      expression = Expression::BinaryExpression { left: Box::new(expression), operator, right: Box::new(right) };
+    }
+
+    Ok(expression)
+  }
+
+  fn parse_and_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_not_expression()?;
+
+    while let Some(operator) = self.match_keyword_to_operator(Keyword::AND, Operator::And) {
+      let right = self.parse_not_expression()?;
+      expression = Expression::BinaryExpression { left: Box::new(expression), operator, right: Box::new(right) };
+    }
+
+    Ok(expression)
+  }
+
+  fn parse_not_expression(&mut self) -> Result<Expression, ParserError> {
+    if let Some(operator) = self.match_keyword_to_operator(Keyword::NOT, Operator::Not) {
+      let expression = self.parse_not_expression()?;
+      return Ok(Expression::UnaryExpression { operator, expression: Box::new(expression) });
+    }
+
+    self.parse_is_expression()
+  }
+
+  fn parse_is_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_comparison()?;
+
+    while self.peek_check_if_next_token_is(Token::Keyword(Keyword::IS)) {
+      let negated = self.peek_check_if_next_token_is(Token::Keyword(Keyword::NOT));
+      self.check_if_next_token_is(Token::Null)?;
+
+      expression = Expression::IsNull { expression: Box::new(expression), negated };
+    }
+
+    Ok(expression)
+  }
+
+  fn parse_comparison(&mut self) -> Result<Expression, ParserError> {
+    let left = self.parse_additive_expression()?;
+
+    // INFO: NOT after an operand can only start NOT IN, NOT BETWEEN or NOT [I]LIKE
+    let negated = self.peek_check_if_next_token_is(Token::Keyword(Keyword::NOT));
+    match self.tokenizer.peek() {
+      Some(Token::Keyword(Keyword::IN)) => {
+        self.tokenizer.next();
+        return self.parse_in(left, negated);
+      }
+      Some(Token::Keyword(Keyword::BETWEEN)) => {
+        self.tokenizer.next();
+        let low = self.parse_additive_expression()?;
+        self.check_if_next_token_is_keyword(Keyword::AND)?;
+        let high = self.parse_additive_expression()?;
+
+        return Ok(Expression::Between { expression: Box::new(left), low: Box::new(low), high: Box::new(high), negated });
+      }
+      Some(Token::Keyword(Keyword::LIKE | Keyword::ILIKE)) => {
+        let case_insensitive = self.tokenizer.next() == Some(Token::Keyword(Keyword::ILIKE));
+        let pattern = self.parse_additive_expression()?;
+        let escape = self.parse_escape()?;
+
+        return Ok(Expression::Like {
+          expression: Box::new(left),
+          pattern: Box::new(pattern),
+          escape,
+          case_insensitive,
+          negated,
+        });
+      }
+      _ if negated => return Err(ParserError::UnexpectedToken),
+      _ => {}
+    }
+
+    let operator = match self.tokenizer.peek() {
+      Some(
+        token @ (Token::Equal
+        | Token::NotEqual
+        | Token::LessThan
+        | Token::LessThanOrEqual
+        | Token::GreaterThan
+        | Token::GreaterThanOrEqual),
+      ) => token.to_operator()?,
+      _ => return Ok(left),
+    };
+    self.tokenizer.next();
+
+    let right = self.parse_additive_expression()?;
+
+    Ok(Expression::BinaryExpression { left: Box::new(left), operator, right: Box::new(right) })
+  }
+
+  // INFO: `IN (SELECT ..)` or `IN (value, ..)`, the IN keyword is already consumed
+  fn parse_in(&mut self, expression: Expression, negated: bool) -> Result<Expression, ParserError> {
+    self.check_if_next_token_is(Token::OpenParen)?;
+
+    if let Some(Token::Keyword(Keyword::SELECT) | Token::Keyword(Keyword::WITH)) = self.tokenizer.peek() {
+      let subquery = self.parse_subquery()?;
+      return Ok(Expression::InSubquery { expression: Box::new(expression), subquery: Box::new(subquery), negated });
+    }
+
+    let list = self.parse_arguments()?;
+    if list.is_empty() {
+      return Err(ParserError::ExpectedValue);
+    }
+
+    Ok(Expression::InList { expression: Box::new(expression), list, negated })
+  }
+
+  fn parse_escape(&mut self) -> Result<Option<char>, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::Keyword(Keyword::ESCAPE)) {
+      return Ok(Some('\\'));
+    }
+
+    match self.tokenizer.next() {
+      Some(Token::String(escape)) => {
+        let mut chars = escape.chars();
+        match (chars.next(), chars.next()) {
+          (escape, None) => Ok(escape),
+          _ => Err(ParserError::InvalidEscape),
+        }
+      }
+      _ => Err(ParserError::InvalidEscape),
+    }
+  }
+
+  fn parse_additive_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_multiplicative_expression()?;
+
+    loop {
+      let operator = match self.tokenizer.peek() {
+        Some(Token::Plus) => Operator::Add,
+        Some(Token::Minus) => Operator::Subtract,
+        _ => break,
+      };
+      self.tokenizer.next();
+
+      let right = self.parse_multiplicative_expression()?;
+      expression = Expression::BinaryExpression { left: Box::new(expression), operator, right: Box::new(right) };
+    }
+
+    Ok(expression)
+  }
+
+  fn parse_multiplicative_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_json_expression()?;
+
+    loop {
+      let operator = match self.tokenizer.peek() {
+        Some(Token::Asterisk) => Operator::Multiply,
+        Some(Token::Slash) => Operator::Divide,
+        _ => break,
+      };
+      self.tokenizer.next();
+
+      let right = self.parse_json_expression()?;
+      expression = Expression::BinaryExpression { left: Box::new(expression), operator, right: Box::new(right) };
+    }
+
+    Ok(expression)
+  }
+
+  // INFO: `->`, `->>` and `#>` bind tighter than arithmetic so `payload ->> 'a' = 'b'` needs no parentheses
+  fn parse_json_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_unary_expression()?;
+
+    while let Some(token @ (Token::Arrow | Token::LongArrow | Token::HashArrow)) = self.tokenizer.peek() {
+      let operator = token.to_operator()?;
+      self.tokenizer.next();
+
+      let right = self.parse_unary_expression()?;
+      expression = Expression::BinaryExpression { left: Box::new(expression), operator, right: Box::new(right) };
+    }
+
+    Ok(expression)
+  }
+
+  fn parse_unary_expression(&mut self) -> Result<Expression, ParserError> {
+    if self.peek_check_if_next_token_is(Token::Minus) {
+      return match self.parse_unary_expression()? {
+        Expression::Literal(Literal::Integer(number)) => Ok(Expression::Literal(Literal::Integer(-number))),
+        Expression::Literal(Literal::Decimal(number)) => Ok(Expression::Literal(Literal::Decimal(-number))),
+        Expression::Literal(Literal::Number(number)) => Ok(Expression::Literal(Literal::Number(-number))),
+        expression => Ok(Expression::UnaryExpression { operator: Operator::Subtract, expression: Box::new(expression) }),
+      };
+    }
+
+    self.parse_cast_expression()
+  }
+
+  // INFO: `expression::type`, which binds tighter than any other operator
+  fn parse_cast_expression(&mut self) -> Result<Expression, ParserError> {
+    let mut expression = self.parse_primary_expression()?;
+
+    while self.peek_check_if_next_token_is(Token::DoubleColon) {
+      let data_type = self.parse_data_type()?;
+      expression = Expression::Cast { expression: Box::new(expression), data_type };
+    }
+
+    Ok(expression)
+  }
+
+  fn parse_primary_expression(&mut self) -> Result<Expression, ParserError> {
+    match self.tokenizer.next() {
+      Some(Token::Identifier(name)) => {
+        if self.peek_check_if_next_token_is(Token::OpenParen) {
+          return self.parse_function_call(name);
+        }
+
+        if self.peek_check_if_next_token_is(Token::Period) {
+          let column = self.parse_identifier_expression()?.parse_identifier();
+          return Ok(Expression::Identifier(format!("{}.{}", name, column)));
+        }
+
+        // INFO: CURRENT_DATE is called without parentheses
+        if name.eq_ignore_ascii_case("CURRENT_DATE") {
+          return Ok(Expression::Function { name, args: vec![], distinct: false });
+        }
+
+        Ok(Expression::Identifier(name))
+      }
+      Some(Token::OpenParen) => {
+        if let Some(Token::Keyword(Keyword::SELECT) | Token::Keyword(Keyword::WITH)) = self.tokenizer.peek() {
+          return Ok(Expression::Subquery(Box::new(self.parse_subquery()?)));
+        }
+
+        let expression = self.parse_expression()?;
+        self.check_if_next_token_is(Token::CloseParen)?;
+
+        Ok(expression)
+      }
+      Some(Token::Keyword(Keyword::EXISTS)) => {
+        self.check_if_next_token_is(Token::OpenParen)?;
+        Ok(Expression::Exists(Box::new(self.parse_subquery()?)))
+      }
+      Some(Token::Keyword(Keyword::CASE)) => self.parse_case(),
+      Some(Token::Keyword(keyword @ (Keyword::DATE | Keyword::TIMESTAMP | Keyword::TIMESTAMPTZ | Keyword::INTERVAL))) => {
+        self.parse_typed_literal(keyword).map(Expression::Literal)
+      }
+      Some(Token::Keyword(Keyword::CAST)) => {
+        self.check_if_next_token_is(Token::OpenParen)?;
+        let expression = self.parse_expression()?;
+        self.check_if_next_token_is_keyword(Keyword::AS)?;
+        let data_type = self.parse_data_type()?;
+        self.check_if_next_token_is(Token::CloseParen)?;
+
+        Ok(Expression::Cast { expression: Box::new(expression), data_type })
+      }
+      Some(Token::Keyword(Keyword::COALESCE)) => {
+        self.check_if_next_token_is(Token::OpenParen)?;
+        let arguments = self.parse_arguments()?;
+
+        if arguments.is_empty() {
+          return Err(ParserError::ExpectedValue);
+        }
+
+        Ok(Expression::Coalesce(arguments))
+      }
+      Some(Token::Keyword(Keyword::NULLIF)) => {
+        self.check_if_next_token_is(Token::OpenParen)?;
+        let left = self.parse_expression()?;
+        self.check_if_next_token_is(Token::Comma)?;
+        let right = self.parse_expression()?;
+        self.check_if_next_token_is(Token::CloseParen)?;
+
+        Ok(Expression::NullIf { left: Box::new(left), right: Box::new(right) })
+      }
+      Some(token) => self.parse_literal(token).map(Expression::Literal),
+      None => Err(ParserError::UnexpectedEndOfStream),
+    }
+  }
+
+  // INFO: A string after the name of its type like `DATE '2024-01-31'` or `INTERVAL '3 days'`
+  fn parse_typed_literal(&mut self, keyword: Keyword) -> Result<Literal, ParserError> {
+    let text = match self.tokenizer.next() {
+      Some(Token::String(text)) => text,
+      Some(_) => return Err(ParserError::UnexpectedToken),
+      None => return Err(ParserError::UnexpectedEndOfStream),
     };
+    let text = text.trim();
+
+    match keyword {
+      Keyword::DATE => text.parse().map(Literal::Date).map_err(|_| ParserError::FailedToParseDate),
+      Keyword::TIMESTAMP => catalog::parse_timestamp(text)
+        .or_else(|| text.parse::<NaiveDate>().ok().map(|date| date.and_time(Default::default())))
+        .map(Literal::DateTime)
+        .ok_or(ParserError::FailedToParseTimestamp),
+      Keyword::TIMESTAMPTZ => catalog::parse_timestamptz(text).map(Literal::Timestamp).ok_or(ParserError::FailedToParseTimestamp),
+      _ => text.parse().map(Literal::Interval).map_err(|_| ParserError::FailedToParseInterval),
+    }
+  }
+
+  // INFO: `CASE [operand] WHEN .. THEN .. [ELSE ..] END`, with an operand every WHEN value is compared to it
+  fn parse_case(&mut self) -> Result<Expression, ParserError> {
+    let operand = if self.check_if_next_keyword_is(Keyword::WHEN) { None } else { Some(Box::new(self.parse_expression()?)) };
 
-    let operator = self.tokenizer.next().ok_or(ParserError::UnexpectedEndOfStream)?.to_operator()?;
+    let mut branches = Vec::new();
+    while self.peek_check_if_next_token_is(Token::Keyword(Keyword::WHEN)) {
+      let condition = self.parse_expression()?;
+      self.check_if_next_token_is_keyword(Keyword::THEN)?;
+      branches.push((condition, self.parse_expression()?));
+    }
 
-    let right = match self.tokenizer.next() {
-      Some(token) => self.parse_literal(token).map(|literal| Box::new(Expression::Literal(literal))),
-      _ => return Err(ParserError::ExpectedValue),
-    }?;
+    if branches.is_empty() {
+      return Err(ParserError::UnexpectedToken);
+    }
 
-    Ok(Expression::BinaryExpression { left, operator, right })
+    let default = if self.peek_check_if_next_token_is(Token::Keyword(Keyword::ELSE)) {
+      Some(Box::new(self.parse_expression()?))
+    } else {
+      None
+    };
+    self.check_if_next_token_is_keyword(Keyword::END)?;
+
+    Ok(Expression::Case { operand, branches, default })
   }
 
   fn match_keyword_to_operator(&mut self, keyword: Keyword, operator: Operator) -> Option<Operator> {
@@ -135,12 +652,6 @@ impl<'a> Parser<'a> {
     None
   }
 
-  fn parse_logical_operator(&mut self) -> Option<Operator> {
-    self
-      .match_keyword_to_operator(Keyword::AND, Operator::And)
-      .or_else(|| self.match_keyword_to_operator(Keyword::OR, Operator::Or))
-  }
-
   fn parse_limit_and_offset(&mut self) -> Result<(Option<Expression>, Option<Expression>), ParserError> {
     let mut limit = None;
     let mut offset = None;
@@ -165,10 +676,7 @@ impl<'a> Parser<'a> {
       Some(Token::Keyword(k)) if *k == keyword => {
         self.tokenizer.next();
         match self.tokenizer.next() {
-          Some(Token::Number(num)) => num
-            .parse::<f64>()
-            .map(|number| Some(Expression::Literal(Literal::Number(number))))
-            .map_err(|_| ParserError::FailedToParseNumber),
+          Some(token @ Token::Number(_)) => Ok(Some(Expression::Literal(self.parse_literal(token)?))),
           _ => Err(ParserError::ExpectedValue),
         }
       }
@@ -177,26 +685,164 @@ impl<'a> Parser<'a> {
   }
 
   fn check_if_next_keyword_is(&mut self, keyword: Keyword) -> bool {
-    match self.tokenizer.peek() {
-      Some(Token::Keyword(k)) if *k == keyword => true,
-      _ => false,
+    matches!(self.tokenizer.peek(), Some(Token::Keyword(k)) if *k == keyword)
+  }
+
+  // INFO: `DISTINCT` or `DISTINCT ON (expressions)` right after SELECT
+  fn parse_distinct(&mut self) -> Result<Option<ast::Distinct>, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::Keyword(Keyword::DISTINCT)) {
+      return Ok(None);
+    }
+
+    if !self.peek_check_if_next_token_is(Token::Keyword(Keyword::ON)) {
+      return Ok(Some(ast::Distinct::Rows));
     }
+
+    self.check_if_next_token_is(Token::OpenParen)?;
+    let expressions = self.parse_arguments()?;
+
+    Ok(Some(ast::Distinct::On(expressions)))
   }
 
-  fn parse_group_by(&mut self, select: Vec<Expression>) -> Result<Option<Vec<Expression>>, ParserError> {
-    let mut group_by_exprs = Vec::new();
+  // INFO: Comma separated expressions up to the closing parenthesis, the opening one is already consumed
+  fn parse_arguments(&mut self) -> Result<Vec<Expression>, ParserError> {
+    let mut arguments = Vec::new();
 
-    if self.check_if_next_keyword_is(Keyword::GROUP) {
-      self.tokenizer.next();
+    if self.peek_check_if_next_token_is(Token::CloseParen) {
+      return Ok(arguments);
+    }
+
+    loop {
+      arguments.push(self.parse_expression()?);
+
+      if !self.peek_check_if_next_token_is(Token::Comma) {
+        break;
+      }
+    }
+
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok(arguments)
+  }
+
+  // INFO: name([DISTINCT] arguments) or name(*), the opening parenthesis is already consumed
+  fn parse_function_call(&mut self, name: String) -> Result<Expression, ParserError> {
+    if name.eq_ignore_ascii_case("EXTRACT") {
+      return self.parse_extract(name);
+    }
+
+    let function = if self.peek_check_if_next_token_is(Token::Asterisk) {
+      self.check_if_next_token_is(Token::CloseParen)?;
+      Expression::Function { name, args: vec![Expression::Identifier("*".to_string())], distinct: false }
+    } else {
+      let distinct = self.peek_check_if_next_token_is(Token::Keyword(Keyword::DISTINCT));
+      Expression::Function { name, args: self.parse_arguments()?, distinct }
+    };
+
+    if self.peek_check_if_next_token_is(Token::Keyword(Keyword::OVER)) {
+      return Ok(Expression::Window { function: Box::new(function), over: Box::new(self.parse_window()?) });
+    }
+
+    Ok(function)
+  }
+
+  // INFO: `(PARTITION BY .. ORDER BY .. ROWS|RANGE frame)`, every part is optional
+  fn parse_window(&mut self) -> Result<ast::Window, ParserError> {
+    self.check_if_next_token_is(Token::OpenParen)?;
+
+    let mut partition_by = Vec::new();
+    if self.peek_check_if_next_token_is(Token::Keyword(Keyword::PARTITION)) {
       self.check_if_next_token_is_keyword(Keyword::BY)?;
 
       loop {
-        let expr = self.parse_identifier_expression()?;
+        partition_by.push(self.parse_expression()?);
 
-        if !select.contains(&expr) {
-          return Err(ParserError::UnexpectedToken); // should be in select and if not throw that kind of error
+        if !self.peek_check_if_next_token_is(Token::Comma) {
+          break;
         }
+      }
+    }
+
+    let order_by = self.parse_order_by()?.unwrap_or_default();
+
+    let units = match self.tokenizer.peek() {
+      Some(Token::Keyword(Keyword::ROWS)) => Some(ast::FrameUnits::Rows),
+      Some(Token::Keyword(Keyword::RANGE)) => Some(ast::FrameUnits::Range),
+      _ => None,
+    };
+
+    let frame = match units {
+      Some(units) => {
+        self.tokenizer.next();
+        Some(self.parse_window_frame(units)?)
+      }
+      None => None,
+    };
+
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok(ast::Window { partition_by, order_by, frame })
+  }
+
+  // INFO: A frame with only the start ends at the current row
+  fn parse_window_frame(&mut self, units: ast::FrameUnits) -> Result<ast::WindowFrame, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::Keyword(Keyword::BETWEEN)) {
+      let start = self.parse_frame_bound()?;
+      return Ok(ast::WindowFrame { units, start, end: ast::FrameBound::CurrentRow });
+    }
+
+    let start = self.parse_frame_bound()?;
+    self.check_if_next_token_is_keyword(Keyword::AND)?;
+    let end = self.parse_frame_bound()?;
+
+    Ok(ast::WindowFrame { units, start, end })
+  }
+
+  fn parse_frame_bound(&mut self) -> Result<ast::FrameBound, ParserError> {
+    let offset = match self.tokenizer.next() {
+      Some(Token::Keyword(Keyword::CURRENT)) => {
+        self.check_if_next_token_is_keyword(Keyword::ROW)?;
+        return Ok(ast::FrameBound::CurrentRow);
+      }
+      Some(Token::Keyword(Keyword::UNBOUNDED)) => None,
+      Some(Token::Number(number)) => Some(number.parse().map_err(|_| ParserError::FailedToParseNumber)?),
+      Some(_) => return Err(ParserError::UnexpectedToken),
+      None => return Err(ParserError::UnexpectedEndOfStream),
+    };
+
+    match (self.tokenizer.next(), offset) {
+      (Some(Token::Keyword(Keyword::PRECEDING)), None) => Ok(ast::FrameBound::UnboundedPreceding),
+      (Some(Token::Keyword(Keyword::PRECEDING)), Some(offset)) => Ok(ast::FrameBound::Preceding(offset)),
+      (Some(Token::Keyword(Keyword::FOLLOWING)), None) => Ok(ast::FrameBound::UnboundedFollowing),
+      (Some(Token::Keyword(Keyword::FOLLOWING)), Some(offset)) => Ok(ast::FrameBound::Following(offset)),
+      (Some(_), _) => Err(ParserError::UnexpectedToken),
+      (None, _) => Err(ParserError::UnexpectedEndOfStream),
+    }
+  }
+
+  // INFO: `EXTRACT(field FROM expression)` is a call with the field name as its first argument
+  fn parse_extract(&mut self, name: String) -> Result<Expression, ParserError> {
+    let field = match self.tokenizer.next() {
+      Some(Token::Identifier(field) | Token::String(field)) => field,
+      _ => return Err(ParserError::ExpectedIdentifier),
+    };
+
+    self.check_if_next_token_is_keyword(Keyword::FROM)?;
+    let expression = self.parse_expression()?;
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok(Expression::Function { name, args: vec![Expression::Literal(Literal::String(field)), expression], distinct: false })
+  }
+
+  fn parse_group_by(&mut self) -> Result<Option<Vec<Expression>>, ParserError> {
+    let mut group_by_exprs = Vec::new();
 
+    if self.check_if_next_keyword_is(Keyword::GROUP) {
+      self.tokenizer.next();
+      self.check_if_next_token_is_keyword(Keyword::BY)?;
+
+      loop {
+        let expr = self.parse_expression()?;
         group_by_exprs.push(expr);
 
         if !self.peek_check_if_next_token_is(Token::Comma) {
@@ -210,14 +856,7 @@ impl<'a> Parser<'a> {
 
   fn parse_having(&mut self) -> Result<Option<Expression>, ParserError> {
     if self.peek_check_if_next_token_is(Token::Keyword(Keyword::HAVING)) {
-      let mut condition = self.parse_condition()?;
-
-      while let Some(operator) = self.parse_logical_operator() {
-        let right_condition = self.parse_condition()?;
-        condition = Expression::BinaryExpression { left: Box::new(condition), operator, right: Box::new(right_condition) };
-      }
-
-      Ok(Some(condition))
+      Ok(Some(self.parse_expression()?))
     } else {
       Ok(None)
     }
@@ -233,10 +872,7 @@ impl<'a> Parser<'a> {
         self.tokenizer.next();
         Ok(ast::Order::Desc)
       }
-      _ => {
-        self.tokenizer.next();
-        Ok(ast::Order::Asc)
-      }
+      _ => Ok(ast::Order::Asc),
     }
   }
 
@@ -259,7 +895,7 @@ impl<'a> Parser<'a> {
 
   fn parse_identifier_expression(&mut self) -> Result<Expression, ParserError> {
     match self.tokenizer.next() {
-      Some(Token::String(name)) => Ok(Expression::Identifier(name)),
+      Some(Token::Identifier(name)) => Ok(Expression::Identifier(name)),
       _ => Err(ParserError::ExpectedIdentifier),
     }
   }
@@ -272,7 +908,7 @@ impl<'a> Parser<'a> {
 
       self.check_if_next_token_is_keyword(Keyword::BY)?;
       loop {
-        let expr = self.parse_identifier_expression()?;
+        let expr = self.parse_expression()?;
         let order = self.parse_order_direction()?;
 
         order_by_exprs.push((expr, order));
@@ -291,23 +927,33 @@ impl<'a> Parser<'a> {
 
     match keyword {
       Some(Token::Keyword(Keyword::SELECT)) => {
+        let distinct = self.parse_distinct()?;
         let select = self.parse_select_columns()?;
-        let from = self.parse_table()?;
+        let from = self.parse_from_list()?;
         let where_clause = self.parse_where_clause()?;
-        let group_by = self.parse_group_by(select.clone())?; // clone to check if group by is in select, maybe there is a better way to do this
+        let group_by = self.parse_group_by()?;
         let having = self.parse_having()?;
-        let order_by = self.parse_order_by()?;
-        let (limit, offset) = self.parse_limit_and_offset()?;
 
-        Ok(ast::Statement::Select { select, from, where_clause, group_by, having, order_by, limit, offset })
+        Ok(ast::Statement::Select {
+          distinct,
+          select,
+          from,
+          where_clause,
+          group_by,
+          having,
+          order_by: None,
+          limit: None,
+          offset: None,
+        })
       }
       Some(Token::Keyword(Keyword::DELETE)) => {
         self.check_if_next_token_is_keyword(Keyword::FROM)?;
 
         let from = self.parse_table()?;
         let where_clause = self.parse_where_clause()?;
+        let returning = self.parse_returning()?;
 
-        Ok(ast::Statement::Delete { table: from, where_clause })
+        Ok(ast::Statement::Delete { table: from, where_clause, returning })
       }
       _ => Err(ParserError::UnexpectedToken),
     }
@@ -320,30 +966,39 @@ impl<'a> Parser<'a> {
     }
   }
 
-  fn parse_entries(&mut self) -> Result<Vec<(Expression, Expression)>, ParserError> {
-    let mut entries = Vec::new();
-    self.peek_check_if_next_token_is(Token::OpenParen);
+  fn parse_insert_source(&mut self) -> Result<ast::InsertSource, ParserError> {
+    match self.tokenizer.peek() {
+      Some(Token::Keyword(Keyword::VAULES)) => Ok(ast::InsertSource::Values(self.parse_values()?)),
+      Some(Token::Keyword(Keyword::SELECT) | Token::Keyword(Keyword::WITH)) => {
+        Ok(ast::InsertSource::Select(Box::new(self.parse_query()?)))
+      }
+      _ => Err(ParserError::UnexpectedToken),
+    }
+  }
 
-    let columns = self.parse_intos()?;
-    let values = self.parse_values()?;
+  fn parse_values(&mut self) -> Result<Vec<Vec<Expression>>, ParserError> {
+    let mut rows = Vec::new();
+
+    self.check_if_next_token_is_keyword(Keyword::VAULES)?;
 
-    for i in 0..columns.len() {
-      entries.push((columns[i].clone(), values[i].clone()));
+    loop {
+      rows.push(self.parse_value_list()?);
+
+      if !self.peek_check_if_next_token_is(Token::Comma) {
+        break;
+      }
     }
 
-    Ok(entries)
+    Ok(rows)
   }
 
-  fn parse_values(&mut self) -> Result<Vec<Expression>, ParserError> {
+  fn parse_value_list(&mut self) -> Result<Vec<Expression>, ParserError> {
     let mut values = Vec::new();
 
-    self.check_if_next_token_is_keyword(Keyword::VAULES)?;
     self.check_if_next_token_is(Token::OpenParen)?;
 
     loop {
-      let token = self.tokenizer.next().ok_or(ParserError::UnexpectedEndOfStream)?;
-      let literal = self.parse_literal(token)?;
-      values.push(Expression::Literal(literal));
+      values.push(self.parse_expression()?);
 
       if !self.peek_check_if_next_token_is(Token::Comma) {
         break;
@@ -381,10 +1036,7 @@ impl<'a> Parser<'a> {
       let column = self.parse_identifier_expression()?;
       self.check_if_next_token_is(Token::Equal)?;
 
-      let token = self.tokenizer.next().ok_or(ParserError::UnexpectedEndOfStream)?;
-      let literal = self.parse_literal(token)?;
-
-      entries.push((column, Expression::Literal(literal)));
+      entries.push((column, self.parse_expression()?));
 
       if !self.peek_check_if_next_token_is(Token::Comma) {
         break;
@@ -394,6 +1046,43 @@ impl<'a> Parser<'a> {
     Ok(entries)
   }
 
+  fn parse_on_conflict(&mut self) -> Result<Option<ast::OnConflict>, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::Keyword(Keyword::ON)) {
+      return Ok(None);
+    }
+
+    self.check_if_next_token_is_keyword(Keyword::CONFLICT)?;
+
+    let columns = if self.peek_check_if_next_token_is(Token::OpenParen) { self.parse_intos()? } else { Vec::new() };
+
+    self.check_if_next_token_is_keyword(Keyword::DO)?;
+
+    let action = match self.tokenizer.next() {
+      Some(Token::Keyword(Keyword::NOTHING)) => ast::ConflictAction::DoNothing,
+      Some(Token::Keyword(Keyword::UPDATE)) => {
+        if columns.is_empty() {
+          return Err(ParserError::ExpectedConflictTarget);
+        }
+
+        let entries = self.parse_set()?;
+        let where_clause = self.parse_where_clause()?;
+
+        ast::ConflictAction::DoUpdate { entries, where_clause }
+      }
+      _ => return Err(ParserError::UnexpectedToken),
+    };
+
+    Ok(Some(ast::OnConflict { columns, action }))
+  }
+
+  fn parse_returning(&mut self) -> Result<Option<Vec<Expression>>, ParserError> {
+    if self.peek_check_if_next_token_is(Token::Keyword(Keyword::RETURNING)) {
+      Ok(Some(self.parse_expression_list()?))
+    } else {
+      Ok(None)
+    }
+  }
+
   fn parse_dml_statement(&mut self) -> Result<ast::Statement, ParserError> {
     let keyword = self.tokenizer.next();
 
@@ -402,29 +1091,54 @@ impl<'a> Parser<'a> {
         self.check_if_next_token_is_keyword(Keyword::INTO)?;
 
         let table = self.parse_table()?;
-        let entries = self.parse_entries()?;
+        let columns = if self.peek_check_if_next_token_is(Token::OpenParen) { Some(self.parse_intos()?) } else { None };
+        let source = self.parse_insert_source()?;
+        let on_conflict = self.parse_on_conflict()?;
+        let returning = self.parse_returning()?;
 
-        Ok(ast::Statement::Insert { table, entries })
+        Ok(ast::Statement::Insert { table, columns, source, on_conflict, returning })
       }
       Some(Token::Keyword(Keyword::UPDATE)) => {
         let table = self.parse_table()?;
         let entries = self.parse_set()?;
         let where_clause = self.parse_where_clause()?;
+        let returning = self.parse_returning()?;
 
-        Ok(ast::Statement::Update { table, entries, where_clause })
+        Ok(ast::Statement::Update { table, entries, where_clause, returning })
       }
       _ => Err(ParserError::UnexpectedToken),
     }
   }
 
-  fn parse_create_table_statement(&mut self) -> Result<(Expression, Vec<ColumnDefinition>), ParserError> {
+  fn parse_create_index_statement(&mut self, unique: bool) -> Result<ast::Statement, ParserError> {
+    let name = self.parse_identifier_expression()?;
+    self.check_if_next_token_is_keyword(Keyword::ON)?;
+    let table = self.parse_identifier_expression()?;
+
+    self.check_if_next_token_is(Token::OpenParen)?;
+    let mut keys = vec![self.parse_expression()?];
+    while self.peek_check_if_next_token_is(Token::Comma) {
+      keys.push(self.parse_expression()?);
+    }
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok(ast::Statement::CreateIndex { name, table, unique, keys })
+  }
+
+  // INFO: Columns and table constraints can be written in any order
+  fn parse_create_table_statement(&mut self) -> Result<ast::Statement, ParserError> {
     let name = self.parse_identifier_expression()?;
     self.check_if_next_token_is(Token::OpenParen)?;
 
     let mut columns = Vec::new();
+    let mut constraints = Vec::new();
     loop {
-      let column = self.parse_column_definition()?;
-      columns.push(column);
+      match self.tokenizer.peek() {
+        Some(Token::Keyword(Keyword::CONSTRAINT | Keyword::PRIMARY | Keyword::UNIQUE | Keyword::CHECK | Keyword::FOREIGN)) => {
+          constraints.push(self.parse_table_constraint()?)
+        }
+        _ => columns.push(self.parse_column_definition()?),
+      }
 
       if !self.peek_check_if_next_token_is(Token::Comma) {
         break;
@@ -433,7 +1147,46 @@ impl<'a> Parser<'a> {
 
     self.check_if_next_token_is(Token::CloseParen)?;
 
-    Ok((name, columns))
+    Ok(ast::Statement::CreateTable { name, columns, constraints })
+  }
+
+  fn parse_table_constraint(&mut self) -> Result<ast::TableConstraint, ParserError> {
+    let name = match self.peek_check_if_next_token_is(Token::Keyword(Keyword::CONSTRAINT)) {
+      true => Some(self.parse_identifier_expression()?),
+      false => None,
+    };
+
+    let kind = match self.tokenizer.next() {
+      Some(Token::Keyword(Keyword::PRIMARY)) => {
+        self.check_if_next_token_is_keyword(Keyword::KEY)?;
+        self.check_if_next_token_is(Token::OpenParen)?;
+
+        ast::TableConstraintKind::PrimaryKey(self.parse_intos()?)
+      }
+      Some(Token::Keyword(Keyword::UNIQUE)) => {
+        self.check_if_next_token_is(Token::OpenParen)?;
+
+        ast::TableConstraintKind::Unique(self.parse_intos()?)
+      }
+      Some(Token::Keyword(Keyword::CHECK)) => ast::TableConstraintKind::Check(self.parse_expression()?),
+      Some(Token::Keyword(Keyword::FOREIGN)) => {
+        self.check_if_next_token_is_keyword(Keyword::KEY)?;
+        self.check_if_next_token_is(Token::OpenParen)?;
+        let columns = self.parse_intos()?;
+
+        self.check_if_next_token_is_keyword(Keyword::REFERENCES)?;
+        let table = self.parse_identifier_expression()?;
+        self.check_if_next_token_is(Token::OpenParen)?;
+        let referenced_columns = self.parse_intos()?;
+        let actions = self.parse_referential_actions()?;
+
+        ast::TableConstraintKind::ForeignKey { columns, table, referenced_columns, actions }
+      }
+      Some(_) => return Err(ParserError::UnexpectedToken),
+      None => return Err(ParserError::UnexpectedEndOfStream),
+    };
+
+    Ok(ast::TableConstraint { name, kind })
   }
 
   fn parse_column_definition(&mut self) -> Result<ast::ColumnDefinition, ParserError> {
@@ -441,18 +1194,15 @@ impl<'a> Parser<'a> {
     let data_type = self.parse_data_type()?;
 
     let mut constraints = Vec::new();
-    loop {
-      if let Some(constraint) = self.parse_column_constraint()? {
-        constraints.push(constraint);
-      } else {
-        break;
-      }
+    while let Some(constraint) = self.parse_column_constraint(&name)? {
+      constraints.push(constraint);
     }
 
     Ok(ast::ColumnDefinition { name, data_type, constraints })
   }
 
-  fn parse_column_constraint(&mut self) -> Result<Option<ast::ColumnConstraint>, ParserError> {
+  // INFO: `REFERENCES table (column)` is the short form of a FOREIGN KEY on the column itself
+  fn parse_column_constraint(&mut self, column: &Expression) -> Result<Option<ast::ColumnConstraint>, ParserError> {
     match self.tokenizer.peek() {
       Some(Token::Keyword(Keyword::PRIMARY)) => {
         self.tokenizer.next();
@@ -473,10 +1223,23 @@ impl<'a> Parser<'a> {
       }
       Some(Token::Keyword(Keyword::CHECK)) => {
         self.tokenizer.next();
-        let condition = self.parse_condition()?;
+        let condition = self.parse_expression()?;
 
         Ok(Some(ast::ColumnConstraint::Check(condition)))
       }
+      Some(Token::Keyword(Keyword::DEFAULT)) => {
+        self.tokenizer.next();
+        let value = self.parse_additive_expression()?;
+
+        Ok(Some(ast::ColumnConstraint::Default(value)))
+      }
+      Some(Token::Keyword(Keyword::REFERENCES)) => {
+        self.tokenizer.next();
+        let (table, parent_column) = self.parse_reference()?;
+        let actions = self.parse_referential_actions()?;
+
+        Ok(Some(ast::ColumnConstraint::ForeignKey { table, child_column: column.clone(), parent_column, actions }))
+      }
       Some(Token::Keyword(Keyword::FOREIGN)) => {
         self.tokenizer.next();
         self.check_if_next_token_is_keyword(Keyword::KEY)?;
@@ -487,39 +1250,169 @@ impl<'a> Parser<'a> {
         self.check_if_next_token_is(Token::CloseParen)?;
         self.check_if_next_token_is_keyword(Keyword::REFERENCES)?;
 
-        let parent_table = self.parse_identifier_expression()?;
+        let (parent_table, parent_column) = self.parse_reference()?;
+        let actions = self.parse_referential_actions()?;
 
-        self.check_if_next_token_is(Token::OpenParen)?;
+        Ok(Some(ast::ColumnConstraint::ForeignKey { table: parent_table, child_column, parent_column, actions }))
+      }
+      _ => Ok(None),
+    }
+  }
 
-        let parent_column = self.parse_identifier_expression()?;
-        self.check_if_next_token_is(Token::CloseParen)?;
+  // INFO: `table (column)` after REFERENCES
+  fn parse_reference(&mut self) -> Result<(Expression, Expression), ParserError> {
+    let table = self.parse_identifier_expression()?;
 
-        Ok(Some(ast::ColumnConstraint::ForeignKey { table: parent_table, child_column, parent_column }))
+    self.check_if_next_token_is(Token::OpenParen)?;
+    let column = self.parse_identifier_expression()?;
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok((table, column))
+  }
+
+  // INFO: `ON DELETE action` and `ON UPDATE action` in any order, both are NO ACTION when left out
+  fn parse_referential_actions(&mut self) -> Result<ast::ReferentialActions, ParserError> {
+    let mut actions = ast::ReferentialActions::default();
+
+    while self.peek_check_if_next_token_is(Token::Keyword(Keyword::ON)) {
+      match self.tokenizer.next() {
+        Some(Token::Keyword(Keyword::DELETE)) => actions.on_delete = self.parse_referential_action()?,
+        Some(Token::Keyword(Keyword::UPDATE)) => actions.on_update = self.parse_referential_action()?,
+        Some(_) => return Err(ParserError::UnexpectedToken),
+        None => return Err(ParserError::UnexpectedEndOfStream),
       }
-      _ => Ok(None),
+    }
+
+    Ok(actions)
+  }
+
+  fn parse_referential_action(&mut self) -> Result<ast::ReferentialAction, ParserError> {
+    match self.tokenizer.next() {
+      Some(Token::Keyword(Keyword::CASCADE)) => Ok(ast::ReferentialAction::Cascade),
+      Some(Token::Keyword(Keyword::RESTRICT)) => Ok(ast::ReferentialAction::Restrict),
+      Some(Token::Keyword(Keyword::SET)) => {
+        self.check_if_next_token_is(Token::Null)?;
+        Ok(ast::ReferentialAction::SetNull)
+      }
+      Some(Token::Keyword(Keyword::NO)) => {
+        self.check_if_next_token_is_keyword(Keyword::ACTION)?;
+        Ok(ast::ReferentialAction::NoAction)
+      }
+      Some(_) => Err(ParserError::UnexpectedToken),
+      None => Err(ParserError::UnexpectedEndOfStream),
     }
   }
 
   fn parse_data_type(&mut self) -> Result<ast::DataType, ParserError> {
     match self.tokenizer.next() {
-      Some(Token::Keyword(Keyword::INT)) => Ok(ast::DataType::Int),
+      Some(Token::Keyword(Keyword::SMALLINT)) => Ok(ast::DataType::SmallInt),
+      Some(Token::Keyword(Keyword::INT | Keyword::INTEGER)) => Ok(ast::DataType::Int),
+      Some(Token::Keyword(Keyword::BIGINT)) => Ok(ast::DataType::BigInt),
+      Some(Token::Keyword(Keyword::REAL)) => Ok(ast::DataType::Real),
+      Some(Token::Keyword(Keyword::FLOAT)) => Ok(ast::DataType::Float),
+      Some(Token::Keyword(Keyword::DOUBLE)) => {
+        self.peek_check_if_next_token_is(Token::Keyword(Keyword::PRECISION));
+        Ok(ast::DataType::Double)
+      }
       Some(Token::Keyword(Keyword::TEXT)) => Ok(ast::DataType::Text),
+      Some(Token::Keyword(Keyword::VARCHAR)) => Ok(ast::DataType::Varchar(self.parse_type_length()?)),
+      Some(Token::Keyword(Keyword::CHAR)) => Ok(ast::DataType::Char(self.parse_type_length()?)),
+      Some(Token::Keyword(Keyword::JSON | Keyword::JSONB)) => Ok(ast::DataType::Json),
+      Some(Token::Keyword(Keyword::BYTEA | Keyword::BLOB)) => Ok(ast::DataType::Bytea),
+      Some(Token::Keyword(Keyword::DECIMAL | Keyword::NUMERIC)) => Ok(ast::DataType::Decimal(self.parse_type_precision()?)),
       Some(Token::Keyword(Keyword::DATE)) => Ok(ast::DataType::Date),
-      Some(Token::Keyword(Keyword::TIMESTAMP)) => Ok(ast::DataType::Timestamp),
+      Some(Token::Keyword(Keyword::TIMESTAMP)) => match self.parse_time_zone()? {
+        true => Ok(ast::DataType::TimestampTz),
+        false => Ok(ast::DataType::Timestamp),
+      },
+      Some(Token::Keyword(Keyword::TIMESTAMPTZ)) => Ok(ast::DataType::TimestampTz),
+      Some(Token::Keyword(Keyword::INTERVAL)) => Ok(ast::DataType::Interval),
       Some(Token::Keyword(Keyword::BOOLEAN)) => Ok(ast::DataType::Boolean),
+      // INFO: not a keyword so columns can still be named `uuid`
+      Some(Token::Identifier(name)) if name.eq_ignore_ascii_case("UUID") => Ok(ast::DataType::Uuid),
       _ => Err(ParserError::UnexpectedToken),
     }
   }
 
+  // INFO: The optional `WITH TIME ZONE` or `WITHOUT TIME ZONE` after TIMESTAMP, true for a timestamp with a time zone
+  fn parse_time_zone(&mut self) -> Result<bool, ParserError> {
+    let with_time_zone = match self.tokenizer.peek() {
+      Some(Token::Keyword(Keyword::WITH)) => true,
+      Some(Token::Identifier(name)) if name.eq_ignore_ascii_case("WITHOUT") => false,
+      _ => return Ok(false),
+    };
+    self.tokenizer.next();
+
+    for word in ["TIME", "ZONE"] {
+      match self.tokenizer.next() {
+        Some(Token::Identifier(name)) if name.eq_ignore_ascii_case(word) => {}
+        Some(_) => return Err(ParserError::UnexpectedToken),
+        None => return Err(ParserError::UnexpectedEndOfStream),
+      }
+    }
+
+    Ok(with_time_zone)
+  }
+
+  // INFO: The optional `(n)` after VARCHAR and CHAR
+  fn parse_type_length(&mut self) -> Result<Option<u32>, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::OpenParen) {
+      return Ok(None);
+    }
+
+    let length = match self.tokenizer.next() {
+      Some(Token::Number(number)) => {
+        number.parse::<u32>().ok().filter(|length| *length > 0).ok_or(ParserError::InvalidTypeLength)?
+      }
+      Some(_) => return Err(ParserError::InvalidTypeLength),
+      None => return Err(ParserError::UnexpectedEndOfStream),
+    };
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    Ok(Some(length))
+  }
+
+  // INFO: The optional `(precision [, scale])` after DECIMAL and NUMERIC, the scale is 0 when only the precision is given
+  fn parse_type_precision(&mut self) -> Result<Option<(u32, u32)>, ParserError> {
+    if !self.peek_check_if_next_token_is(Token::OpenParen) {
+      return Ok(None);
+    }
+
+    let precision = self.parse_type_number()?;
+    let scale = if self.peek_check_if_next_token_is(Token::Comma) { self.parse_type_number()? } else { 0 };
+    self.check_if_next_token_is(Token::CloseParen)?;
+
+    if precision == 0 || precision > catalog::DECIMAL_MAX_PRECISION || scale > precision {
+      return Err(ParserError::InvalidTypePrecision);
+    }
+
+    Ok(Some((precision, scale)))
+  }
+
+  fn parse_type_number(&mut self) -> Result<u32, ParserError> {
+    match self.tokenizer.next() {
+      Some(Token::Number(number)) => number.parse::<u32>().map_err(|_| ParserError::InvalidTypePrecision),
+      Some(_) => Err(ParserError::InvalidTypePrecision),
+      None => Err(ParserError::UnexpectedEndOfStream),
+    }
+  }
+
   fn parse_alter_table_operation(&mut self) -> Result<ast::AlterTableOperation, ParserError> {
     match self.tokenizer.next() {
       Some(Token::Keyword(Keyword::ADD)) => {
-        self.check_if_next_token_is_keyword(Keyword::COLUMN)?;
+        if !self.peek_check_if_next_token_is(Token::Keyword(Keyword::COLUMN)) {
+          return Ok(ast::AlterTableOperation::AddConstraint(self.parse_table_constraint()?));
+        }
+
         let column = self.parse_column_definition()?;
 
         Ok(ast::AlterTableOperation::AddColumn(column))
       }
       Some(Token::Keyword(Keyword::DROP)) => {
+        if self.peek_check_if_next_token_is(Token::Keyword(Keyword::CONSTRAINT)) {
+          return Ok(ast::AlterTableOperation::DropConstraint(self.parse_identifier_expression()?));
+        }
+
         self.check_if_next_token_is_keyword(Keyword::COLUMN)?;
         let column = self.parse_identifier_expression()?;
 
@@ -531,19 +1424,71 @@ impl<'a> Parser<'a> {
 
         Ok(ast::AlterTableOperation::ModifyColumn(column))
       }
+      Some(Token::Keyword(Keyword::RENAME)) => {
+        if self.peek_check_if_next_token_is(Token::Keyword(Keyword::TO)) {
+          return Ok(ast::AlterTableOperation::RenameTable(self.parse_identifier_expression()?));
+        }
+
+        self.check_if_next_token_is_keyword(Keyword::COLUMN)?;
+        let column = self.parse_identifier_expression()?;
+        self.check_if_next_token_is_keyword(Keyword::TO)?;
+        let name = self.parse_identifier_expression()?;
+
+        Ok(ast::AlterTableOperation::RenameColumn { column, name })
+      }
+      Some(Token::Keyword(Keyword::ALTER)) => {
+        self.check_if_next_token_is_keyword(Keyword::COLUMN)?;
+        let column = self.parse_identifier_expression()?;
+        let action = self.parse_alter_column_action()?;
+
+        Ok(ast::AlterTableOperation::AlterColumn { column, action })
+      }
       _ => Err(ParserError::UnexpectedToken),
     }
   }
 
+  // INFO: `SET DEFAULT value`, `DROP DEFAULT`, `SET NOT NULL` or `DROP NOT NULL`
+  fn parse_alter_column_action(&mut self) -> Result<ast::AlterColumnAction, ParserError> {
+    let set = match self.tokenizer.next() {
+      Some(Token::Keyword(Keyword::SET)) => true,
+      Some(Token::Keyword(Keyword::DROP)) => false,
+      Some(_) => return Err(ParserError::UnexpectedToken),
+      None => return Err(ParserError::UnexpectedEndOfStream),
+    };
+
+    match (self.tokenizer.next(), set) {
+      (Some(Token::Keyword(Keyword::DEFAULT)), true) => Ok(ast::AlterColumnAction::SetDefault(self.parse_additive_expression()?)),
+      (Some(Token::Keyword(Keyword::DEFAULT)), false) => Ok(ast::AlterColumnAction::DropDefault),
+      (Some(Token::Keyword(Keyword::NOT)), set) => {
+        self.check_if_next_token_is(Token::Null)?;
+
+        match set {
+          true => Ok(ast::AlterColumnAction::SetNotNull),
+          false => Ok(ast::AlterColumnAction::DropNotNull),
+        }
+      }
+      (Some(_), _) => Err(ParserError::UnexpectedToken),
+      (None, _) => Err(ParserError::UnexpectedEndOfStream),
+    }
+  }
+
   fn parse_ddl_statement(&mut self) -> Result<ast::Statement, ParserError> {
     let keyword = self.tokenizer.next();
+    let unique =
+      keyword == Some(Token::Keyword(Keyword::CREATE)) && self.peek_check_if_next_token_is(Token::Keyword(Keyword::UNIQUE));
+    if unique || self.tokenizer.peek() == Some(&Token::Keyword(Keyword::INDEX)) {
+      self.check_if_next_token_is_keyword(Keyword::INDEX)?;
+
+      return match keyword {
+        Some(Token::Keyword(Keyword::CREATE)) => self.parse_create_index_statement(unique),
+        Some(Token::Keyword(Keyword::DROP)) => Ok(ast::Statement::DropIndex { name: self.parse_identifier_expression()? }),
+        _ => Err(ParserError::UnexpectedToken),
+      };
+    }
     self.check_if_next_token_is_keyword(Keyword::TABLE)?;
 
     match keyword {
-      Some(Token::Keyword(Keyword::CREATE)) => {
-        let (name, columns) = self.parse_create_table_statement()?;
-        Ok(ast::Statement::CreateTable { name, columns })
-      }
+      Some(Token::Keyword(Keyword::CREATE)) => self.parse_create_table_statement(),
       Some(Token::Keyword(Keyword::DROP)) => {
         let name = self.parse_identifier_expression()?;
 
@@ -573,3 +1518,15 @@ impl<'a> Parser<'a> {
     }
   }
 }
+
+fn set_operation(operator: ast::SetOperator, all: bool, left: ast::Statement, right: ast::Statement) -> ast::Statement {
+  ast::Statement::SetOperation {
+    operator,
+    all,
+    left: Box::new(left),
+    right: Box::new(right),
+    order_by: None,
+    limit: None,
+    offset: None,
+  }
+}
@@ -1,11 +1,10 @@
-use chrono::{NaiveDate, NaiveDateTime};
-
 use super::{ast, error::ParserError};
 use std::{iter::Peekable, str::Chars};
 
 #[derive(Debug, PartialEq)]
 pub enum Token {
   Keyword(Keyword),
+  Identifier(String),
   String(String),
   Number(String),
   Asterisk,
@@ -14,6 +13,7 @@ pub enum Token {
   LessThan,
   LessThanOrEqual,
   Equal,
+  NotEqual,
   Not,
   Plus,
   Minus,
@@ -22,9 +22,13 @@ pub enum Token {
   OpenParen,
   CloseParen,
   Comma,
+  Period,
   Semicolon,
-  Date(String),
-  Timestamp(String),
+  DoubleColon,
+  Arrow,         // INFO: `->`
+  LongArrow,     // INFO: `->>`
+  HashArrow,     // INFO: `#>`
+  Bytes(String), // INFO: the hex digits of `X'..'`
   Boolean(bool),
   Null,
 }
@@ -33,7 +37,7 @@ impl Token {
   pub fn to_operator(&self) -> Result<ast::Operator, ParserError> {
     match self {
       Token::Equal => Ok(ast::Operator::Equal),
-      Token::Not => Ok(ast::Operator::NotEqual),
+      Token::NotEqual => Ok(ast::Operator::NotEqual),
       Token::LessThan => Ok(ast::Operator::LessThan),
       Token::LessThanOrEqual => Ok(ast::Operator::LessThanOrEqual),
       Token::GreaterThan => Ok(ast::Operator::GreaterThan),
@@ -41,11 +45,15 @@ impl Token {
       Token::Plus => Ok(ast::Operator::Add),
       Token::Minus => Ok(ast::Operator::Subtract),
       Token::Slash => Ok(ast::Operator::Divide),
+      Token::Arrow => Ok(ast::Operator::JsonGet),
+      Token::LongArrow => Ok(ast::Operator::JsonGetText),
+      Token::HashArrow => Ok(ast::Operator::JsonPath),
       _ => Err(ParserError::UnexpectedToken),
     }
   }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, PartialEq)]
 pub enum Keyword {
   SELECT,
@@ -91,6 +99,70 @@ pub enum Keyword {
   COMMIT,
   ROLLBACK,
   TRANSACTION,
+  RETURNING,
+  ON,
+  CONFLICT,
+  DO,
+  NOTHING,
+  EXISTS,
+  IN,
+  AS,
+  WITH,
+  RECURSIVE,
+  UNION,
+  ALL,
+  INTERSECT,
+  EXCEPT,
+  DISTINCT,
+  IS,
+  CASE,
+  WHEN,
+  THEN,
+  ELSE,
+  END,
+  CAST,
+  COALESCE,
+  NULLIF,
+  LIKE,
+  ILIKE,
+  ESCAPE,
+  BETWEEN,
+  OVER,
+  PARTITION,
+  ROWS,
+  RANGE,
+  UNBOUNDED,
+  PRECEDING,
+  FOLLOWING,
+  CURRENT,
+  ROW,
+  DEFAULT,
+  DESCRIBE,
+  CASCADE,
+  RESTRICT,
+  NO,
+  ACTION,
+  CONSTRAINT,
+  RENAME,
+  TO,
+  INTEGER,
+  SMALLINT,
+  BIGINT,
+  REAL,
+  FLOAT,
+  DOUBLE,
+  PRECISION,
+  VARCHAR,
+  CHAR,
+  DECIMAL,
+  NUMERIC,
+  BYTEA,
+  BLOB,
+  JSON,
+  JSONB,
+  INDEX,
+  TIMESTAMPTZ,
+  INTERVAL,
 }
 
 impl Keyword {
@@ -139,6 +211,70 @@ impl Keyword {
       "COMMIT" => Some(Keyword::COMMIT),
       "ROLLBACK" => Some(Keyword::ROLLBACK),
       "TRANSACTION" => Some(Keyword::TRANSACTION),
+      "RETURNING" => Some(Keyword::RETURNING),
+      "ON" => Some(Keyword::ON),
+      "CONFLICT" => Some(Keyword::CONFLICT),
+      "DO" => Some(Keyword::DO),
+      "NOTHING" => Some(Keyword::NOTHING),
+      "EXISTS" => Some(Keyword::EXISTS),
+      "IN" => Some(Keyword::IN),
+      "AS" => Some(Keyword::AS),
+      "WITH" => Some(Keyword::WITH),
+      "RECURSIVE" => Some(Keyword::RECURSIVE),
+      "UNION" => Some(Keyword::UNION),
+      "ALL" => Some(Keyword::ALL),
+      "INTERSECT" => Some(Keyword::INTERSECT),
+      "EXCEPT" => Some(Keyword::EXCEPT),
+      "DISTINCT" => Some(Keyword::DISTINCT),
+      "IS" => Some(Keyword::IS),
+      "CASE" => Some(Keyword::CASE),
+      "WHEN" => Some(Keyword::WHEN),
+      "THEN" => Some(Keyword::THEN),
+      "ELSE" => Some(Keyword::ELSE),
+      "END" => Some(Keyword::END),
+      "CAST" => Some(Keyword::CAST),
+      "COALESCE" => Some(Keyword::COALESCE),
+      "NULLIF" => Some(Keyword::NULLIF),
+      "LIKE" => Some(Keyword::LIKE),
+      "ILIKE" => Some(Keyword::ILIKE),
+      "ESCAPE" => Some(Keyword::ESCAPE),
+      "BETWEEN" => Some(Keyword::BETWEEN),
+      "OVER" => Some(Keyword::OVER),
+      "PARTITION" => Some(Keyword::PARTITION),
+      "ROWS" => Some(Keyword::ROWS),
+      "RANGE" => Some(Keyword::RANGE),
+      "UNBOUNDED" => Some(Keyword::UNBOUNDED),
+      "PRECEDING" => Some(Keyword::PRECEDING),
+      "FOLLOWING" => Some(Keyword::FOLLOWING),
+      "CURRENT" => Some(Keyword::CURRENT),
+      "ROW" => Some(Keyword::ROW),
+      "DEFAULT" => Some(Keyword::DEFAULT),
+      "DESCRIBE" => Some(Keyword::DESCRIBE),
+      "CASCADE" => Some(Keyword::CASCADE),
+      "RESTRICT" => Some(Keyword::RESTRICT),
+      "NO" => Some(Keyword::NO),
+      "ACTION" => Some(Keyword::ACTION),
+      "CONSTRAINT" => Some(Keyword::CONSTRAINT),
+      "RENAME" => Some(Keyword::RENAME),
+      "TO" => Some(Keyword::TO),
+      "INTEGER" => Some(Keyword::INTEGER),
+      "SMALLINT" => Some(Keyword::SMALLINT),
+      "BIGINT" => Some(Keyword::BIGINT),
+      "REAL" => Some(Keyword::REAL),
+      "FLOAT" => Some(Keyword::FLOAT),
+      "DOUBLE" => Some(Keyword::DOUBLE),
+      "PRECISION" => Some(Keyword::PRECISION),
+      "VARCHAR" => Some(Keyword::VARCHAR),
+      "CHAR" => Some(Keyword::CHAR),
+      "DECIMAL" => Some(Keyword::DECIMAL),
+      "NUMERIC" => Some(Keyword::NUMERIC),
+      "BYTEA" => Some(Keyword::BYTEA),
+      "BLOB" => Some(Keyword::BLOB),
+      "JSON" => Some(Keyword::JSON),
+      "JSONB" => Some(Keyword::JSONB),
+      "INDEX" => Some(Keyword::INDEX),
+      "TIMESTAMPTZ" => Some(Keyword::TIMESTAMPTZ),
+      "INTERVAL" => Some(Keyword::INTERVAL),
       _ => None,
     }
   }
@@ -149,9 +285,9 @@ pub struct Tokenizer<'a> {
 }
 
 impl<'a> Iterator for Tokenizer<'a> {
-  type Item = Token;
+  type Item = Result<Token, ParserError>;
 
-  fn next(&mut self) -> Option<Token> {
+  fn next(&mut self) -> Option<Result<Token, ParserError>> {
     self.read()
   }
 }
@@ -180,114 +316,139 @@ impl<'a> Tokenizer<'a> {
     value
   }
 
-  pub fn read(&mut self) -> Option<Token> {
+  fn peek_pair(&self) -> (Option<char>, Option<char>) {
+    let mut iterator = self.iterator.clone();
+    (iterator.next(), iterator.next())
+  }
+
+  // INFO: Skips `-- line` and `/* block */` comments, returns false if there was no comment to skip
+  fn skip_comment(&mut self) -> Result<bool, ParserError> {
+    match self.peek_pair() {
+      (Some('-'), Some('-')) => {
+        self.next_while(|ch| ch != '\n');
+        Ok(true)
+      }
+      (Some('/'), Some('*')) => {
+        self.iterator.next();
+        self.iterator.next();
+
+        while let Some(ch) = self.iterator.next() {
+          if ch == '*' && self.next_if(|c| c == '/').is_some() {
+            return Ok(true);
+          }
+        }
+
+        Err(ParserError::UnterminatedComment)
+      }
+      _ => Ok(false),
+    }
+  }
+
+  // INFO: None at the end of the input, an error for input that isn't a token
+  pub fn read(&mut self) -> Option<Result<Token, ParserError>> {
     self.skip_whitespace();
 
+    loop {
+      match self.skip_comment() {
+        Ok(true) => self.skip_whitespace(),
+        Ok(false) => break,
+        Err(e) => return Some(Err(e)),
+      }
+    }
+
     match self.iterator.peek().cloned() {
-      Some(ch) if ch.is_numeric() => Some(self.read_number()),
-      Some(ch) if ch.is_alphabetic() => Some(self.read_keyword_or_string()),
-      Some(ch) if ch == '\'' => self.read_string(ch).ok(),
-      Some(ch) if is_symbol(ch) => self.read_symbol().ok(),
-      Some(_) => None,
+      Some(ch) if ch.is_numeric() => Some(Ok(self.read_number())),
+      Some('x' | 'X') if self.peek_pair().1 == Some('\'') => {
+        self.iterator.next();
+        Some(self.read_quoted().map(Token::Bytes))
+      }
+      Some(ch) if ch.is_alphabetic() => Some(Ok(self.read_keyword_or_string())),
+      Some(ch) if ch == '\'' => Some(self.read_string(ch)),
+      Some(ch) if is_symbol(ch) => Some(self.read_symbol()),
+      Some(_) => Some(Err(ParserError::UnexpectedSymbol)),
       None => None,
     }
   }
 
   fn read_keyword_or_string(&mut self) -> Token {
-    let string = self.next_while(|c| is_keyword_or_identifier(c));
+    let string = self.next_while(is_keyword_or_identifier);
 
     match string.to_uppercase().as_str() {
       "TRUE" => Token::Boolean(true),
       "FALSE" => Token::Boolean(false),
       "NULL" => Token::Null,
-      _ => Keyword::from_string(&string).map_or_else(|| Token::String(string), Token::Keyword),
+      _ => Keyword::from_string(&string).map_or_else(|| Token::Identifier(string), Token::Keyword),
     }
   }
 
+  // INFO: `12`, `1.5` or `1.5e-3`, a fraction or an exponent is only read when a digit follows it
   fn read_number(&mut self) -> Token {
-    let value = self.next_while(|c| c.is_numeric());
-    Token::Number(value)
-  }
+    let mut value = self.next_while(|c| c.is_numeric());
 
-  fn parse_date_or_timestamp(&self, literal: &str) -> Token {
-    if NaiveDate::parse_from_str(literal, "%Y-%m-%d").is_ok() {
-      return Token::Date(literal.to_string());
+    if let (Some('.'), Some(digit)) = self.peek_pair() {
+      if digit.is_numeric() {
+        value.push_str(&self.next_while(|c| c == '.'));
+        value.push_str(&self.next_while(|c| c.is_numeric()));
+      }
     }
 
-    if NaiveDateTime::parse_from_str(literal, "%Y-%m-%d %H:%M:%S").is_ok() {
-      return Token::Timestamp(literal.to_string());
+    let mut lookahead = self.iterator.clone();
+    if lookahead.next().is_some_and(|c| c == 'e' || c == 'E') {
+      let sign = lookahead.next_if(|c| *c == '+' || *c == '-');
+      if lookahead.next().is_some_and(|c| c.is_numeric()) {
+        value.extend(self.iterator.next());
+        value.extend(sign.and_then(|_| self.iterator.next()));
+        value.push_str(&self.next_while(|c| c.is_numeric()));
+      }
     }
 
-    Token::String(literal.to_string())
+    Token::Number(value)
   }
 
   fn read_string(&mut self, _first: char) -> Result<Token, ParserError> {
-    let mut string = String::new();
+    self.read_quoted().map(Token::String)
+  }
 
-    while let Some(&next) = self.iterator.peek() {
-      match next {
-        'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '\'' | '-' | ':' => {
-          string.push(self.iterator.next().unwrap());
-        }
-        _ => break,
+  fn read_quoted(&mut self) -> Result<String, ParserError> {
+    self.iterator.next();
+    let mut literal = String::new();
+
+    loop {
+      match self.iterator.next() {
+        // INFO: Two single quotes inside of a string are an escaped quote
+        Some('\'') if self.next_if(|c| c == '\'').is_some() => literal.push('\''),
+        Some('\'') => break,
+        Some(ch) => literal.push(ch),
+        None => return Err(ParserError::UnexpectedEndOfStream),
       }
     }
 
-    if string.starts_with('\'') && string.ends_with('\'') {
-      let literal = string[1..string.len() - 1].to_string();
-
-      return Ok(self.parse_date_or_timestamp(&literal));
-    }
-
-
-    // dangerous because we can wrongly parse a string as a keyword where it should be column value or name
-    match string.to_uppercase().as_str() {
-      "SELECT" => Ok(Token::Keyword(Keyword::SELECT)),
-      "FROM" => Ok(Token::Keyword(Keyword::FROM)),
-      "WHERE" => Ok(Token::Keyword(Keyword::WHERE)),
-      "AND" => Ok(Token::Keyword(Keyword::AND)),
-      "OR" => Ok(Token::Keyword(Keyword::OR)),
-      "OFFSET" => Ok(Token::Keyword(Keyword::OFFSET)),
-      "LIMIT" => Ok(Token::Keyword(Keyword::LIMIT)),
-      "INSERT" => Ok(Token::Keyword(Keyword::INSERT)),
-      "INTO" => Ok(Token::Keyword(Keyword::INTO)),
-      "VALUES" => Ok(Token::Keyword(Keyword::VAULES)),
-      "UPDATE" => Ok(Token::Keyword(Keyword::UPDATE)),
-      "SET" => Ok(Token::Keyword(Keyword::SET)),
-      "DELETE" => Ok(Token::Keyword(Keyword::DELETE)),
-      "CREATE" => Ok(Token::Keyword(Keyword::CREATE)),
-      "TABLE" => Ok(Token::Keyword(Keyword::TABLE)),
-      "ALTER" => Ok(Token::Keyword(Keyword::ALTER)),
-      "DROP" => Ok(Token::Keyword(Keyword::DROP)),
-      "PRIMARY" => Ok(Token::Keyword(Keyword::PRIMARY)),
-      "KEY" => Ok(Token::Keyword(Keyword::KEY)),
-      "FOREIGN" => Ok(Token::Keyword(Keyword::FOREIGN)),
-      "COLUMN" => Ok(Token::Keyword(Keyword::COLUMN)),
-      "INT" => Ok(Token::Keyword(Keyword::INT)),
-      "DATE" => Ok(Token::Keyword(Keyword::DATE)),
-      "TIMESTAMP" => Ok(Token::Keyword(Keyword::TIMESTAMP)),
-      "BOOLEAN" => Ok(Token::Keyword(Keyword::BOOLEAN)),
-      "NULL" => Ok(Token::Keyword(Keyword::NULL)),
-      "NOT" => Ok(Token::Keyword(Keyword::NOT)),
-      _ => Ok(Token::String(string)),
-    }
+    Ok(literal)
   }
 
   fn read_symbol(&mut self) -> Result<Token, ParserError> {
     match self.iterator.next() {
       Some('*') => Ok(Token::Asterisk),
       Some('>') => self.read_compound_token(Token::GreaterThan, Token::GreaterThanOrEqual),
+      Some('<') if self.next_if(|c| c == '>').is_some() => Ok(Token::NotEqual),
       Some('<') => self.read_compound_token(Token::LessThan, Token::LessThanOrEqual),
       Some('=') => Ok(Token::Equal),
-      Some('!') => Ok(Token::Not),
+      Some('!') => self.read_compound_token(Token::Not, Token::NotEqual),
       Some('+') => Ok(Token::Plus),
+      Some('-') if self.next_if(|c| c == '>').is_some() => match self.next_if(|c| c == '>') {
+        Some(_) => Ok(Token::LongArrow),
+        None => Ok(Token::Arrow),
+      },
       Some('-') => Ok(Token::Minus),
+      Some('#') if self.next_if(|c| c == '>').is_some() => Ok(Token::HashArrow),
       Some('/') => Ok(Token::Slash),
       Some('%') => Ok(Token::Percent),
       Some('(') => Ok(Token::OpenParen),
       Some(')') => Ok(Token::CloseParen),
       Some(',') => Ok(Token::Comma),
+      Some('.') => Ok(Token::Period),
       Some(';') => Ok(Token::Semicolon),
+      Some(':') if self.next_if(|c| c == ':').is_some() => Ok(Token::DoubleColon),
       _ => Err(ParserError::UnexpectedSymbol),
     }
   }
@@ -303,13 +464,10 @@ impl<'a> Tokenizer<'a> {
 }
 
 fn is_keyword_or_identifier(ch: char) -> bool {
-  ch.is_alphabetic() || ch == '_'
+  ch.is_alphanumeric() || ch == '_'
 }
 
 // trafer it to utils.rs
 fn is_symbol(ch: char) -> bool {
-  match ch {
-    '*' | '>' | '<' | '=' | '!' | '+' | '-' | '/' | '%' | '(' | ')' | ',' | ';' => true,
-    _ => false,
-  }
+  matches!(ch, '*' | '>' | '<' | '=' | '!' | '+' | '-' | '/' | '%' | '(' | ')' | ',' | '.' | ';' | ':' | '#')
 }
@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::sql::catalog::DataType;
+
+#[derive(Debug)]
+pub enum PlannerError {
+  TableNotFound(String),
+  TableAlreadyExists(String),
+  ColumnNotFound(String),
+  AmbiguousColumn(String),
+  DuplicateColumn(String),
+  ColumnAlreadyExists { table: String, column: String },
+  ColumnCountMismatch { expected: usize, found: usize },
+  TypeMismatch { column: String, expected: DataType, found: DataType },
+  NoConflictIndex(Vec<String>),
+  SubqueryColumnCount(usize),
+  CteColumnCount { name: String, expected: usize, found: usize },
+  SetOperationColumnCount { operator: String, left: usize, right: usize },
+  SetOperationTypeMismatch { operator: String, column: String, left: DataType, right: DataType },
+  FunctionNotFound(String),
+  FunctionAlreadyExists(String),
+  FunctionArgumentCount { function: String, expected: String, found: usize },
+  FunctionArgumentType { function: String, position: usize, expected: String, found: DataType },
+  AggregateNotAllowed(String),
+  NestedAggregate(String),
+  NotGrouped(String),
+  WindowNotAllowed(String),
+  NotWindowFunction(String),
+  InvalidWindowFrame(String),
+  MultiplePrimaryKeys(String),
+  InvalidConstraint { constraint: String, reason: String },
+  InvalidDefault { column: String, reason: String },
+  InvalidStoredExpression { expression: String, reason: String },
+  DuplicateConstraint(String),
+  ConstraintNotFound { table: String, constraint: String },
+  ReferencedTable { table: String, referencing: String },
+  ReferencedConstraint { constraint: String, referencing: String },
+  InvalidAlterTable { table: String, reason: String },
+  IndexAlreadyExists(String),
+  IndexNotFound(String),
+  InvalidIndex { index: String, reason: String },
+  TransactionsNotSupported,
+  Unsupported(String),
+}
+
+impl fmt::Display for PlannerError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PlannerError::TableNotFound(table) => write!(f, "Planning Error: Table '{}' does not exist.", table),
+      PlannerError::TableAlreadyExists(table) => write!(f, "Planning Error: Table '{}' already exists.", table),
+      PlannerError::ColumnNotFound(column) => write!(f, "Planning Error: Column '{}' does not exist.", column),
+      PlannerError::AmbiguousColumn(column) => write!(f, "Planning Error: Column '{}' is ambiguous.", column),
+      PlannerError::DuplicateColumn(column) => write!(f, "Planning Error: Column '{}' is specified more than once.", column),
+      PlannerError::ColumnAlreadyExists { table, column } => {
+        write!(f, "Planning Error: Column '{}' of table '{}' already exists.", column, table)
+      }
+      PlannerError::ColumnCountMismatch { expected, found } => {
+        write!(f, "Planning Error: Expected {} values but found {}.", expected, found)
+      }
+      PlannerError::TypeMismatch { column, expected, found } => {
+        write!(f, "Planning Error: Column '{}' is of type {} but the value is of type {}.", column, expected, found)
+      }
+      PlannerError::NoConflictIndex(columns) => {
+        write!(f, "Planning Error: There is no unique constraint matching the ON CONFLICT columns ({}).", columns.join(", "))
+      }
+      PlannerError::SubqueryColumnCount(count) => {
+        write!(f, "Planning Error: Subquery has to return exactly one column but it returns {}.", count)
+      }
+      PlannerError::CteColumnCount { name, expected, found } => {
+        write!(f, "Planning Error: WITH query '{}' has {} columns but {} column names were given.", name, expected, found)
+      }
+      PlannerError::SetOperationColumnCount { operator, left, right } => {
+        write!(f, "Planning Error: Each {} query must have the same number of columns, found {} and {}.", operator, left, right)
+      }
+      PlannerError::SetOperationTypeMismatch { operator, column, left, right } => {
+        write!(f, "Planning Error: {} can't combine {} and {} in column '{}'.", operator, left, right, column)
+      }
+      PlannerError::FunctionNotFound(function) => write!(f, "Planning Error: Function '{}' does not exist.", function),
+      PlannerError::FunctionAlreadyExists(function) => write!(f, "Planning Error: Function '{}' already exists.", function),
+      PlannerError::FunctionArgumentCount { function, expected, found } => {
+        write!(f, "Planning Error: Function '{}' takes {} arguments but {} were given.", function, expected, found)
+      }
+      PlannerError::FunctionArgumentType { function, position, expected, found } => write!(
+        f,
+        "Planning Error: Argument {} of function '{}' has to be {} but it is of type {}.",
+        position, function, expected, found
+      ),
+      PlannerError::AggregateNotAllowed(clause) => {
+        write!(f, "Planning Error: Aggregate functions are not allowed in {}.", clause)
+      }
+      PlannerError::NestedAggregate(function) => {
+        write!(f, "Planning Error: Aggregate function '{}' can't be nested in another aggregate.", function)
+      }
+      PlannerError::NotGrouped(column) => {
+        write!(f, "Planning Error: Column '{}' must appear in the GROUP BY clause or be used in an aggregate function.", column)
+      }
+      PlannerError::WindowNotAllowed(clause) => write!(f, "Planning Error: Window functions are not allowed in {}.", clause),
+      PlannerError::NotWindowFunction(function) => {
+        write!(f, "Planning Error: Function '{}' is neither a window function nor an aggregate function.", function)
+      }
+      PlannerError::InvalidWindowFrame(reason) => write!(f, "Planning Error: Invalid window frame, {}.", reason),
+      PlannerError::MultiplePrimaryKeys(table) => {
+        write!(f, "Planning Error: Table '{}' can't have more than one primary key.", table)
+      }
+      PlannerError::InvalidConstraint { constraint, reason } => {
+        write!(f, "Planning Error: Constraint '{}' is invalid, {}.", constraint, reason)
+      }
+      PlannerError::InvalidDefault { column, reason } => {
+        write!(f, "Planning Error: DEFAULT of column '{}' is invalid, {}.", column, reason)
+      }
+      PlannerError::InvalidStoredExpression { expression, reason } => {
+        write!(f, "Planning Error: Stored expression '{}' can't be parsed, {}", expression, reason)
+      }
+      PlannerError::DuplicateConstraint(constraint) => write!(f, "Planning Error: Constraint '{}' already exists.", constraint),
+      PlannerError::ConstraintNotFound { table, constraint } => {
+        write!(f, "Planning Error: Constraint '{}' of table '{}' does not exist.", constraint, table)
+      }
+      PlannerError::ReferencedTable { table, referencing } => {
+        write!(f, "Planning Error: Table '{}' can't be dropped because table '{}' references it.", table, referencing)
+      }
+      PlannerError::ReferencedConstraint { constraint, referencing } => {
+        write!(f, "Planning Error: Constraint '{}' can't be dropped because table '{}' references it.", constraint, referencing)
+      }
+      PlannerError::InvalidAlterTable { table, reason } => {
+        write!(f, "Planning Error: Table '{}' can't be altered, {}.", table, reason)
+      }
+      PlannerError::IndexAlreadyExists(index) => write!(f, "Planning Error: Index '{}' already exists.", index),
+      PlannerError::IndexNotFound(index) => write!(f, "Planning Error: Index '{}' does not exist.", index),
+      PlannerError::InvalidIndex { index, reason } => write!(f, "Planning Error: Index '{}' is invalid, {}.", index, reason),
+      PlannerError::TransactionsNotSupported => write!(f, "Planning Error: Transactions are not supported yet."),
+      PlannerError::Unsupported(feature) => write!(f, "Planning Error: {} is not supported yet.", feature),
+    }
+  }
+}
+
+impl std::error::Error for PlannerError {}
@@ -1,41 +1,178 @@
-#![allow(unused)]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use std::convert::Infallible;
+use std::fmt;
 use std::vec;
 
 use crate::sql::{
-  catalog::{Column, DataType, Table},
-  parser::ast::{self, AlterTableOperation, Statement},
+  catalog::{
+    self, parse_path, Catalog, Column, Constraint, ConstraintKind, DataType, Decimal, Index, Interval, Json, JsonPath, Reference,
+    ReferentialAction, Table, Uuid,
+  },
+  function::{FunctionRegistry, ScalarFunction, UserAggregate},
+  parser::{
+    ast::{self, AlterTableOperation, InsertSource},
+    Parser,
+  },
 };
 
+use super::error::PlannerError;
+
 #[derive(Debug)]
 pub struct Plan(pub Node);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
   // INFO: DDl sts
-  CreateTable { schema: Table },
-  DropTable { table: String },
-  AlterTable { table: String, values: Vec<(Expression, Expression)> },
+  CreateTable {
+    schema: Table,
+  },
+  Describe {
+    table: String,
+  },
+  DropTable {
+    table: String,
+  },
+  AlterTable {
+    table: String,
+    schema: Table,
+    action: AlterAction,
+    constraints: RowConstraints,
+  }, // INFO: schema is the new one
 
   // INFO: Insert sts
-  Insert { table: String, values: Vec<(Expression, Expression)> },
-  Update { table: String, values: Vec<(Expression, Expression)> },
-  Delete { table: String },
+  Insert {
+    table: String,
+    columns: Vec<String>,
+    source: Box<Node>,
+    on_conflict: Option<OnConflict>,
+    returning: Option<Vec<Expression>>,
+    constraints: RowConstraints,
+  },
+  Update {
+    table: String,
+    source: Box<Node>,
+    values: Vec<(Expression, Expression)>,
+    returning: Option<Vec<Expression>>,
+    constraints: RowConstraints,
+  },
+  Delete {
+    table: String,
+    source: Box<Node>,
+    returning: Option<Vec<Expression>>,
+  },
 
   // INFO: Select sts
-  Limit { source: Box<Node>, limit: Expression },
-  Offset { source: Box<Node>, offset: Expression },
-  Projection { source: Box<Node>, columns: Vec<Expression> },
-  Filter { source: Box<Node>, condition: Expression },
-  GroupBy { source: Box<Node>, values: Vec<Expression> },
-  Having { source: Box<Node>, condition: Expression }, // INFO: Maybe this can go to filter
+  Limit {
+    source: Box<Node>,
+    limit: Expression,
+  },
+  Offset {
+    source: Box<Node>,
+    offset: Expression,
+  },
+  Projection {
+    source: Box<Node>,
+    columns: Vec<Expression>,
+  },
+  Filter {
+    source: Box<Node>,
+    condition: Expression,
+  },
+  GroupBy {
+    source: Box<Node>,
+    values: Vec<Expression>,
+    aggregates: Vec<Expression>,
+  }, // INFO: outputs keys then aggregates
+  Window {
+    source: Box<Node>,
+    functions: Vec<Expression>,
+  }, // INFO: outputs the source columns then one column per function
+  Distinct {
+    source: Box<Node>,
+    on: Option<Vec<Expression>>,
+  }, // INFO: without `on` whole rows are compared
+  Having {
+    source: Box<Node>,
+    condition: Expression,
+  }, // INFO: Maybe this can go to filter
 
-  Scan { table: String, alias: Option<String>, filter: Option<Expression> },
+  Scan {
+    table: String,
+    alias: Option<String>,
+    filter: Option<Expression>,
+  },
+  Values {
+    rows: Vec<Vec<Expression>>,
+  },
+  Subquery {
+    source: Box<Node>,
+    alias: String,
+  }, // INFO: SELECT in FROM, its columns are qualified by the alias
+
+  // INFO: WITH RECURSIVE, the recursive query runs on the rows of the previous round until it returns no new rows
+  Recursive {
+    name: String,
+    anchor: Box<Node>,
+    recursive: Box<Node>,
+    distinct: bool,
+  },
+  WorkingTable {
+    name: String,
+  }, // INFO: rows of the previous round of the recursive CTE with this name
+  SetOperation {
+    operator: SetOperator,
+    all: bool,
+    left: Box<Node>,
+    right: Box<Node>,
+  },
 
   // TODO: Implement
-  IndexLookup { table: String, alias: Option<String>, index: String },
-  NestedLoopJoin { left: Box<Node>, right: Box<Node>, condition: Expression },
-  HashJoin { left: Box<Node>, right: Box<Node>, condition: Expression },
-  Sort { source: Box<Node>, order: Vec<(Expression, bool)> },
+  IndexLookup {
+    table: String,
+    alias: Option<String>,
+    index: String,
+  },
+  NestedLoopJoin {
+    left: Box<Node>,
+    right: Box<Node>,
+    condition: Expression,
+    join_type: JoinType,
+  },
+  HashJoin {
+    left: Box<Node>,
+    right: Box<Node>,
+    condition: Expression,
+    join_type: JoinType,
+  },
+  Sort {
+    source: Box<Node>,
+    order: Vec<(Expression, bool)>,
+  }, // INFO: true is ascending order
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SetOperator {
+  Union,
+  Intersect,
+  Except,
+}
+
+impl fmt::Display for SetOperator {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SetOperator::Union => write!(f, "UNION"),
+      SetOperator::Intersect => write!(f, "INTERSECT"),
+      SetOperator::Except => write!(f, "EXCEPT"),
+    }
+  }
+}
+
+// INFO: Semi and anti joins return the left rows that have or don't have a match, without the right columns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JoinType {
+  Inner,
+  Semi,
+  Anti,
 }
 
 impl Node {
@@ -46,7 +183,7 @@ impl Node {
       Node::AlterTable { table, .. } => Some(table),
       Node::Insert { table, .. } => Some(table),
       Node::Update { table, .. } => Some(table),
-      Node::Delete { table } => Some(table),
+      Node::Delete { table, .. } => Some(table),
       Node::Scan { table, .. } => Some(table),
       _ => None,
     }
@@ -57,36 +194,218 @@ impl Node {
 
     match self {
       Node::CreateTable { .. }
+      | Node::Describe { .. }
       | Node::DropTable { .. }
       | Node::AlterTable { .. }
-      | Node::Insert { .. }
-      | Node::Update { .. }
-      | Node::Delete { .. }
-      | Node::Scan { .. } => nodes,
-      Node::Limit { source, .. }
+      | Node::Scan { .. }
+      | Node::IndexLookup { .. }
+      | Node::WorkingTable { .. }
+      | Node::Values { .. } => nodes,
+      Node::Insert { source, .. }
+      | Node::Update { source, .. }
+      | Node::Delete { source, .. }
+      | Node::Sort { source, .. }
+      | Node::Limit { source, .. }
       | Node::Offset { source, .. }
       | Node::Projection { source, .. }
       | Node::Filter { source, .. }
       | Node::GroupBy { source, .. }
-      | Node::Having { source, .. } => {
+      | Node::Window { source, .. }
+      | Node::Distinct { source, .. }
+      | Node::Having { source, .. }
+      | Node::Subquery { source, .. } => {
         nodes.push(source);
         nodes
       }
-      _ => unimplemented!(),
+      Node::NestedLoopJoin { left, right, .. }
+      | Node::HashJoin { left, right, .. }
+      | Node::Recursive { anchor: left, recursive: right, .. }
+      | Node::SetOperation { left, right, .. } => {
+        nodes.push(left);
+        nodes.push(right);
+        nodes
+      }
+    }
+  }
+
+  pub fn children_mut(&mut self) -> Vec<&mut Node> {
+    match self {
+      Node::CreateTable { .. }
+      | Node::Describe { .. }
+      | Node::DropTable { .. }
+      | Node::AlterTable { .. }
+      | Node::Scan { .. }
+      | Node::IndexLookup { .. }
+      | Node::WorkingTable { .. }
+      | Node::Values { .. } => vec![],
+      Node::Insert { source, .. }
+      | Node::Update { source, .. }
+      | Node::Delete { source, .. }
+      | Node::Sort { source, .. }
+      | Node::Limit { source, .. }
+      | Node::Offset { source, .. }
+      | Node::Projection { source, .. }
+      | Node::Filter { source, .. }
+      | Node::GroupBy { source, .. }
+      | Node::Window { source, .. }
+      | Node::Distinct { source, .. }
+      | Node::Having { source, .. }
+      | Node::Subquery { source, .. } => vec![source],
+      Node::NestedLoopJoin { left, right, .. }
+      | Node::HashJoin { left, right, .. }
+      | Node::Recursive { anchor: left, recursive: right, .. }
+      | Node::SetOperation { left, right, .. } => vec![left, right],
+    }
+  }
+
+  // INFO: Expressions held by this node, the ones of its children are not included
+  pub fn expressions(&self) -> Vec<&Expression> {
+    match self {
+      Node::CreateTable { .. }
+      | Node::Describe { .. }
+      | Node::DropTable { .. }
+      | Node::AlterTable { .. }
+      | Node::IndexLookup { .. }
+      | Node::Subquery { .. }
+      | Node::Recursive { .. }
+      | Node::SetOperation { .. }
+      | Node::WorkingTable { .. } => vec![],
+      Node::Insert { on_conflict, returning, .. } => {
+        let mut expressions: Vec<&Expression> = returning.iter().flatten().collect();
+
+        if let Some(OnConflict { action: ConflictAction::DoUpdate { values, condition }, .. }) = on_conflict {
+          expressions.extend(values.iter().map(|(_, value)| value).chain(condition));
+        }
+
+        expressions
+      }
+      Node::Update { values, returning, .. } => {
+        values.iter().flat_map(|(column, value)| [column, value]).chain(returning.iter().flatten()).collect()
+      }
+      Node::Delete { returning, .. } => returning.iter().flatten().collect(),
+      Node::Limit { limit: expression, .. }
+      | Node::Offset { offset: expression, .. }
+      | Node::Filter { condition: expression, .. }
+      | Node::Having { condition: expression, .. }
+      | Node::NestedLoopJoin { condition: expression, .. }
+      | Node::HashJoin { condition: expression, .. } => vec![expression],
+      Node::Projection { columns: expressions, .. } => expressions.iter().collect(),
+      Node::GroupBy { values, aggregates, .. } => values.iter().chain(aggregates).collect(),
+      Node::Window { functions, .. } => functions.iter().collect(),
+      Node::Distinct { on, .. } => on.iter().flatten().collect(),
+      Node::Scan { filter, .. } => filter.iter().collect(),
+      Node::Values { rows } => rows.iter().flatten().collect(),
+      Node::Sort { order, .. } => order.iter().map(|(expression, _)| expression).collect(),
+    }
+  }
+
+  pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+    match self {
+      Node::CreateTable { .. }
+      | Node::Describe { .. }
+      | Node::DropTable { .. }
+      | Node::AlterTable { .. }
+      | Node::IndexLookup { .. }
+      | Node::Subquery { .. }
+      | Node::Recursive { .. }
+      | Node::SetOperation { .. }
+      | Node::WorkingTable { .. } => vec![],
+      Node::Insert { on_conflict, returning, .. } => {
+        let mut expressions: Vec<&mut Expression> = returning.iter_mut().flatten().collect();
+
+        if let Some(OnConflict { action: ConflictAction::DoUpdate { values, condition }, .. }) = on_conflict {
+          expressions.extend(values.iter_mut().map(|(_, value)| value).chain(condition));
+        }
+
+        expressions
+      }
+      Node::Update { values, returning, .. } => {
+        values.iter_mut().flat_map(|(column, value)| [column, value]).chain(returning.iter_mut().flatten()).collect()
+      }
+      Node::Delete { returning, .. } => returning.iter_mut().flatten().collect(),
+      Node::Limit { limit: expression, .. }
+      | Node::Offset { offset: expression, .. }
+      | Node::Filter { condition: expression, .. }
+      | Node::Having { condition: expression, .. }
+      | Node::NestedLoopJoin { condition: expression, .. }
+      | Node::HashJoin { condition: expression, .. } => vec![expression],
+      Node::Projection { columns: expressions, .. } => expressions.iter_mut().collect(),
+      Node::GroupBy { values, aggregates, .. } => values.iter_mut().chain(aggregates).collect(),
+      Node::Window { functions, .. } => functions.iter_mut().collect(),
+      Node::Distinct { on, .. } => on.iter_mut().flatten().collect(),
+      Node::Scan { filter, .. } => filter.iter_mut().collect(),
+      Node::Values { rows } => rows.iter_mut().flatten().collect(),
+      Node::Sort { order, .. } => order.iter_mut().map(|(expression, _)| expression).collect(),
     }
   }
+
+  // INFO: Whether the node uses a column of a query that is `depth` or more levels above it
+  pub fn references_outer(&self, depth: usize) -> bool {
+    self.expressions().into_iter().any(|expression| expression.references_outer(depth))
+      || self.tranverse().into_iter().skip(1).any(|node| node.references_outer(depth))
+  }
 }
 
-#[derive(Debug, Clone)]
+// INFO: What a write evaluates for every row it stores, NOT NULL and UNIQUE come from the catalog.
+// The expressions are bound to the target table
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RowConstraints {
+  pub defaults: Vec<(String, Expression)>, // INFO: values of the columns an INSERT doesn't list
+  pub checks: Vec<Check>,
+}
+
+impl RowConstraints {
+  pub fn expressions_mut(&mut self) -> Vec<&mut Expression> {
+    let defaults = self.defaults.iter_mut().map(|(_, value)| value);
+    defaults.chain(self.checks.iter_mut().map(|check| &mut check.condition)).collect()
+  }
+}
+
+// INFO: How the stored rows follow an ALTER TABLE, the other changes only touch the catalog and the indexes
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlterAction {
+  AddColumn { column: String, default: Option<Expression> }, // INFO: without a default the rows are read as NULL
+  DropColumn(String),
+  ModifyColumn(String), // INFO: the stored values are cast to the new type
+  RenameColumn { from: String, to: String },
+  RenameTable,
+  Constraints,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+  pub name: String,
+  pub columns: Vec<String>,
+  pub condition: Expression,
+}
+
+// INFO: indexes are the unique indexes a new row is checked against
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnConflict {
+  pub indexes: Vec<String>,
+  pub action: ConflictAction,
+}
+
+// INFO: DO UPDATE expressions only use qualified identifiers, the target table or `excluded`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictAction {
+  DoNothing,
+  DoUpdate { values: Vec<(String, Expression)>, condition: Option<Expression> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
   Identifier(String),
   Constant(Value),
   DataType(DataType),
+  Alias(Box<Expression>, String),
 
   Equal(Box<Expression>, Box<Expression>),
   NotEqual(Box<Expression>, Box<Expression>),
   GreaterThan(Box<Expression>, Box<Expression>),
   GreaterThanOrEqual(Box<Expression>, Box<Expression>),
+  LessThan(Box<Expression>, Box<Expression>),
+  LessThanOrEqual(Box<Expression>, Box<Expression>),
 
   Add(Box<Expression>, Box<Expression>),
   Subtract(Box<Expression>, Box<Expression>),
@@ -95,178 +414,2368 @@ pub enum Expression {
 
   And(Box<Expression>, Box<Expression>),
   Or(Box<Expression>, Box<Expression>),
+  Not(Box<Expression>),
+  Negate(Box<Expression>),
+
+  IsNull(Box<Expression>),
+  Case(Vec<(Expression, Expression)>, Option<Box<Expression>>), // INFO: a simple CASE is bound as a searched one
+  Coalesce(Vec<Expression>),
+  NullIf(Box<Expression>, Box<Expression>),
+  Cast(Box<Expression>, DataType),
+
+  // INFO: Subqueries are replaced by their results before the expression is evaluated
+  Subquery(Box<Node>),
+  Exists(Box<Node>),
+  InSubquery(Box<Expression>, Box<Node>),
+  InList(Box<Expression>, Vec<Expression>),
+  Between(Box<Expression>, Box<Expression>, Box<Expression>),
+  Like(Box<Expression>, Box<Expression>, Option<char>), // INFO: the character is the escape
+  ILike(Box<Expression>, Box<Expression>, Option<char>),
+  Outer(String, usize), // INFO: column of an enclosing query, the number says how many queries up it is
+
+  Aggregate(AggregateFunction, Vec<Expression>, bool), // INFO: the flag is DISTINCT, COUNT(*) has no arguments
+  Function(ScalarFunction, Vec<Expression>),
+  Window(WindowFunction, Vec<Expression>, Box<Window>),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowFunction {
+  RowNumber,
+  Rank,
+  DenseRank,
+  Lag,
+  Lead,
+  FirstValue,
+  Aggregate(AggregateFunction, bool), // INFO: the flag is DISTINCT
+}
+
+// INFO: The frame is always given, the binder fills in the default one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Window {
+  pub partition_by: Vec<Expression>,
+  pub order_by: Vec<(Expression, bool)>, // INFO: true is ascending order
+  pub frame: ast::WindowFrame,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AggregateFunction {
+  Count,
+  Sum,
+  Avg,
+  Min,
+  Max,
+  User(UserAggregate),
+}
+
+impl AggregateFunction {
+  pub fn from_name(name: &str) -> Option<AggregateFunction> {
+    match name.to_uppercase().as_str() {
+      "COUNT" => Some(AggregateFunction::Count),
+      "SUM" => Some(AggregateFunction::Sum),
+      "AVG" => Some(AggregateFunction::Avg),
+      "MIN" => Some(AggregateFunction::Min),
+      "MAX" => Some(AggregateFunction::Max),
+      _ => None,
+    }
+  }
+}
+
+impl WindowFunction {
+  pub fn from_name(name: &str) -> Option<WindowFunction> {
+    match name.to_uppercase().as_str() {
+      "ROW_NUMBER" => Some(WindowFunction::RowNumber),
+      "RANK" => Some(WindowFunction::Rank),
+      "DENSE_RANK" => Some(WindowFunction::DenseRank),
+      "LAG" => Some(WindowFunction::Lag),
+      "LEAD" => Some(WindowFunction::Lead),
+      "FIRST_VALUE" => Some(WindowFunction::FirstValue),
+      _ => None,
+    }
+  }
+
+  // INFO: Accepted argument counts of the functions that are not aggregates
+  fn arity(&self) -> (usize, usize) {
+    match self {
+      WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank => (0, 0),
+      WindowFunction::Lag | WindowFunction::Lead => (1, 3), // INFO: value, offset and default
+      WindowFunction::FirstValue | WindowFunction::Aggregate(..) => (1, 1),
+    }
+  }
+}
+
+impl fmt::Display for WindowFunction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      WindowFunction::RowNumber => write!(f, "ROW_NUMBER"),
+      WindowFunction::Rank => write!(f, "RANK"),
+      WindowFunction::DenseRank => write!(f, "DENSE_RANK"),
+      WindowFunction::Lag => write!(f, "LAG"),
+      WindowFunction::Lead => write!(f, "LEAD"),
+      WindowFunction::FirstValue => write!(f, "FIRST_VALUE"),
+      WindowFunction::Aggregate(function, _) => write!(f, "{}", function),
+    }
+  }
+}
+
+impl fmt::Display for AggregateFunction {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      AggregateFunction::Count => write!(f, "COUNT"),
+      AggregateFunction::Sum => write!(f, "SUM"),
+      AggregateFunction::Avg => write!(f, "AVG"),
+      AggregateFunction::Min => write!(f, "MIN"),
+      AggregateFunction::Max => write!(f, "MAX"),
+      AggregateFunction::User(aggregate) => write!(f, "{}", aggregate.name),
+    }
+  }
+}
+
+impl Expression {
+  // INFO: Expressions directly under this one, subqueries are not entered since they have their own scope
+  pub fn children(&self) -> Vec<&Expression> {
+    match self {
+      Expression::Identifier(_)
+      | Expression::Constant(_)
+      | Expression::DataType(_)
+      | Expression::Outer(..)
+      | Expression::Subquery(_)
+      | Expression::Exists(_) => vec![],
+      Expression::Alias(expression, _)
+      | Expression::Not(expression)
+      | Expression::Negate(expression)
+      | Expression::IsNull(expression)
+      | Expression::Cast(expression, _)
+      | Expression::InSubquery(expression, _) => vec![expression],
+      Expression::InList(expression, list) => std::iter::once(&**expression).chain(list).collect(),
+      Expression::Case(branches, default) => {
+        branches.iter().flat_map(|(condition, result)| [condition, result]).chain(default.as_deref()).collect()
+      }
+      Expression::Coalesce(expressions) => expressions.iter().collect(),
+      Expression::Between(expression, low, high) => vec![expression, low, high],
+      Expression::Aggregate(_, args, _) | Expression::Function(_, args) => args.iter().collect(),
+      Expression::Window(_, args, window) => {
+        let order = window.order_by.iter().map(|(expression, _)| expression);
+        args.iter().chain(&window.partition_by).chain(order).collect()
+      }
+      Expression::Equal(left, right)
+      | Expression::NotEqual(left, right)
+      | Expression::GreaterThan(left, right)
+      | Expression::GreaterThanOrEqual(left, right)
+      | Expression::LessThan(left, right)
+      | Expression::LessThanOrEqual(left, right)
+      | Expression::Add(left, right)
+      | Expression::Subtract(left, right)
+      | Expression::Multiply(left, right)
+      | Expression::Divide(left, right)
+      | Expression::And(left, right)
+      | Expression::Or(left, right)
+      | Expression::NullIf(left, right)
+      | Expression::Like(left, right, _)
+      | Expression::ILike(left, right, _) => vec![left, right],
+    }
+  }
+
+  pub fn identifiers(&self) -> Vec<&String> {
+    match self {
+      Expression::Identifier(name) => vec![name],
+      expression => expression.children().into_iter().flat_map(Expression::identifiers).collect(),
+    }
+  }
+
+  pub fn subquery(&self) -> Option<&Node> {
+    match self {
+      Expression::Subquery(node) | Expression::Exists(node) | Expression::InSubquery(_, node) => Some(node),
+      _ => None,
+    }
+  }
+
+  pub fn has_aggregate(&self) -> bool {
+    matches!(self, Expression::Aggregate(..)) || self.children().into_iter().any(Expression::has_aggregate)
+  }
+
+  pub fn has_window(&self) -> bool {
+    matches!(self, Expression::Window(..)) || self.children().into_iter().any(Expression::has_window)
+  }
+
+  pub fn has_subquery(&self) -> bool {
+    self.subquery().is_some() || self.children().into_iter().any(Expression::has_subquery)
+  }
+
+  // INFO: Whether the expression uses a column of a query that is `depth` or more levels above it
+  pub fn references_outer(&self, depth: usize) -> bool {
+    match self {
+      Expression::Outer(_, level) => *level >= depth,
+      expression => {
+        expression.subquery().is_some_and(|node| node.references_outer(depth + 1))
+          || expression.children().into_iter().any(|child| child.references_outer(depth))
+      }
+    }
+  }
+
+  // INFO: Rebuilds the expression bottom up, passing every node through the given function
+  pub fn try_transform<E>(self, f: &mut impl FnMut(Expression) -> Result<Expression, E>) -> Result<Expression, E> {
+    let expression = self.try_map_children(&mut |child| child.try_transform(f))?;
+    f(expression)
+  }
+
+  // INFO: Rebuilds the expression with every direct child passed through the given function
+  pub fn try_map_children<E>(self, f: &mut impl FnMut(Expression) -> Result<Expression, E>) -> Result<Expression, E> {
+    let expression = match self {
+      Expression::Alias(expression, alias) => Expression::Alias(boxed(*expression, f)?, alias),
+      Expression::Not(expression) => Expression::Not(boxed(*expression, f)?),
+      Expression::Negate(expression) => Expression::Negate(boxed(*expression, f)?),
+      Expression::IsNull(expression) => Expression::IsNull(boxed(*expression, f)?),
+      Expression::Cast(expression, data_type) => Expression::Cast(boxed(*expression, f)?, data_type),
+      Expression::Case(branches, default) => {
+        let branches =
+          branches.into_iter().map(|(condition, result)| Ok((f(condition)?, f(result)?))).collect::<Result<_, _>>()?;
+        Expression::Case(branches, default.map(|default| boxed(*default, f)).transpose()?)
+      }
+      Expression::Coalesce(expressions) => Expression::Coalesce(expressions.into_iter().map(&mut *f).collect::<Result<_, _>>()?),
+      Expression::NullIf(left, right) => Expression::NullIf(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::Between(expression, low, high) => {
+        Expression::Between(boxed(*expression, f)?, boxed(*low, f)?, boxed(*high, f)?)
+      }
+      Expression::Like(left, right, escape) => Expression::Like(boxed(*left, f)?, boxed(*right, f)?, escape),
+      Expression::ILike(left, right, escape) => Expression::ILike(boxed(*left, f)?, boxed(*right, f)?, escape),
+      Expression::InSubquery(expression, node) => Expression::InSubquery(boxed(*expression, f)?, node),
+      Expression::InList(expression, list) => {
+        let list = list.into_iter().map(&mut *f).collect::<Result<_, _>>()?;
+        Expression::InList(boxed(*expression, f)?, list)
+      }
+      Expression::Aggregate(function, args, distinct) => {
+        Expression::Aggregate(function, args.into_iter().map(&mut *f).collect::<Result<_, _>>()?, distinct)
+      }
+      Expression::Function(function, args) => {
+        Expression::Function(function, args.into_iter().map(&mut *f).collect::<Result<_, _>>()?)
+      }
+      Expression::Window(function, args, window) => {
+        let args = args.into_iter().map(&mut *f).collect::<Result<_, _>>()?;
+        let Window { partition_by, order_by, frame } = *window;
+        let window = Window {
+          partition_by: partition_by.into_iter().map(&mut *f).collect::<Result<_, _>>()?,
+          order_by: order_by
+            .into_iter()
+            .map(|(expression, ascending)| Ok((f(expression)?, ascending)))
+            .collect::<Result<_, _>>()?,
+          frame,
+        };
+
+        Expression::Window(function, args, Box::new(window))
+      }
+      Expression::Equal(left, right) => Expression::Equal(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::NotEqual(left, right) => Expression::NotEqual(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::GreaterThan(left, right) => Expression::GreaterThan(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::GreaterThanOrEqual(left, right) => Expression::GreaterThanOrEqual(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::LessThan(left, right) => Expression::LessThan(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::LessThanOrEqual(left, right) => Expression::LessThanOrEqual(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::Add(left, right) => Expression::Add(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::Subtract(left, right) => Expression::Subtract(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::Multiply(left, right) => Expression::Multiply(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::Divide(left, right) => Expression::Divide(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::And(left, right) => Expression::And(boxed(*left, f)?, boxed(*right, f)?),
+      Expression::Or(left, right) => Expression::Or(boxed(*left, f)?, boxed(*right, f)?),
+      expression @ (Expression::Identifier(_)
+      | Expression::Constant(_)
+      | Expression::DataType(_)
+      | Expression::Outer(..)
+      | Expression::Subquery(_)
+      | Expression::Exists(_)) => expression,
+    };
+
+    Ok(expression)
+  }
+
+  pub fn transform(self, f: &mut impl FnMut(Expression) -> Expression) -> Expression {
+    match self.try_transform(&mut |expression| Ok::<_, Infallible>(f(expression))) {
+      Ok(expression) => expression,
+      Err(never) => match never {},
+    }
+  }
+}
+
+fn boxed<E>(expression: Expression, f: &mut impl FnMut(Expression) -> Result<Expression, E>) -> Result<Box<Expression>, E> {
+  Ok(Box::new(f(expression)?))
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
   Int(i64),
   Float(f64),
+  Decimal(Decimal),
   Text(String),
   Boolean(bool),
+  Date(NaiveDate),
+  Timestamp(NaiveDateTime),
+  TimestampTz(DateTime<Utc>),
+  Interval(Interval),
+  Json(Json),
+  Bytes(Vec<u8>),
+  Uuid(Uuid),
   Null,
 }
 
+impl From<Value> for catalog::Value {
+  fn from(value: Value) -> Self {
+    match value {
+      Value::Int(i) => catalog::Value::Int(i),
+      Value::Float(f) => catalog::Value::Float(f),
+      Value::Decimal(d) => catalog::Value::Decimal(d),
+      Value::Text(s) => catalog::Value::Text(s),
+      Value::Boolean(b) => catalog::Value::Boolean(b),
+      Value::Date(d) => catalog::Value::Date(d),
+      Value::Timestamp(t) => catalog::Value::Timestamp(t),
+      Value::TimestampTz(t) => catalog::Value::TimestampTz(t),
+      Value::Interval(i) => catalog::Value::Interval(i),
+      Value::Json(j) => catalog::Value::Json(j),
+      Value::Bytes(b) => catalog::Value::Bytes(b),
+      Value::Uuid(u) => catalog::Value::Uuid(u),
+      Value::Null => catalog::Value::Null,
+    }
+  }
+}
+
+impl From<catalog::Value> for Value {
+  fn from(value: catalog::Value) -> Self {
+    match value {
+      catalog::Value::Int(i) => Value::Int(i),
+      catalog::Value::Float(f) => Value::Float(f),
+      catalog::Value::Decimal(d) => Value::Decimal(d),
+      catalog::Value::Text(s) => Value::Text(s),
+      catalog::Value::Boolean(b) => Value::Boolean(b),
+      catalog::Value::Date(d) => Value::Date(d),
+      catalog::Value::Timestamp(t) => Value::Timestamp(t),
+      catalog::Value::TimestampTz(t) => Value::TimestampTz(t),
+      catalog::Value::Interval(i) => Value::Interval(i),
+      catalog::Value::Json(j) => Value::Json(j),
+      catalog::Value::Bytes(b) => Value::Bytes(b),
+      catalog::Value::Uuid(u) => Value::Uuid(u),
+      catalog::Value::Null => Value::Null,
+    }
+  }
+}
+
+// INFO: Columns a query can reference, each one together with the table name or alias it comes from
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+  columns: Vec<(String, String)>,
+}
+
+impl Scope {
+  pub fn new(qualifier: &str, columns: Vec<String>) -> Self {
+    Self { columns: columns.into_iter().map(|column| (qualifier.to_string(), column)).collect() }
+  }
+
+  pub fn extend(&mut self, other: Scope) {
+    self.columns.extend(other.columns);
+  }
+
+  pub fn has_qualifier(&self, qualifier: &str) -> bool {
+    self.columns.iter().any(|(table, _)| table == qualifier)
+  }
+
+  fn matches(&self, identifier: &str) -> Vec<&(String, String)> {
+    self
+      .columns
+      .iter()
+      .filter(|(table, name)| match identifier.split_once('.') {
+        Some((qualifier, column)) => qualifier == table && column == name,
+        None => identifier == name,
+      })
+      .collect()
+  }
+
+  pub fn qualified_columns(&self) -> Vec<String> {
+    self.columns.iter().map(|(table, name)| format!("{}.{}", table, name)).collect()
+  }
+}
+
+// INFO: Query bound to a WITH name, every reference to the name reads the query again
+#[derive(Debug, Clone)]
+struct Cte {
+  name: String,
+  node: Node,
+  columns: Vec<String>,
+}
+
 #[derive(Debug)]
-pub struct Planner {}
+pub struct Planner<'a> {
+  catalog: &'a Catalog,
+  functions: &'a FunctionRegistry,
+  scopes: Vec<Scope>, // INFO: scopes of the queries that enclose the one being bound, the innermost is last
+  ctes: Vec<Cte>,     // INFO: CTEs visible to the query being bound, a later one hides an earlier one with the same name
+}
+
+impl<'a> Planner<'a> {
+  pub fn new(catalog: &'a Catalog, functions: &'a FunctionRegistry) -> Self {
+    Planner { catalog, functions, scopes: Vec::new(), ctes: Vec::new() }
+  }
+
+  pub fn build(&mut self, statement: ast::Statement) -> Result<Plan, PlannerError> {
+    let mut node = self.bind(statement)?;
+    self.cast_comparisons(&mut node, &[]);
 
-impl Planner {
-  pub fn new() -> Self {
-    Planner {}
+    Ok(Plan(node))
   }
 
-  pub fn build(&mut self, statement: ast::Statement) -> Plan {
-    Plan(self.bind(statement))
+  fn get_table(&self, table_name: &str) -> Result<&'a Table, PlannerError> {
+    self.catalog.get_table(table_name).ok_or_else(|| PlannerError::TableNotFound(table_name.to_string()))
   }
 
-  fn bind(&mut self, statement: ast::Statement) -> Node {
-    match statement {
-      ast::Statement::Begin { .. } | ast::Statement::Commit | ast::Statement::Rollback => {
-        panic!("Transaction not supported yet in this point in code")
+  fn bind(&mut self, statement: ast::Statement) -> Result<Node, PlannerError> {
+    let node = match statement {
+      ast::Statement::Begin | ast::Statement::Commit | ast::Statement::Rollback => {
+        return Err(PlannerError::TransactionsNotSupported);
       }
-      ast::Statement::CreateTable { name, columns } => {
-        let columns = columns
-          .into_iter()
-          .map(|column| {
-            let name = column.name.clone().parse_identifier();
-            (name, column_definition_to_column(column))
-          })
-          .collect();
+      ast::Statement::CreateTable { name, columns, constraints } => {
+        let name = name.parse_identifier();
+        let names: Vec<String> = columns.iter().map(|column| column.name.clone().parse_identifier()).collect();
 
+        let mut schema = Table::new(name);
+        let mut table_constraints = Vec::new();
+        for (i, column) in columns.into_iter().enumerate() {
+          if names[..i].contains(&names[i]) {
+            return Err(PlannerError::DuplicateColumn(names[i].clone()));
+          }
+
+          let (column, column_constraints) = self.bind_column(column)?;
+          schema.add_column(column);
+          table_constraints.extend(column_constraints);
+        }
+
+        table_constraints.extend(constraints);
+        self.add_constraints(&mut schema, table_constraints)?;
+
+        Node::CreateTable { schema }
+      }
+      ast::Statement::CreateIndex { name, table, unique, keys } => {
+        let (name, table) = (name.parse_identifier(), table.parse_identifier());
+        let mut schema = self.get_table(&table)?.clone();
+        if self.constraint_name_taken(&schema, &name) {
+          return Err(PlannerError::IndexAlreadyExists(name));
+        }
+
+        let index = self.bind_index(&schema, name, unique, keys)?;
+        schema.add_index(index);
+        let constraints = self.bind_row_constraints(&schema, None)?;
+
+        Node::AlterTable { table, schema, action: AlterAction::Constraints, constraints }
+      }
+      ast::Statement::DropIndex { name } => {
         let name = name.parse_identifier();
+        let Some(table) = self.catalog.index_table(&name) else {
+          return Err(PlannerError::IndexNotFound(name));
+        };
+        if table.get_constraint(&name).is_some() {
+          return Err(index_error(&name, format!("it belongs to constraint '{}', drop the constraint instead", name)));
+        }
 
-        Node::CreateTable { schema: Table::new(name, columns) }
+        let mut schema = table.clone();
+        schema.indexes.retain(|index| index.name != name);
+        let constraints = self.bind_row_constraints(&schema, None)?;
+
+        Node::AlterTable { table: schema.name.clone(), schema, action: AlterAction::Constraints, constraints }
       }
-      ast::Statement::DropTable { name } => Node::DropTable { table: name.parse_identifier() },
-      ast::Statement::AlterTable { name, operation } => {
+      ast::Statement::Describe { name } => {
         let table = name.parse_identifier();
-        let values = match operation {
-          AlterTableOperation::AddColumn(column) => {
-            let column_name = column.name.parse_identifier();
-            let column_type = data_type_to_primitive(column.data_type);
+        self.get_table(&table)?;
 
-            vec![(Expression::Identifier(column_name), Expression::DataType(column_type))]
-          }
-          AlterTableOperation::DropColumn(column) => {
-            vec![(Expression::Identifier(column.parse_identifier()), Expression::Identifier("".to_string()))]
-          }
-          AlterTableOperation::ModifyColumn(column) => {
-            let column_name = column.name.parse_identifier();
-            let column_type = data_type_to_primitive(column.data_type);
+        Node::Describe { table }
+      }
+      ast::Statement::DropTable { name } => {
+        let table = name.parse_identifier();
+        let references = self.catalog.referencing_constraints(&table);
+        if let Some((referencing, ..)) = references.into_iter().find(|(other, ..)| *other != table) {
+          return Err(PlannerError::ReferencedTable { table, referencing });
+        }
 
-            vec![(Expression::Identifier(column_name), Expression::DataType(column_type))]
-          }
-        };
+        Node::DropTable { table }
+      }
+      ast::Statement::AlterTable { name, operation } => {
+        let table = name.parse_identifier();
+        let mut schema = self.get_table(&table)?.clone();
 
-        Node::AlterTable { table, values }
+        let action = self.bind_alter_table(&mut schema, operation)?;
+        let constraints = self.bind_row_constraints(&schema, None)?;
+
+        Node::AlterTable { table, schema, action, constraints }
       }
-      ast::Statement::Insert { table, entries } => {
-        let values: Vec<(Expression, Expression)> =
-          entries.into_iter().map(|(name, value)| (expr_to_expression(name), expr_to_expression(value))).collect();
+      ast::Statement::Insert { table, columns, source, on_conflict, returning } => {
+        let schema = self.get_table(&table.name)?;
+        let returning = self.bind_returning(returning, schema)?;
+        let on_conflict = on_conflict.map(|on_conflict| self.bind_on_conflict(on_conflict, schema)).transpose()?;
 
-        Node::Insert { table: table.name, values }
+        self.bind_insert(table, columns, source, on_conflict, returning)?
       }
-      ast::Statement::Update { table, entries, where_clause } => {
-        let values: Vec<(Expression, Expression)> =
-          entries.into_iter().map(|(name, value)| (expr_to_expression(name), expr_to_expression(value))).collect();
+      ast::Statement::Update { table, entries, where_clause, returning } => {
+        let schema = self.get_table(&table.name)?;
+        let scope = table_scope(&table, schema);
 
-        let mut node = Node::Update { table: table.name, values };
+        let columns: Vec<String> = entries.iter().map(|(name, _)| name.clone().parse_identifier()).collect();
+        let mut values = Vec::with_capacity(entries.len());
+        for (name, value) in entries {
+          values.push((Expression::Identifier(name.parse_identifier()), self.bind_expression(value, &scope)?));
+        }
 
-        if let Some(condition) = where_clause.map(expr_to_expression) {
-          node = Node::Filter { source: Box::new(node), condition };
+        for column in &columns {
+          if schema.get_column(column).is_none() {
+            return Err(PlannerError::ColumnNotFound(column.clone()));
+          }
         }
 
-        node
+        let row: Vec<Expression> = values.iter().map(|(_, value)| value.clone()).collect();
+        self.check_insert_row(schema, &columns, &row, Some(&table.name))?;
+
+        let source = self.bind_dml_source(&table, where_clause)?;
+        let returning = self.bind_returning(returning, schema)?;
+
+        let constraints = self.bind_row_constraints(schema, None)?;
+
+        Node::Update { table: table.name, source: Box::new(source), values, returning, constraints }
+      }
+      ast::Statement::Delete { table, where_clause, returning } => {
+        let schema = self.get_table(&table.name)?;
+
+        let source = self.bind_dml_source(&table, where_clause)?;
+        let returning = self.bind_returning(returning, schema)?;
+
+        Node::Delete { table: table.name, source: Box::new(source), returning }
       }
-      ast::Statement::Delete { table, where_clause } => {
-        let mut node = Node::Delete { table: table.name };
+      select @ ast::Statement::Select { .. } => self.bind_select(select)?,
+      ast::Statement::SetOperation { operator, all, left, right, order_by, limit, offset } => {
+        if order_by.is_none() && limit.is_none() && offset.is_none() {
+          self.bind_set_operation(operator, all, *left, *right)?
+        } else {
+          // INFO: ORDER BY and LIMIT apply to the result of the set operation, as if it was selected from
+          let query = ast::Statement::SetOperation { operator, all, left, right, order_by: None, limit: None, offset: None };
+          let from = ast::FromItem::Subquery { query: Box::new(query), alias: SET_OPERATION_ALIAS.to_string() };
+          let select = vec![ast::Expression::Identifier("*".to_string())];
 
-        if let Some(condition) = where_clause.map(expr_to_expression) {
-          node = Node::Filter { source: Box::new(node), condition };
+          self.bind_select(ast::Statement::Select {
+            distinct: None,
+            from,
+            select,
+            where_clause: None,
+            group_by: None,
+            having: None,
+            order_by,
+            limit,
+            offset,
+          })?
         }
+      }
+      ast::Statement::With { recursive, ctes, body } => {
+        let visible = self.ctes.len();
+        let node = self.bind_with(recursive, ctes, *body);
+        self.ctes.truncate(visible);
 
-        node
+        node?
       }
-      ast::Statement::Select { from, select, where_clause, group_by, having, order_by, limit, offset } => {
-        let mut node = Node::Scan { table: from.name, alias: from.alias, filter: None };
+    };
 
-        if let Some(condition) = where_clause.map(expr_to_expression) {
-          node = Node::Filter { source: Box::new(node), condition };
-        } // add checks for joins and indexes
+    Ok(node)
+  }
 
-        if let Some(limit) = limit {
-          node = Node::Limit { source: Box::new(node), limit: expr_to_expression(limit) };
-        }
+  fn bind_select(&mut self, select: ast::Statement) -> Result<Node, PlannerError> {
+    let ast::Statement::Select { distinct, from, select, where_clause, group_by, having, order_by, limit, offset } = select
+    else {
+      unreachable!()
+    };
+    let (mut node, scope) = self.bind_from(from)?;
 
-        if let Some(offset) = offset {
-          node = Node::Offset { source: Box::new(node), offset: expr_to_expression(offset) };
-        }
+    if let Some(where_clause) = where_clause {
+      let condition = self.bind_expression(where_clause, &scope)?;
+      if condition.has_aggregate() {
+        return Err(PlannerError::AggregateNotAllowed("WHERE".to_string()));
+      }
 
-        node = Node::Projection { source: Box::new(node), columns: select.into_iter().map(expr_to_expression).collect() };
+      if condition.has_window() {
+        return Err(PlannerError::WindowNotAllowed("WHERE".to_string()));
+      }
 
-        node
+      node = decorrelate(node, &scope, condition);
+    } // add checks for joins and indexes
+
+    let mut columns = self.bind_expressions(expand_wildcard(select, &scope), &scope)?;
+    let mut having = having.map(|having| self.bind_expression(having, &scope)).transpose()?;
+    if having.as_ref().is_some_and(Expression::has_window) {
+      return Err(PlannerError::WindowNotAllowed("HAVING".to_string()));
+    }
+    let mut order = Vec::new();
+    for (expression, direction) in order_by.unwrap_or_default() {
+      order.push((self.bind_expression(expression, &scope)?, matches!(direction, ast::Order::Asc)));
+    }
+    let distinct_rows = matches!(distinct, Some(ast::Distinct::Rows));
+    let mut distinct_on = match distinct {
+      Some(ast::Distinct::On(expressions)) => Some(self.bind_expressions(expressions, &scope)?),
+      _ => None,
+    };
+
+    let ordering = order.iter().map(|(expression, _)| expression);
+    let aggregated = columns.iter().chain(having.iter()).chain(ordering).any(Expression::has_aggregate);
+
+    // INFO: above the GroupBy node only the group keys and the aggregate results can be read
+    if group_by.is_some() || having.is_some() || aggregated {
+      let keys = self.bind_expressions(group_by.unwrap_or_default(), &scope)?;
+      if keys.iter().any(Expression::has_aggregate) {
+        return Err(PlannerError::AggregateNotAllowed("GROUP BY".to_string()));
+      }
+
+      if keys.iter().any(Expression::has_window) {
+        return Err(PlannerError::WindowNotAllowed("GROUP BY".to_string()));
+      }
+
+      let mut aggregates = Vec::new();
+      for expression in columns.iter().chain(having.iter()).chain(order.iter().map(|(expression, _)| expression)) {
+        collect_aggregates(expression, &mut aggregates)?;
+      }
+
+      columns = columns
+        .into_iter()
+        .map(|column| {
+          let name = output_name(&column);
+          let column = grouped(column, &keys, &aggregates)?;
+
+          Ok(if output_name(&column) == name { column } else { Expression::Alias(Box::new(column), name) })
+        })
+        .collect::<Result<_, PlannerError>>()?;
+      having = having.map(|having| grouped(having, &keys, &aggregates)).transpose()?;
+      order = order
+        .into_iter()
+        .map(|(expression, ascending)| Ok((grouped(expression, &keys, &aggregates)?, ascending)))
+        .collect::<Result<_, PlannerError>>()?;
+      distinct_on =
+        distinct_on.map(|on| on.into_iter().map(|expression| grouped(expression, &keys, &aggregates)).collect()).transpose()?;
+
+      node = Node::GroupBy { source: Box::new(node), values: keys, aggregates };
+
+      if let Some(condition) = having {
+        node = Node::Filter { source: Box::new(node), condition };
       }
-      _ => unimplemented!(),
     }
-  }
-}
 
-fn expr_to_expression(expr: ast::Expression) -> Expression {
-  match expr {
-    ast::Expression::Identifier(name) => Expression::Identifier(name),
-    ast::Expression::Literal(literal) => Expression::Constant(literal_to_value(literal).unwrap()),
-    ast::Expression::BinaryExpression { left, operator, right } => binary_operator_to_expression(operator, *left, *right),
-    _ => unimplemented!(),
-  }
-}
+    // INFO: window functions see the grouped rows, above the Window node they are read as its columns
+    let mut windows = Vec::new();
+    for expression in columns.iter().chain(order.iter().map(|(expression, _)| expression)).chain(distinct_on.iter().flatten()) {
+      collect_windows(expression, &mut windows)?;
+    }
+
+    if !windows.is_empty() {
+      columns = columns
+        .into_iter()
+        .map(|column| {
+          let name = output_name(&column);
+          let column = windowed(column, &windows);
+
+          if output_name(&column) == name {
+            column
+          } else {
+            Expression::Alias(Box::new(column), name)
+          }
+        })
+        .collect();
+      order = order.into_iter().map(|(expression, ascending)| (windowed(expression, &windows), ascending)).collect();
+      distinct_on = distinct_on.map(|on| on.into_iter().map(|expression| windowed(expression, &windows)).collect());
 
-fn binary_operator_to_expression(operator: ast::Operator, left: ast::Expression, right: ast::Expression) -> Expression {
-  match operator {
-    ast::Operator::Equal => Expression::Equal(Box::new(expr_to_expression(left)), Box::new(expr_to_expression(right))),
-    ast::Operator::NotEqual => Expression::NotEqual(Box::new(expr_to_expression(left)), Box::new(expr_to_expression(right))),
-    ast::Operator::GreaterThan => {
-      Expression::GreaterThan(Box::new(expr_to_expression(left)), Box::new(expr_to_expression(right)))
+      node = Node::Window { source: Box::new(node), functions: windows };
     }
-    ast::Operator::GreaterThanOrEqual => {
-      Expression::GreaterThanOrEqual(Box::new(expr_to_expression(left)), Box::new(expr_to_expression(right)))
+
+    if !order.is_empty() {
+      node = Node::Sort { source: Box::new(node), order };
     }
-    ast::Operator::And => Expression::And(Box::new(expr_to_expression(left)), Box::new(expr_to_expression(right))),
-    ast::Operator::Or => Expression::Or(Box::new(expr_to_expression(left)), Box::new(expr_to_expression(right))),
-    _ => unimplemented!(),
-  }
-}
 
-fn data_type_to_primitive(data_type: ast::DataType) -> DataType {
-  match data_type {
-    ast::DataType::Int => crate::sql::catalog::DataType::Int,
-    ast::DataType::Text => crate::sql::catalog::DataType::Text,
-    ast::DataType::Boolean => crate::sql::catalog::DataType::Boolean,
-    _ => unimplemented!(),
-  }
-}
+    // INFO: DISTINCT ON keeps the first row of every group in the sort order, so it is applied before the projection
+    if let Some(on) = distinct_on {
+      node = Node::Distinct { source: Box::new(node), on: Some(on) };
+    }
 
-fn literal_to_value(literal: ast::Literal) -> Option<Value> {
-  match literal {
-    ast::Literal::Number(value) => Some(Value::Float(value)),
-    ast::Literal::String(value) => Some(Value::Text(value)),
-    ast::Literal::Boolean(value) => Some(Value::Boolean(value)),
-    ast::Literal::Null => Some(Value::Null),
-    _ => None,
-  }
-}
+    node = Node::Projection { source: Box::new(node), columns };
 
-pub fn column_definition_to_column(column_definition: ast::ColumnDefinition) -> Column {
-  let ast::ColumnDefinition { name, data_type, constraints } = column_definition;
+    if distinct_rows {
+      node = Node::Distinct { source: Box::new(node), on: None };
+    }
+
+    if let Some(offset) = offset {
+      node = Node::Offset { source: Box::new(node), offset: self.bind_expression(offset, &Scope::default())? };
+    }
+
+    if let Some(limit) = limit {
+      node = Node::Limit { source: Box::new(node), limit: self.bind_expression(limit, &Scope::default())? };
+    }
+
+    Ok(node)
+  }
+
+  fn bind_with(
+    &mut self,
+    recursive: bool,
+    ctes: Vec<ast::CommonTableExpression>,
+    body: ast::Statement,
+  ) -> Result<Node, PlannerError> {
+    for cte in ctes {
+      let cte = self.bind_cte(recursive, cte)?;
+      self.ctes.push(cte);
+    }
+
+    self.bind(body)
+  }
+
+  fn bind_cte(&mut self, recursive: bool, cte: ast::CommonTableExpression) -> Result<Cte, PlannerError> {
+    let name = cte.name;
+
+    let node = match *cte.query {
+      ast::Statement::SetOperation {
+        operator: ast::SetOperator::Union,
+        all,
+        left,
+        right,
+        order_by: None,
+        limit: None,
+        offset: None,
+      } if recursive => {
+        let mut anchor = self.bind(*left)?;
+
+        if let Some(columns) = &cte.columns {
+          rename_columns(&mut anchor, &name, columns)?;
+        }
+
+        // INFO: inside of the recursive query the name reads the rows of the previous round
+        let columns = output_columns(&anchor);
+        self.ctes.push(Cte { name: name.clone(), node: Node::WorkingTable { name: name.clone() }, columns });
+        let right = self.bind(*right);
+        self.ctes.pop();
+
+        let right = right?;
+        self.check_set_operation(SetOperator::Union, &anchor, &right)?;
+
+        if reads_working_table(&right, &name) {
+          Node::Recursive { name: name.clone(), anchor: Box::new(anchor), recursive: Box::new(right), distinct: !all }
+        } else {
+          Node::SetOperation { operator: SetOperator::Union, all, left: Box::new(anchor), right: Box::new(right) }
+        }
+      }
+      query => {
+        let mut node = self.bind(query)?;
+
+        if let Some(columns) = &cte.columns {
+          rename_columns(&mut node, &name, columns)?;
+        }
+
+        node
+      }
+    };
+
+    Ok(Cte { name, columns: output_columns(&node), node })
+  }
+
+  fn bind_set_operation(
+    &mut self,
+    operator: ast::SetOperator,
+    all: bool,
+    left: ast::Statement,
+    right: ast::Statement,
+  ) -> Result<Node, PlannerError> {
+    let operator = match operator {
+      ast::SetOperator::Union => SetOperator::Union,
+      ast::SetOperator::Intersect => SetOperator::Intersect,
+      ast::SetOperator::Except => SetOperator::Except,
+    };
+
+    let left = self.bind(left)?;
+    let right = self.bind(right)?;
+    self.check_set_operation(operator, &left, &right)?;
+
+    Ok(Node::SetOperation { operator, all, left: Box::new(left), right: Box::new(right) })
+  }
+
+  // INFO: Both queries have to return the same number of columns, and columns of known types have to be comparable
+  fn check_set_operation(&self, operator: SetOperator, left: &Node, right: &Node) -> Result<(), PlannerError> {
+    let (names, left_types, right_types) = (output_columns(left), self.output_types(left), self.output_types(right));
+
+    if left_types.len() != right_types.len() {
+      let (left, right) = (left_types.len(), right_types.len());
+      return Err(PlannerError::SetOperationColumnCount { operator: operator.to_string(), left, right });
+    }
+
+    for ((name, left), right) in names.into_iter().zip(left_types).zip(right_types) {
+      if let (Some(left), Some(right)) = (left, right) {
+        if !left.is_assignable_to(&right) && !right.is_assignable_to(&left) {
+          return Err(PlannerError::SetOperationTypeMismatch { operator: operator.to_string(), column: name, left, right });
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  // INFO: Types of the columns a query returns, None where the type is only known once the query runs
+  fn output_types(&self, node: &Node) -> Vec<Option<DataType>> {
+    match find_projection(node) {
+      Node::Projection { source, columns } => columns.iter().map(|column| self.expression_type(source, column)).collect(),
+      _ => Vec::new(),
+    }
+  }
+
+  fn expression_type(&self, source: &Node, expression: &Expression) -> Option<DataType> {
+    match expression {
+      Expression::Alias(expression, _) => self.expression_type(source, expression),
+      Expression::Identifier(name) => match name.split_once('.') {
+        Some((qualifier, column)) => self.column_type(source, qualifier, column),
+        None => self.computed_column_type(source, name),
+      },
+      Expression::Aggregate(AggregateFunction::Min | AggregateFunction::Max, args, _) => {
+        self.expression_type(source, args.first()?)
+      }
+      Expression::Function(function, args) => {
+        function.signature.return_type(&args.iter().map(|arg| self.expression_type(source, arg)).collect::<Vec<_>>())
+      }
+      expression => static_type(expression),
+    }
+  }
+
+  fn column_type(&self, node: &Node, qualifier: &str, column: &str) -> Option<DataType> {
+    match node {
+      Node::Scan { table, alias, .. } if alias.as_ref().unwrap_or(table) == qualifier => {
+        self.catalog.get_column(table, column).map(|column| column.data_type.value_type())
+      }
+      Node::Subquery { source, alias } if alias == qualifier => {
+        let position = output_columns(source).iter().position(|name| name == column)?;
+        self.output_types(source).into_iter().nth(position).flatten()
+      }
+      node => node.tranverse().into_iter().skip(1).find_map(|child| self.column_type(child, qualifier, column)),
+    }
+  }
+
+  // INFO: Type of a group key, aggregate result or window function result read above its GroupBy or Window node
+  fn computed_column_type(&self, node: &Node, name: &str) -> Option<DataType> {
+    let computed = |expressions: &[Expression], column: fn(usize) -> String| {
+      (0..expressions.len()).position(|index| column(index) == name).map(|index| expressions[index].clone())
+    };
+
+    match node {
+      Node::GroupBy { source, values, aggregates } => {
+        let expression = computed(values, group_column).or_else(|| computed(aggregates, aggregate_column))?;
+        self.expression_type(source, &expression)
+      }
+      Node::Window { source, functions } => match computed(functions, window_column) {
+        Some(expression) => self.expression_type(source, &expression),
+        None => self.computed_column_type(source, name),
+      },
+      node => node.tranverse().into_iter().skip(1).find_map(|child| self.computed_column_type(child, name)),
+    }
+  }
+
+  // INFO: A string compared with a value of a type that is written as text, like a date, is cast to the type of the
+  // value, so once the query runs values are only compared with values of their own type
+  fn cast_comparisons(&self, node: &mut Node, outer: &[Node]) {
+    let context = node.clone();
+    let enclosing: Vec<Node> = outer.iter().cloned().chain(std::iter::once(context.clone())).collect();
+
+    for expression in node.expressions_mut() {
+      let bound = std::mem::replace(expression, Expression::Constant(Value::Null));
+      *expression = self.cast_operands(bound, &|expression| self.comparison_type(&context, outer, expression), &enclosing);
+    }
+
+    for child in node.children_mut() {
+      self.cast_comparisons(child, outer);
+    }
+  }
+
+  // INFO: Rewrites the comparisons of the expression, `enclosing` are the queries its subqueries are nested in
+  fn cast_operands(
+    &self,
+    expression: Expression,
+    type_of: &dyn Fn(&Expression) -> Option<DataType>,
+    enclosing: &[Node],
+  ) -> Expression {
+    let pair = |left: Box<Expression>, right: Box<Expression>| {
+      let (left_type, right_type) = (type_of(&left), type_of(&right));
+
+      match (text_cast(left_type.as_ref(), right_type.as_ref()), text_cast(right_type.as_ref(), left_type.as_ref())) {
+        (Some(data_type), _) => (Box::new(Expression::Cast(left, data_type)), right),
+        (_, Some(data_type)) => (left, Box::new(Expression::Cast(right, data_type))),
+        _ => (left, right),
+      }
+    };
+    let compared = |left, right, comparison: fn(Box<Expression>, Box<Expression>) -> Expression| {
+      let (left, right) = pair(left, right);
+      comparison(left, right)
+    };
+
+    expression.transform(&mut |expression| match expression {
+      Expression::Equal(left, right) => compared(left, right, Expression::Equal),
+      Expression::NotEqual(left, right) => compared(left, right, Expression::NotEqual),
+      Expression::GreaterThan(left, right) => compared(left, right, Expression::GreaterThan),
+      Expression::GreaterThanOrEqual(left, right) => compared(left, right, Expression::GreaterThanOrEqual),
+      Expression::LessThan(left, right) => compared(left, right, Expression::LessThan),
+      Expression::LessThanOrEqual(left, right) => compared(left, right, Expression::LessThanOrEqual),
+      Expression::NullIf(left, right) => compared(left, right, Expression::NullIf),
+      Expression::Between(expression, low, high) => {
+        let (expression, low) = pair(expression, low);
+        let (expression, high) = pair(expression, high);
+        Expression::Between(expression, low, high)
+      }
+      Expression::InList(mut expression, list) => {
+        let list = list
+          .into_iter()
+          .map(|item| {
+            let (left, item) = pair(expression.clone(), Box::new(item));
+            expression = left;
+            *item
+          })
+          .collect();
+
+        Expression::InList(expression, list)
+      }
+      Expression::Subquery(mut node) => {
+        self.cast_comparisons(&mut node, enclosing);
+        Expression::Subquery(node)
+      }
+      Expression::Exists(mut node) => {
+        self.cast_comparisons(&mut node, enclosing);
+        Expression::Exists(node)
+      }
+      Expression::InSubquery(expression, mut node) => {
+        self.cast_comparisons(&mut node, enclosing);
+        let (left, right) = (type_of(&expression), self.output_types(&node).into_iter().next().flatten());
+
+        match (text_cast(left.as_ref(), right.as_ref()), text_cast(right.as_ref(), left.as_ref())) {
+          (Some(data_type), _) => Expression::InSubquery(Box::new(Expression::Cast(expression, data_type)), node),
+          (_, Some(data_type)) => Expression::InSubquery(expression, Box::new(cast_output(*node, data_type))),
+          _ => Expression::InSubquery(expression, node),
+        }
+      }
+      expression => expression,
+    })
+  }
+
+  fn comparison_type(&self, source: &Node, outer: &[Node], expression: &Expression) -> Option<DataType> {
+    match expression {
+      Expression::Outer(name, level) => {
+        let (qualifier, column) = name.split_once('.')?;
+        self.column_type(outer.get(outer.len().checked_sub(*level)?)?, qualifier, column)
+      }
+      Expression::Subquery(node) => self.output_types(node).into_iter().next().flatten(),
+      expression => self.expression_type(source, expression),
+    }
+  }
+
+  fn find_cte(&self, name: &str) -> Option<&Cte> {
+    self.ctes.iter().rev().find(|cte| cte.name == name)
+  }
+
+  fn bind_from(&mut self, from: ast::FromItem) -> Result<(Node, Scope), PlannerError> {
+    match from {
+      ast::FromItem::Table(table) if self.find_cte(&table.name).is_some() => {
+        let cte = self.find_cte(&table.name).cloned().unwrap();
+        let alias = table.alias.unwrap_or(table.name);
+        let scope = Scope::new(&alias, cte.columns);
+
+        Ok((Node::Subquery { source: Box::new(cte.node), alias }, scope))
+      }
+      ast::FromItem::Table(table) => {
+        let schema = self.get_table(&table.name)?;
+        let scope = table_scope(&table, schema);
+
+        Ok((Node::Scan { table: table.name, alias: table.alias, filter: None }, scope))
+      }
+      ast::FromItem::Subquery { query, alias } => {
+        let node = self.bind(*query)?;
+        let scope = Scope::new(&alias, output_columns(&node));
+
+        Ok((Node::Subquery { source: Box::new(node), alias }, scope))
+      }
+      ast::FromItem::Join { left, right } => {
+        let (left, mut scope) = self.bind_from(*left)?;
+        let (right, right_scope) = self.bind_from(*right)?;
+        scope.extend(right_scope);
+
+        let condition = Expression::Constant(Value::Boolean(true));
+        Ok((Node::NestedLoopJoin { left: Box::new(left), right: Box::new(right), condition, join_type: JoinType::Inner }, scope))
+      }
+    }
+  }
+
+  fn bind_expressions(&mut self, expressions: Vec<ast::Expression>, scope: &Scope) -> Result<Vec<Expression>, PlannerError> {
+    expressions.into_iter().map(|expression| self.bind_expression(expression, scope)).collect()
+  }
+
+  // INFO: Converts the expression and qualifies its identifiers,
+  // the ones that only exist in an enclosing query become outer references
+  fn bind_expression(&mut self, expression: ast::Expression, scope: &Scope) -> Result<Expression, PlannerError> {
+    let expression = match expression {
+      ast::Expression::Identifier(name) => self.resolve_identifier(name, scope)?,
+      ast::Expression::Literal(literal) => Expression::Constant(literal_to_value(literal)),
+      ast::Expression::BinaryExpression {
+        left,
+        operator: operator @ (ast::Operator::JsonGet | ast::Operator::JsonGetText | ast::Operator::JsonPath),
+        right,
+      } => {
+        let name = match operator {
+          ast::Operator::JsonGet => JSON_EXTRACT_PATH,
+          ast::Operator::JsonGetText => JSON_EXTRACT_PATH_TEXT,
+          _ => JSON_EXTRACT,
+        };
+
+        let function = ast::Expression::Function { name: name.to_string(), args: vec![*left, *right], distinct: false };
+        return self.bind_expression(function, scope);
+      }
+      ast::Expression::BinaryExpression { left, operator, right } => {
+        let left = self.bind_expression(*left, scope)?;
+        let right = self.bind_expression(*right, scope)?;
+
+        binary_operator_to_expression(operator, left, right)?
+      }
+      ast::Expression::UnaryExpression { operator: ast::Operator::Not, expression } => {
+        Expression::Not(Box::new(self.bind_expression(*expression, scope)?))
+      }
+      ast::Expression::UnaryExpression { expression, .. } => {
+        Expression::Negate(Box::new(self.bind_expression(*expression, scope)?))
+      }
+      ast::Expression::Alias { expression, alias } => {
+        Expression::Alias(Box::new(self.bind_expression(*expression, scope)?), alias)
+      }
+      ast::Expression::Function { name, args, .. } if self.functions.aggregate(&name).is_none() => {
+        let function = self.functions.get(&name).ok_or_else(|| PlannerError::FunctionNotFound(name.clone()))?;
+        let args = self.bind_expressions(args, scope)?;
+
+        // INFO: Only argument types known without the source are checked here, the rest is checked when the function is called
+        function.signature.check(&function.name, &args.iter().map(static_type).collect::<Vec<_>>())?;
+        Expression::Function(function.clone(), args)
+      }
+      ast::Expression::Function { name, args, distinct } => {
+        let function = self.functions.aggregate(&name).ok_or_else(|| PlannerError::FunctionNotFound(name.clone()))?;
+        let args = match args.as_slice() {
+          [ast::Expression::Identifier(star)] if star == "*" => Vec::new(),
+          _ => self.bind_expressions(args, scope)?,
+        };
+
+        if let AggregateFunction::User(aggregate) = &function {
+          aggregate.signature.check(&aggregate.name, &args.iter().map(static_type).collect::<Vec<_>>())?;
+          return Ok(Expression::Aggregate(function, args, distinct));
+        }
+
+        // INFO: COUNT(*) is the only built-in aggregate without an argument
+        let star = function == AggregateFunction::Count && args.is_empty() && !distinct;
+        if args.len() != 1 && !star {
+          return Err(PlannerError::FunctionArgumentCount { function: name, expected: "1".to_string(), found: args.len() });
+        }
+
+        Expression::Aggregate(function, args, distinct)
+      }
+      ast::Expression::Window { function, over } => {
+        let ast::Expression::Function { name, args, distinct } = *function else { unreachable!() };
+
+        let (function, args) = match WindowFunction::from_name(&name) {
+          Some(function) => (function, self.bind_expressions(args, scope)?),
+          None if self.functions.aggregate(&name).is_some() => {
+            match self.bind_expression(ast::Expression::Function { name: name.clone(), args, distinct }, scope)? {
+              Expression::Aggregate(function, args, distinct) => (WindowFunction::Aggregate(function, distinct), args),
+              _ => unreachable!(),
+            }
+          }
+          None => return Err(PlannerError::NotWindowFunction(name)),
+        };
+
+        let (min, max) = function.arity();
+        if !matches!(function, WindowFunction::Aggregate(..)) && (args.len() < min || args.len() > max) {
+          let expected = if min == max { min.to_string() } else { format!("{} to {}", min, max) };
+          return Err(PlannerError::FunctionArgumentCount { function: name, expected, found: args.len() });
+        }
+
+        Expression::Window(function, args, Box::new(self.bind_window(*over, scope)?))
+      }
+      ast::Expression::IsNull { expression, negated } => {
+        negate(Expression::IsNull(Box::new(self.bind_expression(*expression, scope)?)), negated)
+      }
+      ast::Expression::InList { expression, list, negated } => {
+        let expression = self.bind_expression(*expression, scope)?;
+        negate(Expression::InList(Box::new(expression), self.bind_expressions(list, scope)?), negated)
+      }
+      ast::Expression::Between { expression, low, high, negated } => {
+        let expression = Box::new(self.bind_expression(*expression, scope)?);
+        let (low, high) = (Box::new(self.bind_expression(*low, scope)?), Box::new(self.bind_expression(*high, scope)?));
+
+        negate(Expression::Between(expression, low, high), negated)
+      }
+      ast::Expression::Like { expression, pattern, escape, case_insensitive, negated } => {
+        let expression = Box::new(self.bind_expression(*expression, scope)?);
+        let pattern = Box::new(self.bind_expression(*pattern, scope)?);
+        let like = match case_insensitive {
+          true => Expression::ILike(expression, pattern, escape),
+          false => Expression::Like(expression, pattern, escape),
+        };
+
+        negate(like, negated)
+      }
+      ast::Expression::Case { operand, branches, default } => {
+        let operand = operand.map(|operand| self.bind_expression(*operand, scope)).transpose()?;
+
+        let mut bound = Vec::with_capacity(branches.len());
+        for (condition, result) in branches {
+          let condition = self.bind_expression(condition, scope)?;
+          let condition = match &operand {
+            Some(operand) => Expression::Equal(Box::new(operand.clone()), Box::new(condition)),
+            None => condition,
+          };
+
+          bound.push((condition, self.bind_expression(result, scope)?));
+        }
+
+        let default = default.map(|default| self.bind_expression(*default, scope)).transpose()?;
+        Expression::Case(bound, default.map(Box::new))
+      }
+      ast::Expression::Cast { expression, data_type } => {
+        Expression::Cast(Box::new(self.bind_expression(*expression, scope)?), data_type_to_primitive(data_type))
+      }
+      ast::Expression::Coalesce(expressions) => Expression::Coalesce(self.bind_expressions(expressions, scope)?),
+      ast::Expression::NullIf { left, right } => {
+        Expression::NullIf(Box::new(self.bind_expression(*left, scope)?), Box::new(self.bind_expression(*right, scope)?))
+      }
+      ast::Expression::Subquery(query) => Expression::Subquery(Box::new(self.bind_subquery(*query, scope, true)?)),
+      ast::Expression::Exists(query) => Expression::Exists(Box::new(self.bind_subquery(*query, scope, false)?)),
+      ast::Expression::InSubquery { expression, subquery, negated } => {
+        let expression = self.bind_expression(*expression, scope)?;
+        let subquery = self.bind_subquery(*subquery, scope, true)?;
+        negate(Expression::InSubquery(Box::new(expression), Box::new(subquery)), negated)
+      }
+    };
+
+    Ok(expression)
+  }
+
+  // INFO: Without a frame the window ends at the last peer of the current row, or at the end of the partition without ORDER BY
+  fn bind_window(&mut self, window: ast::Window, scope: &Scope) -> Result<Window, PlannerError> {
+    let partition_by = self.bind_expressions(window.partition_by, scope)?;
+    let mut order_by = Vec::new();
+    for (expression, direction) in window.order_by {
+      order_by.push((self.bind_expression(expression, scope)?, matches!(direction, ast::Order::Asc)));
+    }
+
+    let end = if order_by.is_empty() { ast::FrameBound::UnboundedFollowing } else { ast::FrameBound::CurrentRow };
+    let frame =
+      window.frame.unwrap_or(ast::WindowFrame { units: ast::FrameUnits::Range, start: ast::FrameBound::UnboundedPreceding, end });
+
+    let position = |bound: ast::FrameBound| match bound {
+      ast::FrameBound::UnboundedPreceding => 0,
+      ast::FrameBound::Preceding(_) => 1,
+      ast::FrameBound::CurrentRow => 2,
+      ast::FrameBound::Following(_) => 3,
+      ast::FrameBound::UnboundedFollowing => 4,
+    };
+
+    if frame.start == ast::FrameBound::UnboundedFollowing || frame.end == ast::FrameBound::UnboundedPreceding {
+      let reason = "it can't start at UNBOUNDED FOLLOWING or end at UNBOUNDED PRECEDING";
+      return Err(PlannerError::InvalidWindowFrame(reason.to_string()));
+    }
+
+    if position(frame.start) > position(frame.end) {
+      return Err(PlannerError::InvalidWindowFrame("it can't end before the row it starts at".to_string()));
+    }
+
+    let offset =
+      [frame.start, frame.end].iter().any(|bound| matches!(bound, ast::FrameBound::Preceding(_) | ast::FrameBound::Following(_)));
+    if frame.units == ast::FrameUnits::Range && offset && order_by.len() != 1 {
+      return Err(PlannerError::InvalidWindowFrame("RANGE with an offset needs exactly one ORDER BY column".to_string()));
+    }
+
+    Ok(Window { partition_by, order_by, frame })
+  }
+
+  // INFO: The innermost scope that has the column wins, a name matching several columns of that scope is ambiguous
+  fn resolve_identifier(&self, name: String, scope: &Scope) -> Result<Expression, PlannerError> {
+    let scopes = std::iter::once(scope).chain(self.scopes.iter().rev());
+
+    for (level, current) in scopes.enumerate() {
+      match current.matches(&name).as_slice() {
+        [] => continue,
+        [(table, column)] if level == 0 => return Ok(Expression::Identifier(format!("{}.{}", table, column))),
+        [(table, column)] => return Ok(Expression::Outer(format!("{}.{}", table, column), level)),
+        _ => return Err(PlannerError::AmbiguousColumn(name)),
+      }
+    }
+
+    Err(PlannerError::ColumnNotFound(name))
+  }
+
+  fn bind_subquery(&mut self, query: ast::Statement, scope: &Scope, single_column: bool) -> Result<Node, PlannerError> {
+    self.scopes.push(scope.clone());
+    let node = self.bind(query);
+    self.scopes.pop();
+
+    let node = node?;
+    let columns = output_columns(&node).len();
+
+    if single_column && columns != 1 {
+      return Err(PlannerError::SubqueryColumnCount(columns));
+    }
+
+    Ok(node)
+  }
+
+  // INFO: RETURNING is checked up front so a bad column doesn't fail the statement after the rows were written
+  fn bind_returning(
+    &mut self,
+    returning: Option<Vec<ast::Expression>>,
+    schema: &Table,
+  ) -> Result<Option<Vec<Expression>>, PlannerError> {
+    let scope = Scope::new(&schema.name, schema.column_names());
+    let returning = match returning {
+      Some(returning) => self.bind_expressions(expand_wildcard(returning, &scope), &scope)?,
+      None => return Ok(None),
+    };
+
+    for expression in &returning {
+      for identifier in expression.identifiers() {
+        if schema.get_column(unqualified(identifier)).is_none() {
+          return Err(PlannerError::ColumnNotFound(identifier.clone()));
+        }
+      }
+    }
+
+    Ok(Some(returning))
+  }
+
+  // INFO: Finds the unique indexes the conflict columns point to and qualifies the DO UPDATE identifiers
+  fn bind_on_conflict(&mut self, on_conflict: ast::OnConflict, schema: &Table) -> Result<OnConflict, PlannerError> {
+    let columns: Vec<String> = on_conflict.columns.into_iter().map(|column| column.parse_identifier()).collect();
+
+    let indexes = if columns.is_empty() {
+      schema.unique_indexes().map(|index| index.name.clone()).collect()
+    } else {
+      match schema.find_unique_index(&columns) {
+        Some(index) => vec![index.name.clone()],
+        None => return Err(PlannerError::NoConflictIndex(columns)),
+      }
+    };
+
+    let action = match on_conflict.action {
+      ast::ConflictAction::DoNothing => ConflictAction::DoNothing,
+      ast::ConflictAction::DoUpdate { entries, where_clause } => {
+        let mut values = Vec::with_capacity(entries.len());
+        for (column, value) in entries {
+          let column = column.parse_identifier();
+
+          if schema.get_column(&column).is_none() {
+            return Err(PlannerError::ColumnNotFound(column));
+          }
+
+          let value = self.bind_conflict_expression(value, schema)?;
+          self.check_insert_row(schema, std::slice::from_ref(&column), std::slice::from_ref(&value), None)?;
+
+          values.push((column, value));
+        }
+
+        let condition = match where_clause {
+          Some(where_clause) => Some(self.bind_conflict_expression(where_clause, schema)?),
+          None => None,
+        };
+
+        ConflictAction::DoUpdate { values, condition }
+      }
+    };
+
+    Ok(OnConflict { indexes, action })
+  }
+
+  // INFO: Column names that exist in both the target table and `excluded` mean the target table
+  fn bind_conflict_expression(&mut self, expression: ast::Expression, schema: &Table) -> Result<Expression, PlannerError> {
+    let mut scope = Scope::new(&schema.name, schema.column_names());
+    scope.extend(Scope::new(EXCLUDED_TABLE, schema.column_names()));
+
+    let expression = self.bind_expression(expression, &scope)?.transform(&mut |expression| match expression {
+      Expression::Identifier(name) if !name.contains('.') => Expression::Identifier(format!("{}.{}", schema.name, name)),
+      expression => expression,
+    });
+
+    for identifier in expression.identifiers() {
+      let (table, column) = identifier.split_once('.').unwrap_or_default();
+
+      if table != schema.name && table != EXCLUDED_TABLE {
+        return Err(PlannerError::TableNotFound(table.to_string()));
+      }
+
+      if schema.get_column(column).is_none() {
+        return Err(PlannerError::ColumnNotFound(identifier.clone()));
+      }
+    }
+
+    Ok(self.cast_operands(expression, &|expression| row_type(schema, expression), &[]))
+  }
+
+  // INFO: Rows an UPDATE or DELETE works on
+  fn bind_dml_source(&mut self, table: &ast::Table, where_clause: Option<ast::Expression>) -> Result<Node, PlannerError> {
+    let mut node = Node::Scan { table: table.name.clone(), alias: table.alias.clone(), filter: None };
+
+    if let Some(where_clause) = where_clause {
+      let scope = table_scope(table, self.get_table(&table.name)?);
+      node = Node::Filter { source: Box::new(node), condition: self.bind_expression(where_clause, &scope)? };
+    }
+
+    Ok(node)
+  }
+
+  // INFO: Resolves the target columns and checks that every row of the source fits them
+  fn bind_insert(
+    &mut self,
+    table: ast::Table,
+    columns: Option<Vec<ast::Expression>>,
+    source: InsertSource,
+    on_conflict: Option<OnConflict>,
+    returning: Option<Vec<Expression>>,
+  ) -> Result<Node, PlannerError> {
+    let schema = self.get_table(&table.name)?;
+
+    let columns = match columns {
+      Some(columns) => columns.into_iter().map(|column| column.parse_identifier()).collect(),
+      None => schema.column_names(),
+    };
+
+    for (i, column) in columns.iter().enumerate() {
+      if schema.get_column(column).is_none() {
+        return Err(PlannerError::ColumnNotFound(column.clone()));
+      }
+
+      if columns[..i].contains(column) {
+        return Err(PlannerError::DuplicateColumn(column.clone()));
+      }
+    }
+
+    let source = match source {
+      InsertSource::Values(rows) => {
+        let rows = rows.into_iter().map(|row| self.bind_expressions(row, &Scope::default())).collect::<Result<Vec<_>, _>>()?;
+
+        for row in &rows {
+          self.check_insert_row(schema, &columns, row, None)?;
+        }
+
+        Node::Values { rows }
+      }
+      InsertSource::Select(statement) => {
+        let node = self.bind(*statement)?;
+
+        if let Node::Projection { columns: projection, .. } = find_projection(&node) {
+          self.check_insert_row(schema, &columns, projection, find_scan_table(&node))?;
+        }
+
+        node
+      }
+    };
+
+    let constraints = self.bind_row_constraints(schema, Some(&columns))?;
+
+    Ok(Node::Insert { table: table.name, columns, source: Box::new(source), on_conflict, returning, constraints })
+  }
+
+  // INFO: NOT NULL and DEFAULT stay on the column, every other constraint becomes a table constraint on the column.
+  // A DEFAULT is validated here and stored as SQL
+  fn bind_column(&mut self, definition: ast::ColumnDefinition) -> Result<(Column, Vec<ast::TableConstraint>), PlannerError> {
+    let name = definition.name.clone().parse_identifier();
+    let data_type = data_type_to_primitive(definition.data_type);
+    let mut column = Column { id: 0, name, data_type, nullable: true, default: None };
+    let mut constraints = Vec::new();
+
+    for constraint in definition.constraints {
+      let kind = match constraint {
+        ast::ColumnConstraint::PrimaryKey => ast::TableConstraintKind::PrimaryKey(vec![definition.name.clone()]),
+        ast::ColumnConstraint::Unique => ast::TableConstraintKind::Unique(vec![definition.name.clone()]),
+        ast::ColumnConstraint::Check(condition) => ast::TableConstraintKind::Check(condition),
+        ast::ColumnConstraint::ForeignKey { table, child_column, parent_column, actions } => {
+          if child_column.clone().parse_identifier() != column.name {
+            let reason = "it has to be on the column it is defined with";
+            return Err(constraint_error(&format!("FOREIGN KEY ({})", child_column), reason));
+          }
+
+          let (columns, referenced_columns) = (vec![child_column], vec![parent_column]);
+          ast::TableConstraintKind::ForeignKey { columns, table, referenced_columns, actions }
+        }
+        ast::ColumnConstraint::NotNull => {
+          column.nullable = false;
+          continue;
+        }
+        ast::ColumnConstraint::Default(value) => {
+          if column.default.is_some() {
+            return Err(default_error(&column.name, "it is specified more than once"));
+          }
+
+          column.default = Some(self.bind_default(&column, value)?);
+          continue;
+        }
+      };
+
+      constraints.push(ast::TableConstraint { name: None, kind });
+    }
+
+    Ok((column, constraints))
+  }
+
+  fn bind_default(&mut self, column: &Column, value: ast::Expression) -> Result<String, PlannerError> {
+    let text = value.to_string();
+    let value = self.bind_expression(value, &Scope::default())?;
+
+    if let Some(reason) = row_expression_error(&value) {
+      return Err(default_error(&column.name, reason));
+    }
+    if !value.identifiers().is_empty() {
+      return Err(default_error(&column.name, "it can't reference columns"));
+    }
+
+    match static_type(&value) {
+      Some(found) if !found.is_assignable_to(&column.data_type) => {
+        Err(PlannerError::TypeMismatch { column: column.name.clone(), expected: column.data_type.clone(), found })
+      }
+      _ => Ok(text),
+    }
+  }
+
+  // INFO: Changes the definition of the table, the returned action tells the executor how the stored rows follow
+  fn bind_alter_table(&mut self, schema: &mut Table, operation: AlterTableOperation) -> Result<AlterAction, PlannerError> {
+    let action = match operation {
+      AlterTableOperation::AddColumn(definition) => {
+        let name = definition.name.clone().parse_identifier();
+        if schema.get_column(&name).is_some() {
+          return Err(PlannerError::ColumnAlreadyExists { table: schema.name.clone(), column: name });
+        }
+
+        let (column, constraints) = self.bind_column(definition)?;
+        let default =
+          column.default.as_ref().map(|default| self.bind_stored_expression(default, &Scope::default())).transpose()?;
+        schema.add_column(column);
+        self.add_constraints(schema, constraints)?;
+
+        AlterAction::AddColumn { column: name, default }
+      }
+      AlterTableOperation::DropColumn(column) => {
+        let column = column.parse_identifier();
+        if schema.get_column(&column).is_none() {
+          return Err(PlannerError::ColumnNotFound(column));
+        }
+        if schema.columns.len() == 1 {
+          return Err(alter_error(schema, format!("column '{}' is its only column", column)));
+        }
+
+        // INFO: constraints on the column go with it, so do foreign keys of the table that reference it
+        let dropped: Vec<String> = schema
+          .constraints
+          .iter()
+          .filter(|constraint| match &constraint.kind {
+            ConstraintKind::ForeignKey(reference) if reference.table_name == schema.name => {
+              reference.columns.contains(&column) || reference.referenced_columns.contains(&column)
+            }
+            _ => constraint.columns().contains(&column),
+          })
+          .map(|constraint| constraint.name.clone())
+          .collect();
+
+        for constraint in &dropped {
+          self.check_not_referenced(schema, constraint, &dropped)?;
+          schema.remove_constraint(constraint);
+        }
+        schema.indexes.retain(|index| !index.columns.contains(&column));
+        schema.remove_column(&column);
+
+        AlterAction::DropColumn(column)
+      }
+      AlterTableOperation::ModifyColumn(definition) => {
+        let name = definition.name.clone().parse_identifier();
+        let Some(previous) = schema.get_column(&name).cloned() else {
+          return Err(PlannerError::ColumnNotFound(name));
+        };
+
+        let (mut column, constraints) = self.bind_column(definition)?;
+        if column.data_type.value_type() != previous.data_type.value_type() {
+          let referenced = self
+            .catalog
+            .referencing_constraints(&schema.name)
+            .into_iter()
+            .find_map(|(_, constraint, reference)| reference.referenced_columns.contains(&name).then_some(constraint));
+          let foreign_key =
+            schema.foreign_keys().find(|(_, reference)| reference.columns.contains(&name)).map(|(name, _)| name.clone());
+
+          if let Some(constraint) = foreign_key.or(referenced) {
+            return Err(alter_error(schema, format!("the type of column '{}' is used by foreign key '{}'", name, constraint)));
+          }
+          if let Some(index) = schema.indexes.iter().find(|index| index.path.is_some() && index.columns.contains(&name)) {
+            return Err(alter_error(schema, format!("column '{}' has to stay JSON for index '{}'", name, index.name)));
+          }
+        }
+        if schema.primary_key().is_some_and(|key| key.columns().contains(&name)) {
+          column.nullable = false;
+        }
+
+        schema.replace_column(column);
+        self.add_constraints(schema, constraints)?;
+
+        AlterAction::ModifyColumn(name)
+      }
+      AlterTableOperation::RenameTable(name) => {
+        let name = name.parse_identifier();
+        if self.catalog.get_table(&name).is_some() {
+          return Err(PlannerError::TableAlreadyExists(name));
+        }
+
+        let previous = std::mem::replace(&mut schema.name, name.clone());
+        for constraint in &mut schema.constraints {
+          match &mut constraint.kind {
+            ConstraintKind::ForeignKey(reference) if reference.table_name == previous => reference.table_name = name.clone(),
+            _ => {}
+          }
+        }
+
+        AlterAction::RenameTable
+      }
+      AlterTableOperation::RenameColumn { column, name } => {
+        let (from, to) = (column.parse_identifier(), name.parse_identifier());
+        if schema.get_column(&from).is_none() {
+          return Err(PlannerError::ColumnNotFound(from));
+        }
+        if schema.get_column(&to).is_some() {
+          return Err(PlannerError::ColumnAlreadyExists { table: schema.name.clone(), column: to });
+        }
+
+        schema.rename_column(&from, &to);
+        for constraint in &mut schema.constraints {
+          if let ConstraintKind::Check { condition, .. } = &mut constraint.kind {
+            let mut expression = Parser::new(condition).parse_standalone_expression().map_err(|error| {
+              PlannerError::InvalidStoredExpression { expression: condition.clone(), reason: error.to_string() }
+            })?;
+
+            expression.rename_column(&from, &to);
+            *condition = expression.to_string();
+          }
+        }
+
+        AlterAction::RenameColumn { from, to }
+      }
+      AlterTableOperation::AlterColumn { column, action } => {
+        let name = column.parse_identifier();
+        let in_primary_key = schema.primary_key().is_some_and(|key| key.columns().contains(&name));
+        let Some(column) = schema.get_mut_column(&name) else {
+          return Err(PlannerError::ColumnNotFound(name));
+        };
+
+        match action {
+          ast::AlterColumnAction::SetDefault(value) => column.default = Some(self.bind_default(column, value)?),
+          ast::AlterColumnAction::DropDefault => column.default = None,
+          ast::AlterColumnAction::SetNotNull => column.nullable = false,
+          ast::AlterColumnAction::DropNotNull if in_primary_key => {
+            return Err(alter_error(schema, format!("column '{}' is part of the primary key", name)));
+          }
+          ast::AlterColumnAction::DropNotNull => column.nullable = true,
+        }
+
+        AlterAction::Constraints
+      }
+      AlterTableOperation::AddConstraint(constraint) => {
+        self.add_constraints(schema, vec![constraint])?;
+        AlterAction::Constraints
+      }
+      AlterTableOperation::DropConstraint(constraint) => {
+        let constraint = constraint.parse_identifier();
+        if schema.get_constraint(&constraint).is_none() {
+          return Err(PlannerError::ConstraintNotFound { table: schema.name.clone(), constraint });
+        }
+
+        self.check_not_referenced(schema, &constraint, &[])?;
+        schema.remove_constraint(&constraint);
+
+        AlterAction::Constraints
+      }
+    };
+
+    Ok(action)
+  }
+
+  // INFO: Foreign keys are bound last so they can reference a key of the table itself
+  fn add_constraints(&mut self, schema: &mut Table, constraints: Vec<ast::TableConstraint>) -> Result<(), PlannerError> {
+    let scope = Scope::new(&schema.name, schema.column_names());
+    let (foreign_keys, others): (Vec<_>, Vec<_>) =
+      constraints.into_iter().partition(|constraint| matches!(constraint.kind, ast::TableConstraintKind::ForeignKey { .. }));
+
+    for constraint in others.into_iter().chain(foreign_keys) {
+      let constraint = self.bind_constraint(schema, constraint, &scope)?;
+      schema.add_constraint(constraint);
+    }
+
+    Ok(())
+  }
+
+  // INFO: A key can't go away while a foreign key references it, unless that foreign key is dropped as well
+  fn check_not_referenced(&self, schema: &Table, constraint: &str, dropped: &[String]) -> Result<(), PlannerError> {
+    let Some(ConstraintKind::PrimaryKey(columns) | ConstraintKind::Unique(columns)) =
+      schema.get_constraint(constraint).map(|constraint| &constraint.kind)
+    else {
+      return Ok(());
+    };
+
+    let referencing = self.catalog.referencing_constraints(&schema.name).into_iter().find(|(table, name, reference)| {
+      !(*table == schema.name && dropped.contains(name))
+        && reference.referenced_columns.len() == columns.len()
+        && reference.referenced_columns.iter().all(|column| columns.contains(column))
+    });
+
+    match referencing {
+      Some((referencing, ..)) => Err(PlannerError::ReferencedConstraint { constraint: constraint.to_string(), referencing }),
+      None => Ok(()),
+    }
+  }
+
+  // INFO: The constraint is checked against the table it will be added to, CHECK is stored as SQL
+  fn bind_constraint(
+    &mut self,
+    schema: &Table,
+    constraint: ast::TableConstraint,
+    scope: &Scope,
+  ) -> Result<Constraint, PlannerError> {
+    let mut condition = None;
+    let kind = match constraint.kind {
+      ast::TableConstraintKind::PrimaryKey(columns) => {
+        if schema.primary_key().is_some() {
+          return Err(PlannerError::MultiplePrimaryKeys(schema.name.clone()));
+        }
+
+        ConstraintKind::PrimaryKey(constraint_columns(schema, columns)?)
+      }
+      ast::TableConstraintKind::Unique(columns) => ConstraintKind::Unique(constraint_columns(schema, columns)?),
+      ast::TableConstraintKind::Check(expression) => {
+        let text = expression.to_string();
+        let expression = self.bind_expression(expression, scope)?;
+
+        let mut columns = Vec::new();
+        for identifier in expression.identifiers() {
+          let Some((_, column)) = identifier.split_once('.') else {
+            return Err(PlannerError::ColumnNotFound(identifier.clone()));
+          };
+          if !columns.iter().any(|name| name == column) {
+            columns.push(column.to_string());
+          }
+        }
+
+        condition = Some(expression);
+        ConstraintKind::Check { columns, condition: text }
+      }
+      ast::TableConstraintKind::ForeignKey { columns, table, referenced_columns, actions } => {
+        ConstraintKind::ForeignKey(Reference {
+          columns: constraint_columns(schema, columns)?,
+          table_name: table.parse_identifier(),
+          referenced_columns: referenced_columns.into_iter().map(ast::Expression::parse_identifier).collect(),
+          on_delete: referential_action(actions.on_delete),
+          on_update: referential_action(actions.on_update),
+        })
+      }
+    };
+
+    let name = match constraint.name {
+      Some(name) => {
+        let name = name.parse_identifier();
+        if self.constraint_name_taken(schema, &name) {
+          return Err(PlannerError::DuplicateConstraint(name));
+        }
+
+        name
+      }
+      None => self.constraint_name(schema, &kind),
+    };
+
+    if let Some(condition) = condition {
+      if let Some(reason) = row_expression_error(&condition) {
+        return Err(constraint_error(&name, reason));
+      }
+      if !matches!(static_type(&condition), Some(DataType::Boolean) | None) {
+        return Err(constraint_error(&name, "it has to be a boolean condition"));
+      }
+    }
+    if let ConstraintKind::ForeignKey(reference) = &kind {
+      self.check_reference(schema, &name, reference)?;
+    }
+
+    Ok(Constraint::new(name, kind))
+  }
+
+  // INFO: Names follow `table_columns_suffix` like `users_pkey` or `orders_user_id_fkey`, a number is added when one is taken
+  fn constraint_name(&self, schema: &Table, kind: &ConstraintKind) -> String {
+    let base = match kind {
+      ConstraintKind::PrimaryKey(_) => format!("{}_pkey", schema.name),
+      ConstraintKind::Unique(columns) => format!("{}_{}_key", schema.name, columns.join("_")),
+      ConstraintKind::Check { columns, .. } if columns.is_empty() => format!("{}_check", schema.name),
+      ConstraintKind::Check { columns, .. } => format!("{}_{}_check", schema.name, columns.join("_")),
+      ConstraintKind::ForeignKey(reference) => format!("{}_{}_fkey", schema.name, reference.columns.join("_")),
+    };
+
+    let mut name = base.clone();
+    let mut i = 0;
+    while self.constraint_name_taken(schema, &name) {
+      i += 1;
+      name = format!("{}{}", base, i);
+    }
+
+    name
+  }
+
+  // INFO: The keys are columns of the table, or a single value extracted from a JSON column with `->`, `->>` or `#>`
+  fn bind_index(
+    &mut self,
+    schema: &Table,
+    name: String,
+    unique: bool,
+    keys: Vec<ast::Expression>,
+  ) -> Result<Index, PlannerError> {
+    let scope = Scope::new(&schema.name, schema.column_names());
+    let written: Vec<String> = keys.iter().map(ToString::to_string).collect();
+    let keys = self.bind_expressions(keys, &scope)?;
+
+    if let [key @ Expression::Function(..)] = keys.as_slice() {
+      let Some((column, path)) = json_path(key) else {
+        return Err(index_error(&name, format!("'{}' is not a column or a path into a JSON column", written[0])));
+      };
+      let column = unqualified(&column).to_string();
+      if schema.get_column(&column).is_some_and(|column| column.data_type != DataType::Json) {
+        return Err(index_error(&name, format!("column '{}' is not of type JSON", column)));
+      }
+
+      return Ok(Index::on_path(name, column, path, unique));
+    }
+
+    let mut columns = Vec::new();
+    for (key, written) in keys.into_iter().zip(written) {
+      let Expression::Identifier(column) = key else {
+        return Err(index_error(&name, format!("'{}' is not a column", written)));
+      };
+      let column = unqualified(&column).to_string();
+      if columns.contains(&column) {
+        return Err(PlannerError::DuplicateColumn(column));
+      }
+
+      columns.push(column);
+    }
+
+    Ok(Index::new(name, columns, unique))
+  }
+
+  // INFO: A PRIMARY KEY or UNIQUE constraint gives its name to an index, index names are shared by the whole database
+  fn constraint_name_taken(&self, schema: &Table, name: &str) -> bool {
+    schema.get_constraint(name).is_some() || schema.indexes.iter().any(|index| index.name == name) || self.catalog.has_index(name)
+  }
+
+  // INFO: The referenced columns have to be a PRIMARY KEY or UNIQUE constraint of the referenced table and match the
+  // types of the columns, the table can reference itself
+  fn check_reference(&self, schema: &Table, name: &str, reference: &Reference) -> Result<(), PlannerError> {
+    let table = match reference.table_name == schema.name {
+      true => schema,
+      false => self.get_table(&reference.table_name)?,
+    };
+
+    if reference.columns.len() != reference.referenced_columns.len() {
+      let reason = format!("it has {} columns but references {}", reference.columns.len(), reference.referenced_columns.len());
+      return Err(constraint_error(name, reason));
+    }
+
+    for (column, referenced) in reference.columns.iter().zip(&reference.referenced_columns) {
+      let Some(parent) = table.get_column(referenced) else {
+        return Err(PlannerError::ColumnNotFound(format!("{}.{}", table.name, referenced)));
+      };
+      let Some(child) = schema.get_column(column) else {
+        return Err(PlannerError::ColumnNotFound(column.clone()));
+      };
+
+      if parent.data_type.value_type() != child.data_type.value_type() {
+        let (parent_type, child_type) = (&parent.data_type, &child.data_type);
+        let reason = format!("'{}.{}' is of type {} but '{}' is {}", table.name, referenced, parent_type, column, child_type);
+        return Err(constraint_error(name, reason));
+      }
+      if [reference.on_delete, reference.on_update].contains(&ReferentialAction::SetNull) && !child.nullable {
+        return Err(constraint_error(name, format!("SET NULL can't be used on NOT NULL column '{}'", column)));
+      }
+    }
+
+    if table.find_unique_index(&reference.referenced_columns).is_none() {
+      let columns = reference.referenced_columns.join(", ");
+      return Err(constraint_error(name, format!("({}) of table '{}' is not a primary key or unique", columns, table.name)));
+    }
+
+    Ok(())
+  }
+
+  // INFO: Defaults are only bound for the columns left out of an INSERT
+  fn bind_row_constraints(&mut self, schema: &Table, listed: Option<&[String]>) -> Result<RowConstraints, PlannerError> {
+    let scope = Scope::new(&schema.name, schema.column_names());
+    let mut constraints = RowConstraints::default();
+
+    for column in &schema.columns {
+      if let (Some(listed), Some(default)) = (listed, &column.default) {
+        if !listed.contains(&column.name) {
+          let value = self.bind_stored_expression(default, &Scope::default())?;
+          constraints.defaults.push((column.name.clone(), value));
+        }
+      }
+    }
+
+    for constraint in &schema.constraints {
+      if let ConstraintKind::Check { columns, condition } = &constraint.kind {
+        let condition = self.bind_stored_expression(condition, &scope)?;
+        let condition = self.cast_operands(condition, &|expression| row_type(schema, expression), &[]);
+        constraints.checks.push(Check { name: constraint.name.clone(), columns: columns.clone(), condition });
+      }
+    }
+
+    Ok(constraints)
+  }
+
+  fn bind_stored_expression(&mut self, text: &str, scope: &Scope) -> Result<Expression, PlannerError> {
+    let expression = Parser::new(text)
+      .parse_standalone_expression()
+      .map_err(|error| PlannerError::InvalidStoredExpression { expression: text.to_string(), reason: error.to_string() })?;
+
+    self.bind_expression(expression, scope)
+  }
+
+  fn check_insert_row(
+    &self,
+    schema: &Table,
+    columns: &[String],
+    row: &[Expression],
+    source_table: Option<&String>,
+  ) -> Result<(), PlannerError> {
+    if row.len() != columns.len() {
+      return Err(PlannerError::ColumnCountMismatch { expected: columns.len(), found: row.len() });
+    }
+
+    for (column, expression) in columns.iter().zip(row) {
+      let Some(target) = schema.get_column(column) else {
+        return Err(PlannerError::ColumnNotFound(column.clone()));
+      };
+
+      let fits = match expression {
+        Expression::Constant(value) => {
+          let value = catalog::Value::from(value.clone());
+          let found = value.data_type();
+
+          match (&value, &target.data_type) {
+            // INFO: the executor reports text that can't be read as JSON or a UUID
+            (catalog::Value::Text(_), DataType::Json | DataType::Uuid) => Ok(value),
+            _ => value.coerce_to(&target.data_type.value_type()).ok_or(found), // INFO: the executor checks the range and length
+          }
+        }
+        Expression::Identifier(name) => match source_table.and_then(|table| self.catalog.get_column(table, unqualified(name))) {
+          Some(source) if !source.data_type.is_assignable_to(&target.data_type) => Err(source.data_type.clone()),
+          _ => Ok(catalog::Value::Null),
+        },
+        _ => Ok(catalog::Value::Null), // INFO: the executor checks everything else once the value is known
+      };
+
+      if let Err(found) = fits {
+        return Err(PlannerError::TypeMismatch { column: column.clone(), expected: target.data_type.clone(), found });
+      }
+    }
+
+    Ok(())
+  }
+}
+
+// INFO: Name of the pseudo table that holds the row proposed for insertion in ON CONFLICT DO UPDATE
+pub const EXCLUDED_TABLE: &str = "excluded";
+
+// INFO: Alias of a set operation that is sorted or limited, its columns are referenced without it
+const SET_OPERATION_ALIAS: &str = "?set?";
+
+// INFO: Alias of an IN subquery whose column is cast to the type of the value looked up in it
+const CAST_ALIAS: &str = "?cast?";
+
+// INFO: Functions the JSON operators are bound to, `->` is JSON_EXTRACT_PATH, `->>` JSON_EXTRACT_PATH_TEXT and `#>` JSON_EXTRACT
+pub const JSON_EXTRACT_PATH: &str = "JSON_EXTRACT_PATH";
+pub const JSON_EXTRACT_PATH_TEXT: &str = "JSON_EXTRACT_PATH_TEXT";
+pub const JSON_EXTRACT: &str = "JSON_EXTRACT";
+
+// INFO: The column and the constant steps of an extraction like `payload -> 'a' ->> 'b'`, None for anything else.
+// Only the last step can give text
+pub fn json_path(expression: &Expression) -> Option<(String, JsonPath)> {
+  let Expression::Function(function, args) = expression else { return None };
+  let (source, keys) = args.split_first()?;
+
+  let (column, mut steps) = match source {
+    Expression::Identifier(column) => (column.clone(), Vec::new()),
+    source => match json_path(source)? {
+      (column, JsonPath { steps, as_text: false }) => (column, steps),
+      _ => return None,
+    },
+  };
+
+  for key in keys {
+    match (function.name.as_str(), key) {
+      (JSON_EXTRACT, Expression::Constant(Value::Text(path))) => steps.extend(parse_path(path)?),
+      (JSON_EXTRACT_PATH | JSON_EXTRACT_PATH_TEXT, Expression::Constant(Value::Text(key))) => steps.push(key.clone()),
+      (JSON_EXTRACT_PATH | JSON_EXTRACT_PATH_TEXT, Expression::Constant(Value::Int(i))) => steps.push(i.to_string()),
+      (JSON_EXTRACT_PATH | JSON_EXTRACT_PATH_TEXT, Expression::Constant(Value::Float(f))) if f.fract() == 0.0 => {
+        steps.push((*f as i64).to_string())
+      }
+      _ => return None,
+    }
+  }
+
+  Some((column, JsonPath { steps, as_text: function.name == JSON_EXTRACT_PATH_TEXT }))
+}
+
+fn unqualified(identifier: &str) -> &str {
+  identifier.rsplit_once('.').map_or(identifier, |(_, column)| column)
+}
+
+fn find_projection(node: &Node) -> &Node {
+  match node {
+    Node::Limit { source, .. }
+    | Node::Offset { source, .. }
+    | Node::Distinct { source, on: None }
+    | Node::Recursive { anchor: source, .. }
+    | Node::SetOperation { left: source, .. } => find_projection(source),
+    _ => node,
+  }
+}
+
+// INFO: Names the output columns of the query with the names listed after the CTE name
+fn rename_columns(node: &mut Node, name: &str, columns: &[String]) -> Result<(), PlannerError> {
+  match node {
+    Node::Limit { source, .. }
+    | Node::Offset { source, .. }
+    | Node::Distinct { source, on: None }
+    | Node::SetOperation { left: source, .. } => rename_columns(source, name, columns),
+    Node::Projection { columns: projection, .. } => {
+      if projection.len() != columns.len() {
+        return Err(PlannerError::CteColumnCount { name: name.to_string(), expected: projection.len(), found: columns.len() });
+      }
+
+      for (expression, column) in projection.iter_mut().zip(columns) {
+        let inner = match std::mem::replace(expression, Expression::Constant(Value::Null)) {
+          Expression::Alias(inner, _) => inner,
+          inner => Box::new(inner),
+        };
+
+        *expression = Expression::Alias(inner, column.clone());
+      }
+
+      Ok(())
+    }
+    _ => Ok(()),
+  }
+}
+
+fn reads_working_table(node: &Node, name: &str) -> bool {
+  matches!(node, Node::WorkingTable { name: table } if table == name)
+    || node.expressions().into_iter().any(|expression| expression_reads_working_table(expression, name))
+    || node.tranverse().into_iter().skip(1).any(|child| reads_working_table(child, name))
+}
+
+fn expression_reads_working_table(expression: &Expression, name: &str) -> bool {
+  expression.subquery().is_some_and(|node| reads_working_table(node, name))
+    || expression.children().into_iter().any(|child| expression_reads_working_table(child, name))
+}
+
+fn find_scan_table(node: &Node) -> Option<&String> {
+  match node {
+    Node::Scan { table, .. } => Some(table),
+    _ => node.tranverse().into_iter().skip(1).find_map(find_scan_table),
+  }
+}
+
+fn expand_wildcard(select: Vec<ast::Expression>, scope: &Scope) -> Vec<ast::Expression> {
+  select
+    .into_iter()
+    .flat_map(|expression| match expression {
+      ast::Expression::Identifier(name) if name == "*" => {
+        scope.qualified_columns().into_iter().map(ast::Expression::Identifier).collect::<Vec<_>>()
+      }
+      expression => vec![expression],
+    })
+    .collect()
+}
+
+fn table_scope(table: &ast::Table, schema: &Table) -> Scope {
+  Scope::new(table.alias.as_ref().unwrap_or(&table.name), schema.column_names())
+}
+
+// INFO: Names of the columns a query returns, they match the names the executor gives its projection
+pub fn output_columns(node: &Node) -> Vec<String> {
+  match find_projection(node) {
+    Node::Projection { columns, .. } => columns.iter().map(output_name).collect(),
+    _ => Vec::new(),
+  }
+}
+
+pub fn output_name(expression: &Expression) -> String {
+  match expression {
+    Expression::Alias(_, alias) => alias.clone(),
+    Expression::Identifier(name) => unqualified(name).to_string(),
+    Expression::Aggregate(function, ..) => function.to_string().to_lowercase(),
+    Expression::Cast(expression, _) => output_name(expression),
+    Expression::Case(..) => "case".to_string(),
+    Expression::Coalesce(_) => "coalesce".to_string(),
+    Expression::NullIf(..) => "nullif".to_string(),
+    Expression::Function(function, _) => function.name.to_lowercase(),
+    Expression::Window(function, ..) => function.to_string().to_lowercase(),
+    _ => "?column?".to_string(),
+  }
+}
+
+// INFO: Type of an expression that doesn't depend on any column, None when it can't be known without the source
+fn static_type(expression: &Expression) -> Option<DataType> {
+  match expression {
+    Expression::Alias(expression, _) => static_type(expression),
+    Expression::Constant(value) => match catalog::Value::from(value.clone()).data_type() {
+      DataType::Null => None,
+      data_type => Some(data_type),
+    },
+    Expression::Cast(_, data_type) => Some(data_type.value_type()),
+    Expression::IsNull(_) | Expression::Between(..) | Expression::Like(..) | Expression::ILike(..) => Some(DataType::Boolean),
+    Expression::Function(function, args) => function.signature.return_type(&args.iter().map(static_type).collect::<Vec<_>>()),
+    Expression::Window(WindowFunction::RowNumber | WindowFunction::Rank | WindowFunction::DenseRank, ..) => Some(DataType::Int),
+    _ => None,
+  }
+}
+
+// INFO: Type of an expression over the columns of a single row of the table
+fn row_type(schema: &Table, expression: &Expression) -> Option<DataType> {
+  match expression {
+    Expression::Identifier(name) => schema.get_column(unqualified(name)).map(|column| column.data_type.value_type()),
+    expression => static_type(expression),
+  }
+}
+
+// INFO: Type a string compared with a value of the other type is cast to, None when it is compared as it is
+fn text_cast(data_type: Option<&DataType>, other: Option<&DataType>) -> Option<DataType> {
+  let other = other?;
+  let written_as_text = matches!(
+    other,
+    DataType::Date
+      | DataType::DateTime
+      | DataType::TimestampTz
+      | DataType::Interval
+      | DataType::Json
+      | DataType::Bytea
+      | DataType::Uuid
+  );
+
+  (*data_type? == DataType::Text && written_as_text).then(|| other.clone())
+}
+
+// INFO: Wraps a single column query so it returns its column cast to the type
+fn cast_output(node: Node, data_type: DataType) -> Node {
+  let column = output_columns(&node).swap_remove(0);
+  let identifier = Expression::Identifier(format!("{}.{}", CAST_ALIAS, column));
+  let source = Node::Subquery { source: Box::new(node), alias: CAST_ALIAS.to_string() };
+
+  Node::Projection {
+    source: Box::new(source),
+    columns: vec![Expression::Alias(Box::new(Expression::Cast(Box::new(identifier), data_type)), column)],
+  }
+}
+
+// INFO: Names of the GroupBy output columns holding computed group keys and aggregate results
+pub fn group_column(index: usize) -> String {
+  format!("#group{}", index)
+}
+
+pub fn aggregate_column(index: usize) -> String {
+  format!("#aggregate{}", index)
+}
+
+pub fn window_column(index: usize) -> String {
+  format!("#window{}", index)
+}
+
+fn collect_windows(expression: &Expression, windows: &mut Vec<Expression>) -> Result<(), PlannerError> {
+  match expression {
+    Expression::Window(..) => {
+      if expression.children().into_iter().any(Expression::has_window) {
+        return Err(PlannerError::WindowNotAllowed("the arguments or the window of another window function".to_string()));
+      }
+
+      if !windows.contains(expression) {
+        windows.push(expression.clone());
+      }
+
+      Ok(())
+    }
+    expression => expression.children().into_iter().try_for_each(|child| collect_windows(child, windows)),
+  }
+}
+
+// INFO: Rewrites an expression above a Window node to read the computed columns instead of the window functions
+fn windowed(expression: Expression, windows: &[Expression]) -> Expression {
+  expression.transform(&mut |expression| match windows.iter().position(|window| *window == expression) {
+    Some(index) => Expression::Identifier(window_column(index)),
+    None => expression,
+  })
+}
+
+fn collect_aggregates(expression: &Expression, aggregates: &mut Vec<Expression>) -> Result<(), PlannerError> {
+  match expression {
+    Expression::Aggregate(function, args, _) => {
+      if args.iter().any(Expression::has_aggregate) {
+        return Err(PlannerError::NestedAggregate(function.to_string()));
+      }
+
+      if !aggregates.contains(expression) {
+        aggregates.push(expression.clone());
+      }
+
+      Ok(())
+    }
+    expression => expression.children().into_iter().try_for_each(|child| collect_aggregates(child, aggregates)),
+  }
+}
+
+// INFO: Rewrites an expression above a GroupBy to read its columns, a column that isn't a group key can't be read there
+fn grouped(expression: Expression, keys: &[Expression], aggregates: &[Expression]) -> Result<Expression, PlannerError> {
+  if let Some(index) = keys.iter().position(|key| *key == expression) {
+    return Ok(match expression {
+      Expression::Identifier(_) => expression,
+      _ => Expression::Identifier(group_column(index)),
+    });
+  }
+
+  match expression {
+    Expression::Aggregate(..) => {
+      let index = aggregates.iter().position(|aggregate| *aggregate == expression).expect("aggregate was collected");
+      Ok(Expression::Identifier(aggregate_column(index)))
+    }
+    Expression::Identifier(name) => Err(PlannerError::NotGrouped(name)),
+    expression => expression.try_map_children(&mut |child| grouped(child, keys, aggregates)),
+  }
+}
+
+// INFO: EXISTS, NOT EXISTS and IN conjuncts of WHERE become semi and anti joins when the subquery is a filtered table scan,
+// the rest stays in a filter where subqueries are evaluated for every row
+fn decorrelate(node: Node, scope: &Scope, condition: Expression) -> Node {
+  let mut joins = Vec::new();
+  let mut remaining = Vec::new();
+
+  for conjunct in split_conjunction(condition) {
+    match semi_join(&conjunct, scope) {
+      Some(join) => joins.push(join),
+      None => remaining.push(conjunct),
+    }
+  }
+
+  let mut node = node;
+
+  if let Some(condition) = join_conjunction(remaining) {
+    node = Node::Filter { source: Box::new(node), condition };
+  }
+
+  for (right, condition, join_type) in joins {
+    node = Node::NestedLoopJoin { left: Box::new(node), right: Box::new(right), condition, join_type };
+  }
+
+  node
+}
+
+fn semi_join(conjunct: &Expression, scope: &Scope) -> Option<(Node, Expression, JoinType)> {
+  let (expression, subquery, join_type) = match conjunct {
+    Expression::Exists(node) => (None, node, JoinType::Semi),
+    Expression::Not(inner) => match &**inner {
+      Expression::Exists(node) => (None, node, JoinType::Anti),
+      _ => return None, // INFO: NOT IN is not an anti join once NULLs are involved
+    },
+    Expression::InSubquery(expression, node) => (Some(expression), node, JoinType::Semi),
+    _ => return None,
+  };
+
+  let (source, projection) = match &**subquery {
+    Node::Projection { source, columns } => (source, columns),
+    _ => return None,
+  };
+
+  let (scan, filter) = match &**source {
+    Node::Filter { source, condition } => (&**source, Some(condition)),
+    scan => (scan, None),
+  };
+
+  let qualifier = match scan {
+    Node::Scan { table, alias, .. } => alias.as_ref().unwrap_or(table),
+    _ => return None,
+  };
+
+  if scope.has_qualifier(qualifier) {
+    return None;
+  }
+
+  let (mut on, inner): (Vec<Expression>, Vec<Expression>) =
+    filter.cloned().map(split_conjunction).unwrap_or_default().into_iter().partition(|conjunct| conjunct.references_outer(1));
+
+  // INFO: only columns of the two tables can be used by the join, anything else stays a subquery
+  if on.iter().chain(&inner).any(|conjunct| conjunct.has_subquery() || conjunct.references_outer(2)) {
+    return None;
+  }
+
+  if let Some(expression) = expression {
+    let column = match projection.as_slice() {
+      [Expression::Alias(column, _)] => &**column,
+      [column] => column,
+      _ => return None,
+    };
+
+    if expression.has_subquery() || column.has_subquery() || column.references_outer(1) {
+      return None;
+    }
+
+    on.push(Expression::Equal(expression.clone(), Box::new(column.clone())));
+  } else if on.is_empty() {
+    return None; // INFO: an uncorrelated EXISTS is cheaper to run once
+  }
+
+  let on = on.into_iter().map(|conjunct| {
+    conjunct.transform(&mut |expression| match expression {
+      Expression::Outer(name, 1) => Expression::Identifier(name),
+      expression => expression,
+    })
+  });
+
+  let mut right = scan.clone();
+  if let Some(condition) = join_conjunction(inner) {
+    right = Node::Filter { source: Box::new(right), condition };
+  }
+
+  Some((right, join_conjunction(on.collect())?, join_type))
+}
+
+pub fn split_conjunction(expression: Expression) -> Vec<Expression> {
+  match expression {
+    Expression::And(left, right) => {
+      let mut conjuncts = split_conjunction(*left);
+      conjuncts.extend(split_conjunction(*right));
+      conjuncts
+    }
+    expression => vec![expression],
+  }
+}
+
+pub fn join_conjunction(conjuncts: Vec<Expression>) -> Option<Expression> {
+  conjuncts.into_iter().reduce(|left, right| Expression::And(Box::new(left), Box::new(right)))
+}
+
+fn negate(expression: Expression, negated: bool) -> Expression {
+  if negated {
+    Expression::Not(Box::new(expression))
+  } else {
+    expression
+  }
+}
+
+fn binary_operator_to_expression(
+  operator: ast::Operator,
+  left: Expression,
+  right: Expression,
+) -> Result<Expression, PlannerError> {
+  let (left, right) = (Box::new(left), Box::new(right));
+
+  Ok(match operator {
+    ast::Operator::Equal => Expression::Equal(left, right),
+    ast::Operator::NotEqual => Expression::NotEqual(left, right),
+    ast::Operator::GreaterThan => Expression::GreaterThan(left, right),
+    ast::Operator::GreaterThanOrEqual => Expression::GreaterThanOrEqual(left, right),
+    ast::Operator::LessThan => Expression::LessThan(left, right),
+    ast::Operator::LessThanOrEqual => Expression::LessThanOrEqual(left, right),
+    ast::Operator::Add => Expression::Add(left, right),
+    ast::Operator::Subtract => Expression::Subtract(left, right),
+    ast::Operator::Multiply | ast::Operator::Asterisk => Expression::Multiply(left, right),
+    ast::Operator::Divide => Expression::Divide(left, right),
+    ast::Operator::And => Expression::And(left, right),
+    ast::Operator::Or => Expression::Or(left, right),
+    operator => return Err(PlannerError::Unsupported(format!("{} as a binary operator", operator))),
+  })
+}
+
+fn data_type_to_primitive(data_type: ast::DataType) -> DataType {
+  match data_type {
+    ast::DataType::SmallInt => crate::sql::catalog::DataType::SmallInt,
+    ast::DataType::Int => crate::sql::catalog::DataType::Int,
+    ast::DataType::BigInt => crate::sql::catalog::DataType::BigInt,
+    ast::DataType::Real => crate::sql::catalog::DataType::Real,
+    ast::DataType::Float | ast::DataType::Double => crate::sql::catalog::DataType::Float,
+    ast::DataType::Text | ast::DataType::Varchar(None) => crate::sql::catalog::DataType::Text,
+    ast::DataType::Varchar(Some(length)) => crate::sql::catalog::DataType::Varchar(length),
+    ast::DataType::Char(length) => crate::sql::catalog::DataType::Char(length.unwrap_or(1)), // INFO: CHAR is CHAR(1)
+    ast::DataType::Decimal(precision) => crate::sql::catalog::DataType::Decimal(precision),
+    ast::DataType::Boolean => crate::sql::catalog::DataType::Boolean,
+    ast::DataType::Date => crate::sql::catalog::DataType::Date,
+    ast::DataType::Timestamp => crate::sql::catalog::DataType::DateTime,
+    ast::DataType::TimestampTz => crate::sql::catalog::DataType::TimestampTz,
+    ast::DataType::Interval => crate::sql::catalog::DataType::Interval,
+    ast::DataType::Json => crate::sql::catalog::DataType::Json,
+    ast::DataType::Bytea => crate::sql::catalog::DataType::Bytea,
+    ast::DataType::Uuid => crate::sql::catalog::DataType::Uuid,
+  }
+}
+
+fn literal_to_value(literal: ast::Literal) -> Value {
+  match literal {
+    ast::Literal::Integer(value) => Value::Int(value),
+    ast::Literal::Decimal(value) => Value::Decimal(value),
+    ast::Literal::Number(value) => Value::Float(value),
+    ast::Literal::String(value) => Value::Text(value),
+    ast::Literal::Boolean(value) => Value::Boolean(value),
+    ast::Literal::Null => Value::Null,
+    ast::Literal::Date(value) => Value::Date(value),
+    ast::Literal::DateTime(value) => Value::Timestamp(value),
+    ast::Literal::Timestamp(value) => Value::TimestampTz(value),
+    ast::Literal::Interval(value) => Value::Interval(value),
+    ast::Literal::Bytes(value) => Value::Bytes(value),
+  }
+}
+
+fn constraint_error(constraint: &str, reason: impl Into<String>) -> PlannerError {
+  PlannerError::InvalidConstraint { constraint: constraint.to_string(), reason: reason.into() }
+}
+
+fn default_error(column: &str, reason: impl Into<String>) -> PlannerError {
+  PlannerError::InvalidDefault { column: column.to_string(), reason: reason.into() }
+}
+
+fn alter_error(schema: &Table, reason: impl Into<String>) -> PlannerError {
+  PlannerError::InvalidAlterTable { table: schema.name.clone(), reason: reason.into() }
+}
+
+fn index_error(index: &str, reason: impl Into<String>) -> PlannerError {
+  PlannerError::InvalidIndex { index: index.to_string(), reason: reason.into() }
+}
+
+// INFO: Subqueries, aggregates and window functions can't be evaluated on a single row that is being written
+fn row_expression_error(expression: &Expression) -> Option<&'static str> {
+  if expression.has_subquery() {
+    return Some("it can't contain subqueries");
+  }
+  if expression.has_aggregate() || expression.has_window() {
+    return Some("it can't contain aggregate or window functions");
+  }
+
+  None
+}
+
+// INFO: Columns of a PRIMARY KEY, UNIQUE or FOREIGN KEY constraint, each one once
+fn constraint_columns(schema: &Table, columns: Vec<ast::Expression>) -> Result<Vec<String>, PlannerError> {
+  let mut names: Vec<String> = Vec::with_capacity(columns.len());
+  for column in columns {
+    let name = column.parse_identifier();
+    if schema.get_column(&name).is_none() {
+      return Err(PlannerError::ColumnNotFound(name));
+    }
+    if names.contains(&name) {
+      return Err(PlannerError::DuplicateColumn(name));
+    }
+
+    names.push(name);
+  }
+
+  Ok(names)
+}
 
-  Column {
-    name: name.parse_identifier(), // check if it is unique on the table and if not throw error
-    data_type: data_type_to_primitive(data_type),
-    unique: false,
-    nullable: false,
-    default: None,
-    references: None,
+fn referential_action(action: ast::ReferentialAction) -> ReferentialAction {
+  match action {
+    ast::ReferentialAction::NoAction => ReferentialAction::NoAction,
+    ast::ReferentialAction::Restrict => ReferentialAction::Restrict,
+    ast::ReferentialAction::Cascade => ReferentialAction::Cascade,
+    ast::ReferentialAction::SetNull => ReferentialAction::SetNull,
   }
 }
 
 pub fn get_column_by_name<'a>(table: &'a Table, name: &'a str) -> Option<&'a Column> {
-  table.columns.iter().find(|(column_name, _)| *column_name == name).map(|(_, column)| column)
+  table.get_column(name)
 }
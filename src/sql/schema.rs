@@ -6,7 +6,7 @@ use super::parser::ast::Table as TableAst;
 // We need functions that decide based od AST what to do with the table
 // Here we should have CRUD operations for table and in planner we should have deciding process
 
-fn create_table(table_ast: TableAst) -> () {}
+fn create_table(table_ast: TableAst) {}
 
 #[derive(Debug, Clone)]
 pub struct Table {
@@ -0,0 +1,49 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::manager::RowId;
+use crate::sql::catalog::Value;
+
+// INFO: Ordered map from the indexed column values to the rows that hold them, kept whole in memory and written to its own file
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BTreeIndex {
+  entries: BTreeMap<Vec<Value>, Vec<RowId>>,
+}
+
+impl BTreeIndex {
+  pub fn new() -> Self {
+    Self { entries: BTreeMap::new() }
+  }
+
+  pub fn insert(&mut self, key: Vec<Value>, row_id: RowId) {
+    let row_ids = self.entries.entry(key).or_default();
+
+    if !row_ids.contains(&row_id) {
+      row_ids.push(row_id);
+    }
+  }
+
+  pub fn remove(&mut self, key: &[Value], row_id: RowId) {
+    if let Some(row_ids) = self.entries.get_mut(key) {
+      row_ids.retain(|id| *id != row_id);
+
+      if row_ids.is_empty() {
+        self.entries.remove(key);
+      }
+    }
+  }
+
+  pub fn get(&self, key: &[Value]) -> &[RowId] {
+    self.entries.get(key).map_or(&[], |row_ids| row_ids.as_slice())
+  }
+
+  // INFO: Rows of the keys from the start key on, for as long as the first key column satisfies the condition
+  pub fn scan(&self, start: &[Value], within: impl Fn(&Value) -> bool) -> Vec<RowId> {
+    self
+      .entries
+      .range(start.to_vec()..)
+      .take_while(|(key, _)| key.first().is_some_and(&within))
+      .flat_map(|(_, row_ids)| row_ids.iter().copied())
+      .collect()
+  }
+}
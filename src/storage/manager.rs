@@ -1,15 +1,17 @@
-use bincode::{deserialize_from, serialize_into};
 use serde_derive::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::io::prelude::*;
+use std::io::SeekFrom;
 use std::path::Path;
 
-use crate::sql::catalog::{Catalog, Column, Table};
-use crate::sql::constants::{BUFFER_POOL_SIZE, CATALOG_FILE, PAGE_SIZE};
-use crate::sql::planner::plan::{self, Expression};
-use crate::sql::{self, catalog};
+use super::index::BTreeIndex;
+use crate::sql::catalog::{self, Catalog, Table};
+use crate::sql::constants::{BUFFER_POOL_SIZE, CATALOG_FILE, INDEX_FILE_EXTENSION, PAGE_SIZE, TABLE_FILE_EXTENSION};
+
+pub const ROW_TOO_LARGE: &str = "Row does not fit in a page";
+const PAGE_FULL: &str = "Not enough space in the page";
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Page {
@@ -32,6 +34,13 @@ impl PageHeader {
   }
 }
 
+// INFO: Location of a row inside of a table file, slot is the index of the tuple in the page
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RowId {
+  pub page: u32,
+  pub slot: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Tuple {
   id: u32,     // tuple id = page_id + offset
@@ -103,41 +112,48 @@ impl Page {
     self.tuples.get(index)
   }
 
+  pub fn get_live_tuple(&self, id: u32) -> Option<&Tuple> {
+    self.tuples.iter().find(|tuple| tuple.id == id && !tuple.is_free())
+  }
+
   pub fn add_tuple(&mut self, tuple: Tuple) -> Result<(), &'static str> {
-    let tuple_length = tuple.length as u16;
     self.tuples.push(tuple);
 
     Ok(())
   }
 
-  pub fn insert_tuple(&mut self, data: Vec<u8>) -> Result<(), &'static str> {
-    // if self.is_full() {
-    //   return Err("Page is full");
-    // }
-
-    // let offset = self.header.page_upper as u32;
+  pub fn empty(id: u32) -> Self {
+    let page_prev = if id == 0 { 0 } else { id as u16 - 1 };
+    Self::new(PageHeader::new(id, 0, page_prev, 0, 0), Vec::new())
+  }
 
-    // let tuple_id = self.header.id + self.tuples.len() as u32;
-    // let tuple = Tuple::new(tuple_id, 2, offset, data); // TODO: clean up the xact field if not used
+  // INFO: Returns the slot of the new tuple, tuples are never moved so the slot stays valid
+  pub fn insert_tuple(&mut self, data: Vec<u8>) -> Result<u32, &'static str> {
+    let slot = self.tuples.len() as u32;
+    self.add_tuple(Tuple::new(slot, slot, data))?;
 
-    // self.add_tuple(tuple);
+    if self.is_full() {
+      self.tuples.pop();
+      return Err("Page is full");
+    }
 
-    Ok(())
+    Ok(slot)
   }
 
   pub fn get_tuples_data(&self) -> Vec<Vec<u8>> {
     self.tuples.iter().map(|tuple| tuple.data.clone()).collect()
   }
 
+  // INFO: Live tuples of the page together with their slots
+  pub fn get_live_tuples(&self) -> Vec<(u32, Vec<u8>)> {
+    self.tuples.iter().filter(|tuple| !tuple.is_free()).map(|tuple| (tuple.id, tuple.data.clone())).collect()
+  }
+
   pub fn remove_tuple_by_id(&mut self, id: u32) -> Result<(), &'static str> {
     let index = self.tuples.iter().position(|tuple| tuple.id == id);
 
     if let Some(index) = index {
-      // let removed_tuple = self.tuples.remove(index);
-      // self.slots.remove(index);
-
-      // self.header.page_lower -= 1;
-      // self.header.page_upper += removed_tuple.length as u16;
+      self.tuples[index].set_free();
 
       Ok(())
     } else {
@@ -145,6 +161,25 @@ impl Page {
     }
   }
 
+  // INFO: Replaces the data of a tuple in place, the old data is kept if the new one doesn't fit in the page
+  pub fn update_tuple_by_id(&mut self, id: u32, data: Vec<u8>) -> Result<(), &'static str> {
+    let index = self.tuples.iter().position(|tuple| tuple.id == id && !tuple.is_free()).ok_or("Tuple not found")?;
+    let old_data = std::mem::replace(&mut self.tuples[index].data, data);
+    self.tuples[index].length = self.tuples[index].data.len() as u16;
+
+    if self.is_full() {
+      self.tuples[index].length = old_data.len() as u16;
+      self.tuples[index].data = old_data;
+      return Err(PAGE_FULL);
+    }
+
+    Ok(())
+  }
+
+  pub fn is_full(&self) -> bool {
+    bincode::serialized_size(self).map_or(true, |size| size as usize > PAGE_SIZE)
+  }
+
   // pub fn update_tuple_by_id(&mut self, id: u32, data: Vec<u8>) -> Result<(), &'static str> {
   //   let index = self.tuples.iter().position(|tuple| tuple.id == id);
 
@@ -187,10 +222,20 @@ pub struct BufferPool {
   frames: Vec<Page>,
   catalog: Catalog,
   storage_manager: StorageManager,
-  page_table: HashMap<u32, usize>,
+  page_table: HashMap<(String, u32), usize>,
   dirty_pages: HashSet<usize>,
   pin_count: Vec<u32>,
-  origin_pages: HashMap<u32, u32>,
+  free_frames: Vec<usize>,
+  page_counts: HashMap<String, u32>,
+  next_victim: usize,
+  indexes: HashMap<String, BTreeIndex>,
+  dirty_indexes: HashSet<String>,
+}
+
+impl Default for BufferPool {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 impl BufferPool {
@@ -202,24 +247,105 @@ impl BufferPool {
     let page_table = HashMap::new();
     let dirty_pages = HashSet::new();
     let pin_count = Vec::with_capacity(BUFFER_POOL_SIZE);
-    let origin_pages = HashMap::new(); // should be loaded from disk but if there are no origin pages it should be empty
+    let free_frames = Vec::new();
+    let page_counts = HashMap::new(); // INFO: loaded lazily from the size of the table file
+
+    let indexes = HashMap::new(); // INFO: loaded lazily from the index files
+
+    Self {
+      frames,
+      catalog,
+      storage_manager,
+      page_table,
+      dirty_pages,
+      pin_count,
+      free_frames,
+      page_counts,
+      next_victim: 0,
+      indexes,
+      dirty_indexes: HashSet::new(),
+    }
+  }
+
+  fn get_page(&mut self, table_name: &str, page_id: u32) -> io::Result<&mut Page> {
+    let index = self.fetch_page(table_name, page_id)?;
+    Ok(&mut self.frames[index])
+  }
+
+  // INFO: Returns the frame that holds the page, reading it from disk if it is not in the buffer pool
+  fn fetch_page(&mut self, table_name: &str, page_id: u32) -> io::Result<usize> {
+    if let Some(&index) = self.page_table.get(&(table_name.to_string(), page_id)) {
+      return Ok(index);
+    }
+
+    let page = self.storage_manager.read_page(table_name, page_id)?;
+    self.add_page(table_name, page)
+  }
+
+  fn add_page(&mut self, table_name: &str, page: Page) -> io::Result<usize> {
+    let page_id = page.header.id;
+
+    let index = if let Some(index) = self.free_frames.pop() {
+      self.frames[index] = page;
+      index
+    } else if self.frames.len() < BUFFER_POOL_SIZE {
+      self.frames.push(page);
+      self.pin_count.push(0);
+      self.frames.len() - 1
+    } else {
+      let index = self.evict_page()?;
+      self.frames[index] = page;
+      index
+    };
 
-    Self { frames, catalog, storage_manager, page_table, dirty_pages, pin_count, origin_pages }
+    self.page_table.insert((table_name.to_string(), page_id), index);
+
+    Ok(index)
   }
 
-  fn get_page(&mut self, page_id: u32) -> Option<&mut Page> {
-    self.page_table.get(&page_id).and_then(|&index| self.frames.get_mut(index))
-    // if not in buffer pool read from disk
+  // INFO: Clock like eviction, the victim is written to disk if it is dirty
+  fn evict_page(&mut self) -> io::Result<usize> {
+    loop {
+      let index = self.next_victim;
+      self.next_victim = (self.next_victim + 1) % self.frames.len();
+
+      if self.pin_count[index] > 0 {
+        continue;
+      }
+
+      let key = self.page_table.iter().find(|(_, &frame)| frame == index).map(|(key, _)| key.clone());
+
+      if let Some((table_name, page_id)) = key {
+        if self.dirty_pages.remove(&index) {
+          self.storage_manager.write_page(&table_name, &self.frames[index])?;
+        }
+
+        self.page_table.remove(&(table_name, page_id));
+      }
+
+      return Ok(index);
+    }
   }
 
-  fn remove_page(&mut self, page_id: u32) {
-    if let Some(index) = self.page_table.remove(&page_id) {
-      self.frames.remove(index);
+  fn remove_page(&mut self, table_name: &str, page_id: u32) {
+    if let Some(index) = self.page_table.remove(&(table_name.to_string(), page_id)) {
       self.dirty_pages.remove(&index);
-      self.pin_count.remove(index);
+      self.pin_count[index] = 0;
+      self.free_frames.push(index);
     }
   }
 
+  fn page_count(&mut self, table_name: &str) -> io::Result<u32> {
+    if let Some(&count) = self.page_counts.get(table_name) {
+      return Ok(count);
+    }
+
+    let count = self.storage_manager.page_count(table_name)?;
+    self.page_counts.insert(table_name.to_string(), count);
+
+    Ok(count)
+  }
+
   pub fn add_table_to_catalog(&mut self, table: Table) {
     self.catalog.add_table(table);
     self.storage_manager.write_catalog(&self.catalog); // TODO: maybe decide when to write the catalog
@@ -234,28 +360,186 @@ impl BufferPool {
     self.storage_manager.write_catalog(&self.catalog); // TODO: maybe decide when to write the catalog
   }
 
-  // INFO: Do sequential read from disk and deseaialize pages and use data to return query result
-  // INFO: maybe this is useless
-  pub fn get_all_data_for_origin(&mut self, origin_page_id: u32) -> Result<Vec<u8>, &'static str> {
-    let mut data = Vec::new();
-    let mut current_page_id = origin_page_id;
+  pub fn update_table_in_catalog(&mut self, table: Table) {
+    self.catalog.update_table(&table.name.clone(), table);
+    self.storage_manager.write_catalog(&self.catalog); // TODO: maybe decide when to write the catalog
+  }
 
-    loop {
-      let page = match self.get_page(current_page_id) {
-        Some(page) => page,
-        None => return Err("Page not found"),
-      };
+  // INFO: Drops every page and index of the table, both from the buffer pool and from disk
+  // the indexes are found through the catalog so this has to run before the table is removed from it
+  pub fn remove_table_data(&mut self, table_name: &str) -> io::Result<()> {
+    let index_names: Vec<String> = match self.catalog.get_table(table_name) {
+      Some(table) => table.indexes.iter().map(|index| index.name.clone()).collect(),
+      None => Vec::new(),
+    };
+
+    for index_name in index_names {
+      self.remove_index(&index_name)?;
+    }
+
+    let page_count = self.page_count(table_name)?;
+
+    for page_id in 0..page_count {
+      self.remove_page(table_name, page_id);
+    }
 
-      data.extend(page.get_tuples_data().into_iter().flatten());
+    self.page_counts.remove(table_name);
+    self.storage_manager.remove_table_file(table_name)
+  }
+
+  pub fn insert_row(&mut self, table_name: &str, data: Vec<u8>) -> io::Result<RowId> {
+    let page_count = self.page_count(table_name)?;
+
+    if page_count > 0 {
+      let index = self.fetch_page(table_name, page_count - 1)?;
+
+      if let Ok(slot) = self.frames[index].insert_tuple(data.clone()) {
+        self.dirty_pages.insert(index);
+        return Ok(RowId { page: page_count - 1, slot });
+      }
+
+      self.frames[index].header.page_next = page_count as u16;
+      self.dirty_pages.insert(index);
+    }
+
+    let mut page = Page::empty(page_count);
+    let slot = page.insert_tuple(data).map_err(|_| io::Error::other(ROW_TOO_LARGE))?;
+
+    let index = self.add_page(table_name, page)?;
+    self.dirty_pages.insert(index);
+    self.page_counts.insert(table_name.to_string(), page_count + 1);
+
+    Ok(RowId { page: page_count, slot })
+  }
+
+  // INFO: Whether the row fits in an empty page, a row that doesn't can't be stored at all
+  pub fn row_fits(&self, data: &[u8]) -> bool {
+    Page::empty(0).insert_tuple(data.to_vec()).is_ok()
+  }
 
-      if page.header.page_next == 0 {
-        break;
-      } else {
-        current_page_id = page.header.page_next as u32;
+  // INFO: Returns the new location of the row, the row moves to another page when it outgrows its own. The moved row is
+  // written before the old one is deleted so a row that can't be stored anywhere keeps its old values
+  pub fn update_row(&mut self, table_name: &str, row_id: RowId, data: Vec<u8>) -> io::Result<RowId> {
+    let index = self.fetch_page(table_name, row_id.page)?;
+
+    match self.frames[index].update_tuple_by_id(row_id.slot, data.clone()) {
+      Ok(()) => {
+        self.dirty_pages.insert(index);
+        Ok(row_id)
+      }
+      Err(PAGE_FULL) => {
+        let new_row_id = self.insert_row(table_name, data)?;
+        self.delete_row(table_name, row_id)?;
+        Ok(new_row_id)
       }
+      Err(e) => Err(io::Error::other(e)),
     }
+  }
+
+  pub fn delete_row(&mut self, table_name: &str, row_id: RowId) -> io::Result<()> {
+    let index = self.fetch_page(table_name, row_id.page)?;
+
+    self.frames[index].remove_tuple_by_id(row_id.slot).map_err(io::Error::other)?;
+    self.dirty_pages.insert(index);
 
-    Ok(data)
+    Ok(())
+  }
+
+  pub fn read_row(&mut self, table_name: &str, row_id: RowId) -> io::Result<Option<Vec<u8>>> {
+    if row_id.page >= self.page_count(table_name)? {
+      return Ok(None);
+    }
+
+    let page = self.get_page(table_name, row_id.page)?;
+    Ok(page.get_live_tuple(row_id.slot).map(|tuple| tuple.read().to_vec()))
+  }
+
+  fn get_index(&mut self, index_name: &str) -> io::Result<&mut BTreeIndex> {
+    if !self.indexes.contains_key(index_name) {
+      let index = self.storage_manager.read_index(index_name)?;
+      self.indexes.insert(index_name.to_string(), index);
+    }
+
+    Ok(self.indexes.get_mut(index_name).unwrap())
+  }
+
+  // INFO: Pages that are in the buffer pool move with the table, indexes keep their names
+  pub fn rename_table_data(&mut self, table_name: &str, new_name: &str) -> io::Result<()> {
+    for page_id in 0..self.page_count(table_name)? {
+      if let Some(index) = self.page_table.remove(&(table_name.to_string(), page_id)) {
+        self.page_table.insert((new_name.to_string(), page_id), index);
+      }
+    }
+
+    if let Some(count) = self.page_counts.remove(table_name) {
+      self.page_counts.insert(new_name.to_string(), count);
+    }
+
+    self.storage_manager.rename_table_file(table_name, new_name)
+  }
+
+  pub fn remove_index(&mut self, index_name: &str) -> io::Result<()> {
+    self.indexes.remove(index_name);
+    self.dirty_indexes.remove(index_name);
+    self.storage_manager.remove_index_file(index_name)
+  }
+
+  pub fn index_insert(&mut self, index_name: &str, key: Vec<catalog::Value>, row_id: RowId) -> io::Result<()> {
+    self.get_index(index_name)?.insert(key, row_id);
+    self.dirty_indexes.insert(index_name.to_string());
+
+    Ok(())
+  }
+
+  pub fn index_remove(&mut self, index_name: &str, key: &[catalog::Value], row_id: RowId) -> io::Result<()> {
+    self.get_index(index_name)?.remove(key, row_id);
+    self.dirty_indexes.insert(index_name.to_string());
+
+    Ok(())
+  }
+
+  pub fn index_lookup(&mut self, index_name: &str, key: &[catalog::Value]) -> io::Result<Vec<RowId>> {
+    Ok(self.get_index(index_name)?.get(key).to_vec())
+  }
+
+  pub fn index_scan(
+    &mut self,
+    index_name: &str,
+    start: &[catalog::Value],
+    within: impl Fn(&catalog::Value) -> bool,
+  ) -> io::Result<Vec<RowId>> {
+    Ok(self.get_index(index_name)?.scan(start, within))
+  }
+
+  // INFO: Sequential read of every live row in the table
+  pub fn scan_table(&mut self, table_name: &str) -> io::Result<Vec<(RowId, Vec<u8>)>> {
+    let mut rows = Vec::new();
+
+    for page_id in 0..self.page_count(table_name)? {
+      let page = self.get_page(table_name, page_id)?;
+      rows.extend(page.get_live_tuples().into_iter().map(|(slot, data)| (RowId { page: page_id, slot }, data)));
+    }
+
+    Ok(rows)
+  }
+
+  // INFO: Writes every dirty page back to its table file
+  pub fn flush(&mut self) -> io::Result<()> {
+    for ((table_name, _), &index) in self.page_table.iter() {
+      if self.dirty_pages.contains(&index) {
+        self.storage_manager.write_page(table_name, &self.frames[index])?;
+      }
+    }
+
+    self.dirty_pages.clear();
+
+    for index_name in self.dirty_indexes.drain() {
+      if let Some(index) = self.indexes.get(&index_name) {
+        self.storage_manager.write_index(&index_name, index)?;
+      }
+    }
+
+    Ok(())
   }
 }
 
@@ -264,6 +548,12 @@ pub struct StorageManager {
   page_size: usize,
 }
 
+impl Default for StorageManager {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl StorageManager {
   pub fn new() -> Self {
     Self { page_size: PAGE_SIZE }
@@ -288,7 +578,7 @@ impl StorageManager {
 
     let tables = match bincode::deserialize(&buffer) {
       Ok(tables) => tables,
-      Err(e) => return Err(io::Error::new(io::ErrorKind::Other, e.to_string())),
+      Err(e) => return Err(io::Error::other(e.to_string())),
     };
 
     let catalog: Catalog = Catalog::new(tables);
@@ -333,5 +623,82 @@ impl StorageManager {
     let _ = Self::write_file(CATALOG_FILE, &buffer);
   }
 
-  pub fn create_file(&mut self, file_name: &String) {}
+  fn table_file(table_name: &str) -> String {
+    format!("{}.{}", table_name, TABLE_FILE_EXTENSION)
+  }
+
+  // INFO: Table files are made of fixed size pages so the number of pages comes from the file size
+  pub fn page_count(&self, table_name: &str) -> io::Result<u32> {
+    match fs::metadata(Self::table_file(table_name)) {
+      Ok(metadata) => Ok((metadata.len() / self.page_size as u64) as u32),
+      Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+      Err(e) => Err(e),
+    }
+  }
+
+  pub fn read_page(&self, table_name: &str, page_id: u32) -> io::Result<Page> {
+    let mut file = Self::read_file(&Self::table_file(table_name))?;
+    let mut buffer = vec![0; self.page_size];
+
+    file.seek(SeekFrom::Start(page_id as u64 * self.page_size as u64))?;
+    file.read_exact(&mut buffer)?;
+
+    bincode::deserialize(&buffer).map_err(|e| io::Error::other(e.to_string()))
+  }
+
+  pub fn write_page(&self, table_name: &str, page: &Page) -> io::Result<()> {
+    let mut buffer = bincode::serialize(page).map_err(|e| io::Error::other(e.to_string()))?;
+
+    if buffer.len() > self.page_size {
+      return Err(io::Error::other("Page is larger than the page size"));
+    }
+
+    buffer.resize(self.page_size, 0);
+
+    let mut file = OpenOptions::new().write(true).create(true).truncate(false).open(Self::table_file(table_name))?;
+    file.seek(SeekFrom::Start(page.header.id as u64 * self.page_size as u64))?;
+    file.write_all(&buffer)
+  }
+
+  pub fn remove_table_file(&self, table_name: &str) -> io::Result<()> {
+    match fs::remove_file(Self::table_file(table_name)) {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+      _ => Ok(()),
+    }
+  }
+
+  pub fn rename_table_file(&self, table_name: &str, new_name: &str) -> io::Result<()> {
+    match fs::rename(Self::table_file(table_name), Self::table_file(new_name)) {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+      _ => Ok(()),
+    }
+  }
+
+  fn index_file(index_name: &str) -> String {
+    format!("{}.{}", index_name, INDEX_FILE_EXTENSION)
+  }
+
+  // INFO: An index that was never written is empty
+  pub fn read_index(&self, index_name: &str) -> io::Result<BTreeIndex> {
+    let mut file = match Self::read_file(&Self::index_file(index_name)) {
+      Ok(file) => file,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeIndex::new()),
+      Err(e) => return Err(e),
+    };
+
+    let buffer = Self::file_to_buffer(&mut file)?;
+    bincode::deserialize(&buffer).map_err(|e| io::Error::other(e.to_string()))
+  }
+
+  pub fn write_index(&self, index_name: &str, index: &BTreeIndex) -> io::Result<()> {
+    let buffer = bincode::serialize(index).map_err(|e| io::Error::other(e.to_string()))?;
+    Self::write_file(&Self::index_file(index_name), &buffer)
+  }
+
+  pub fn remove_index_file(&self, index_name: &str) -> io::Result<()> {
+    match fs::remove_file(Self::index_file(index_name)) {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+      _ => Ok(()),
+    }
+  }
 }
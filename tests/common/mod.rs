@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use std::sync::{Mutex, MutexGuard};
+
+use db::sql::catalog::Value;
+use db::Database;
+
+static WORKING_DIRECTORY: Mutex<()> = Mutex::new(());
+
+// INFO: The database keeps its files in the working directory, so every test runs in an empty directory of its own and
+// the tests of a binary take turns. The guard has to live as long as the database
+pub fn open(name: &str) -> (MutexGuard<'static, ()>, Database) {
+  let guard = WORKING_DIRECTORY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+  let directory = std::env::temp_dir().join(format!("db-test-{}-{}", name, std::process::id()));
+  let _ = std::fs::remove_dir_all(&directory);
+  std::fs::create_dir_all(&directory).unwrap();
+  std::env::set_current_dir(&directory).unwrap();
+
+  (guard, Database::new())
+}
+
+// INFO: Rows returned by the last statement of the script
+pub fn query(database: &mut Database, script: &str) -> Vec<Vec<Value>> {
+  let results = database.execute(script).unwrap_or_else(|error| panic!("{} failed: {}", script, error));
+  results.into_iter().last().map(|result| result.rows).unwrap_or_default()
+}
+
+pub fn error(database: &mut Database, script: &str) -> String {
+  match database.execute(script) {
+    Ok(_) => panic!("{} should have failed", script),
+    Err(error) => error.to_string(),
+  }
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use db::sql::catalog::Value;
+
+const SETUP: &str = "CREATE TABLE p (id INT PRIMARY KEY, name TEXT NOT NULL DEFAULT 'anon', age INT DEFAULT 18 CHECK (age >= 0), email TEXT UNIQUE);
+  INSERT INTO p (id) VALUES (1), (2), (3);";
+
+fn ids(database: &mut db::Database) -> Vec<Value> {
+  common::query(database, "SELECT id FROM p ORDER BY id;").into_iter().map(|row| row[0].clone()).collect()
+}
+
+#[test]
+fn defaults_fill_missing_columns() {
+  let (_guard, mut database) = common::open("constraints-defaults");
+  database.execute(SETUP).unwrap();
+
+  let rows = common::query(&mut database, "SELECT name, age, email FROM p WHERE id = 1;");
+  assert_eq!(rows, vec![vec![Value::Text("anon".to_string()), Value::Int(18), Value::Null]]);
+}
+
+#[test]
+fn not_null_and_check_reject_rows() {
+  let (_guard, mut database) = common::open("constraints-checks");
+  database.execute(SETUP).unwrap();
+
+  assert_eq!(
+    common::error(&mut database, "INSERT INTO p (id, name) VALUES (4, NULL);"),
+    "Execution Error: NULL value in column 'name' of table 'p' violates the NOT NULL constraint."
+  );
+  assert_eq!(
+    common::error(&mut database, "INSERT INTO p (id, age) VALUES (4, -1);"),
+    "Execution Error: Value -1 of column 'age' violates check constraint 'p_age_check'."
+  );
+  assert_eq!(
+    common::error(&mut database, "UPDATE p SET age = -5 WHERE id = 1;"),
+    "Execution Error: Value -5 of column 'age' violates check constraint 'p_age_check'."
+  );
+
+  // INFO: a NULL passes a CHECK like in PostgreSQL
+  database.execute("INSERT INTO p (id, age) VALUES (4, NULL);").unwrap();
+  assert_eq!(ids(&mut database), vec![Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(4)]);
+}
+
+#[test]
+fn unique_keys_reject_duplicates() {
+  let (_guard, mut database) = common::open("constraints-unique");
+  database.execute(SETUP).unwrap();
+
+  assert_eq!(
+    common::error(&mut database, "INSERT INTO p (id, email) VALUES (5, 'a'), (6, 'a');"),
+    "Execution Error: Key (email) = (a) violates unique constraint 'p_email_key'."
+  );
+  assert_eq!(
+    common::error(&mut database, "INSERT INTO p (id) VALUES (1);"),
+    "Execution Error: Key (id) = (1) violates unique constraint 'p_pkey'."
+  );
+  assert_eq!(ids(&mut database), vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+
+  // INFO: NULLs are distinct from each other and keys are checked once the whole statement ran
+  database.execute("INSERT INTO p (id, email) VALUES (5, NULL), (6, NULL);").unwrap();
+  database.execute("UPDATE p SET id = id + 1;").unwrap();
+  assert_eq!(ids(&mut database), vec![Value::Int(2), Value::Int(3), Value::Int(4), Value::Int(6), Value::Int(7)]);
+
+  assert_eq!(
+    common::error(&mut database, "UPDATE p SET id = 3 WHERE id = 4;"),
+    "Execution Error: Key (id) = (3) violates unique constraint 'p_pkey'."
+  );
+}
+
+#[test]
+fn upserts_are_checked() {
+  let (_guard, mut database) = common::open("constraints-upsert");
+  database.execute(SETUP).unwrap();
+  database.execute("INSERT INTO p (id, email) VALUES (9, 'z');").unwrap();
+
+  assert_eq!(
+    common::error(
+      &mut database,
+      "INSERT INTO p (id, email) VALUES (2, 'z') ON CONFLICT (id) DO UPDATE SET email = excluded.email;"
+    ),
+    "Execution Error: Key (email) = (z) violates unique constraint 'p_email_key'."
+  );
+  assert_eq!(
+    common::error(&mut database, "INSERT INTO p (id, age) VALUES (2, 1) ON CONFLICT (id) DO UPDATE SET age = -1;"),
+    "Execution Error: Value -1 of column 'age' violates check constraint 'p_age_check'."
+  );
+
+  database.execute("INSERT INTO p (id, email) VALUES (9, 'y') ON CONFLICT (id) DO NOTHING;").unwrap();
+  let rows = common::query(&mut database, "SELECT email FROM p WHERE id = 9;");
+  assert_eq!(rows, vec![vec![Value::Text("z".to_string())]]);
+}
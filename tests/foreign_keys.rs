@@ -0,0 +1,101 @@
+mod common;
+
+use db::sql::catalog::Value;
+
+const SETUP: &str = "CREATE TABLE parent (id INT PRIMARY KEY, name TEXT);
+  CREATE TABLE child (id INT PRIMARY KEY, pid INT REFERENCES parent (id) ON DELETE CASCADE ON UPDATE CASCADE);
+  CREATE TABLE nulled (id INT PRIMARY KEY, pid INT REFERENCES parent (id) ON DELETE SET NULL ON UPDATE SET NULL);
+  CREATE TABLE strict (id INT PRIMARY KEY, pid INT FOREIGN KEY (pid) REFERENCES parent (id) ON DELETE RESTRICT);
+  INSERT INTO parent (id, name) VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd');
+  INSERT INTO child (id, pid) VALUES (10, 1), (11, 2), (12, NULL);
+  INSERT INTO nulled (id, pid) VALUES (20, 1), (21, 3);
+  INSERT INTO strict (id, pid) VALUES (30, 4);";
+
+fn rows(database: &mut db::Database, table: &str) -> Vec<(i64, Option<i64>)> {
+  let rows = common::query(database, &format!("SELECT id, pid FROM {} ORDER BY id;", table));
+
+  rows
+    .into_iter()
+    .map(|row| match row.as_slice() {
+      [Value::Int(id), Value::Int(pid)] => (*id, Some(*pid)),
+      [Value::Int(id), Value::Null] => (*id, None),
+      row => panic!("unexpected row {:?}", row),
+    })
+    .collect()
+}
+
+#[test]
+fn referencing_rows_need_a_parent() {
+  let (_guard, mut database) = common::open("foreign-keys-insert");
+  database.execute(SETUP).unwrap();
+
+  assert_eq!(
+    common::error(&mut database, "INSERT INTO child (id, pid) VALUES (13, 99);"),
+    "Execution Error: Key (pid) = (99) is not present in table 'parent', it violates foreign key constraint 'child_pid_fkey'."
+  );
+  assert_eq!(
+    common::error(&mut database, "UPDATE child SET pid = 98 WHERE id = 10;"),
+    "Execution Error: Key (pid) = (98) is not present in table 'parent', it violates foreign key constraint 'child_pid_fkey'."
+  );
+  assert_eq!(rows(&mut database, "child"), vec![(10, Some(1)), (11, Some(2)), (12, None)]);
+}
+
+#[test]
+fn restrict_keeps_referenced_rows() {
+  let (_guard, mut database) = common::open("foreign-keys-restrict");
+  database.execute(SETUP).unwrap();
+
+  let referenced =
+    "Execution Error: Key (id) = (4) of table 'parent' is still referenced from table 'strict' by foreign key constraint 'strict_pid_fkey'.";
+  assert_eq!(common::error(&mut database, "DELETE FROM parent WHERE id = 4;"), referenced);
+  assert_eq!(common::error(&mut database, "UPDATE parent SET id = 40 WHERE id = 4;"), referenced);
+
+  let count = common::query(&mut database, "SELECT COUNT(*) FROM parent WHERE id = 4;");
+  assert_eq!(count, vec![vec![Value::Int(1)]]);
+}
+
+#[test]
+fn deletes_cascade_or_set_null() {
+  let (_guard, mut database) = common::open("foreign-keys-delete");
+  database.execute(SETUP).unwrap();
+
+  database.execute("DELETE FROM parent WHERE id = 1;").unwrap();
+  assert_eq!(rows(&mut database, "child"), vec![(11, Some(2)), (12, None)]);
+  assert_eq!(rows(&mut database, "nulled"), vec![(20, None), (21, Some(3))]);
+}
+
+#[test]
+fn updates_cascade_or_set_null() {
+  let (_guard, mut database) = common::open("foreign-keys-update");
+  database.execute(SETUP).unwrap();
+
+  database.execute("UPDATE parent SET id = 20 WHERE id = 2;").unwrap();
+  database.execute("UPDATE parent SET id = 30 WHERE id = 3;").unwrap();
+  assert_eq!(rows(&mut database, "child"), vec![(10, Some(1)), (11, Some(20)), (12, None)]);
+  assert_eq!(rows(&mut database, "nulled"), vec![(20, Some(1)), (21, None)]);
+}
+
+#[test]
+fn cascades_follow_self_references() {
+  let (_guard, mut database) = common::open("foreign-keys-tree");
+  database.execute("CREATE TABLE tree (id INT PRIMARY KEY, pid INT REFERENCES tree (id) ON DELETE CASCADE);").unwrap();
+  database.execute("INSERT INTO tree (id, pid) VALUES (1, NULL), (2, 1), (3, 2), (4, 5), (5, 1), (6, NULL);").unwrap();
+
+  database.execute("DELETE FROM tree WHERE id = 1;").unwrap();
+  assert_eq!(rows(&mut database, "tree"), vec![(6, None)]);
+}
+
+#[test]
+fn referenced_tables_cant_be_dropped() {
+  let (_guard, mut database) = common::open("foreign-keys-drop");
+  database.execute(SETUP).unwrap();
+
+  assert_eq!(
+    common::error(&mut database, "DROP TABLE parent;"),
+    "Planning Error: Table 'parent' can't be dropped because table 'child' references it."
+  );
+  assert_eq!(
+    common::error(&mut database, "CREATE TABLE bad (id INT NOT NULL REFERENCES parent (id) ON DELETE SET NULL);"),
+    "Planning Error: Constraint 'bad_id_fkey' is invalid, SET NULL can't be used on NOT NULL column 'id'."
+  );
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use db::sql::catalog::{DataType, Value};
+use db::sql::engine::error::ExecutionError;
+use db::sql::function::{AggregateUdf, ScalarUdf};
+
+struct Double;
+
+impl ScalarUdf for Double {
+  fn name(&self) -> &str {
+    "double_it"
+  }
+
+  fn arguments(&self) -> Vec<DataType> {
+    vec![DataType::BigInt]
+  }
+
+  fn return_type(&self) -> DataType {
+    DataType::BigInt
+  }
+
+  fn invoke(&self, args: &[Value]) -> Result<Value, ExecutionError> {
+    match &args[0] {
+      Value::Int(i) => i.checked_mul(2).map(Value::Int).ok_or(ExecutionError::NumericOverflow),
+      value => Ok(value.clone()),
+    }
+  }
+}
+
+struct Product;
+
+impl AggregateUdf for Product {
+  type State = i64;
+
+  fn name(&self) -> &str {
+    "product"
+  }
+
+  fn arguments(&self) -> Vec<DataType> {
+    vec![DataType::BigInt]
+  }
+
+  fn return_type(&self) -> DataType {
+    DataType::BigInt
+  }
+
+  fn init(&self) -> i64 {
+    1
+  }
+
+  fn accumulate(&self, state: &mut i64, args: &[Value]) -> Result<(), ExecutionError> {
+    if let Value::Int(i) = args[0] {
+      *state = state.checked_mul(i).ok_or(ExecutionError::NumericOverflow)?;
+    }
+
+    Ok(())
+  }
+
+  fn merge(&self, state: &mut i64, other: i64) -> Result<(), ExecutionError> {
+    *state = state.checked_mul(other).ok_or(ExecutionError::NumericOverflow)?;
+    Ok(())
+  }
+
+  fn finalize(&self, state: i64) -> Result<Value, ExecutionError> {
+    Ok(Value::Int(state))
+  }
+}
+
+const SETUP: &str = "CREATE TABLE t (id INT PRIMARY KEY, g INT, n BIGINT);
+  INSERT INTO t VALUES (1, 1, 2), (2, 1, 3), (3, 2, 4), (4, 2, NULL);";
+
+#[test]
+fn registered_functions_run_in_statements() {
+  let (_guard, mut database) = common::open("udf-statements");
+  database.register_scalar(Double).unwrap();
+  database.register_aggregate(Product).unwrap();
+  database.execute(SETUP).unwrap();
+
+  let rows = common::query(&mut database, "SELECT id, double_it(n) FROM t WHERE double_it(id) > 4 ORDER BY id;");
+  assert_eq!(rows, vec![vec![Value::Int(3), Value::Int(8)], vec![Value::Int(4), Value::Null]]);
+
+  let rows = common::query(&mut database, "SELECT g, PRODUCT(n), double_it(COUNT(n)) FROM t GROUP BY g ORDER BY g;");
+  assert_eq!(rows, vec![vec![Value::Int(1), Value::Int(6), Value::Int(4)], vec![Value::Int(2), Value::Int(4), Value::Int(2)]]);
+
+  let rows = common::query(&mut database, "SELECT id, product(n) OVER (ORDER BY id) FROM t ORDER BY id;");
+  let products: Vec<Value> = rows.into_iter().map(|row| row[1].clone()).collect();
+  assert_eq!(products, vec![Value::Int(2), Value::Int(6), Value::Int(24), Value::Int(24)]);
+}
+
+#[test]
+fn registered_names_are_unique() {
+  let (_guard, mut database) = common::open("udf-names");
+  database.register_scalar(Double).unwrap();
+  database.register_aggregate(Product).unwrap();
+
+  assert!(database.register_scalar(Double).is_err());
+  assert!(database.register_aggregate(Product).is_err());
+
+  struct Shadow(&'static str);
+
+  impl ScalarUdf for Shadow {
+    fn name(&self) -> &str {
+      self.0
+    }
+
+    fn arguments(&self) -> Vec<DataType> {
+      Vec::new()
+    }
+
+    fn return_type(&self) -> DataType {
+      DataType::Int
+    }
+
+    fn invoke(&self, _: &[Value]) -> Result<Value, ExecutionError> {
+      Ok(Value::Int(0))
+    }
+  }
+
+  for name in ["upper", "SUM", "product", "rank"] {
+    let error = database.register_scalar(Shadow(name)).unwrap_err();
+    assert_eq!(error.to_string(), format!("Planning Error: Function '{}' already exists.", name));
+  }
+}